@@ -0,0 +1,3 @@
+-- Cc and Bcc recipients for scheduled emails
+ALTER TABLE scheduled_emails ADD COLUMN cc TEXT DEFAULT '';
+ALTER TABLE scheduled_emails ADD COLUMN bcc TEXT DEFAULT '';
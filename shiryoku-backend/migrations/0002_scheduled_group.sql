@@ -0,0 +1,3 @@
+-- Recipient groups and tracking IDs for scheduled emails
+ALTER TABLE scheduled_emails ADD COLUMN recipient_group TEXT DEFAULT '';
+ALTER TABLE scheduled_emails ADD COLUMN tracking_id TEXT DEFAULT '';
@@ -0,0 +1,2 @@
+-- Sender profile name for scheduled emails
+ALTER TABLE scheduled_emails ADD COLUMN sender_profile TEXT DEFAULT '';
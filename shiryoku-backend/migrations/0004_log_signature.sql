@@ -0,0 +1,2 @@
+-- Pixel URL signatures for open logs
+ALTER TABLE logs ADD COLUMN signature TEXT DEFAULT '';
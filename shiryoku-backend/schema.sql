@@ -7,13 +7,16 @@ CREATE TABLE logs (
     country TEXT,
     city TEXT,
     user_agent TEXT,
-    timezone TEXT
+    timezone TEXT,
+    signature TEXT DEFAULT ''
 );
 
 DROP TABLE IF EXISTS scheduled_emails;
 CREATE TABLE scheduled_emails (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     recipient TEXT NOT NULL,
+    cc TEXT DEFAULT '',
+    bcc TEXT DEFAULT '',
     subject TEXT NOT NULL,
     html_body TEXT NOT NULL,
     plain_body TEXT NOT NULL,
@@ -21,6 +24,9 @@ CREATE TABLE scheduled_emails (
     smtp_username TEXT NOT NULL,
     smtp_password TEXT NOT NULL,
     sender_name TEXT,
+    recipient_group TEXT DEFAULT '',
+    tracking_id TEXT DEFAULT '',
+    sender_profile TEXT DEFAULT '',
     status TEXT DEFAULT 'pending',
     created_at TEXT DEFAULT (datetime('now'))
 );
@@ -12,7 +12,14 @@ export default {
     // --- PIXEL TRACKING (No Auth) ---
     if (url.pathname === '/pixel.png') {
       const id = url.searchParams.get('id') || 'unknown';
-      const recentLog = await env.DB.prepare(`SELECT timestamp FROM logs WHERE tracking_id = ? ORDER BY id DESC LIMIT 1`).bind(id).first();
+      const signature = url.searchParams.get('sig') || '';
+      // The worker cannot check signatures, so repeat hits are keyed on the id and signature
+      // together. A forged hit then never hides a genuine open that follows it.
+      const recentLog = await env.DB.prepare(
+        `SELECT timestamp FROM logs WHERE tracking_id = ? AND COALESCE(signature, '') = ? ORDER BY id DESC LIMIT 1`,
+      )
+        .bind(id, signature)
+        .first();
 
       let shouldLog = true;
       if (recentLog && recentLog.timestamp) {
@@ -31,11 +38,11 @@ export default {
         ctx.waitUntil(
           env.DB.prepare(
             `
-            INSERT INTO logs (tracking_id, timestamp, ip, country, city, user_agent, timezone)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO logs (tracking_id, timestamp, ip, country, city, user_agent, timezone, signature)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
           `,
           )
-            .bind(id, timestamp, ip, country, city, userAgent, timezone)
+            .bind(id, timestamp, ip, country, city, userAgent, timezone, signature)
             .run(),
         );
       }
@@ -119,12 +126,17 @@ export default {
         jobs.push({
           id: String(email.id),
           recipient: email.recipient,
+          cc: email.cc || '',
+          bcc: email.bcc || '',
           subject: email.subject,
           body: email.plain_body, // Prefer plain text for the simple TUI viewer
           scheduled_at: email.scheduled_at,
           recipient_timezone: 'UTC', // DB Schema limitation, defaulting to UTC
           status: statusMap[email.status as string] || 'Pending',
           attachments: attachments,
+          group: email.recipient_group || '',
+          tracking_id: email.tracking_id || '',
+          profile: email.sender_profile || '',
         });
       }
 
@@ -136,6 +148,8 @@ export default {
       try {
         const formData = await request.formData();
         const recipient = formData.get('recipient') as string;
+        const cc = (formData.get('cc') as string) || '';
+        const bcc = (formData.get('bcc') as string) || '';
         const subject = formData.get('subject') as string;
         const html_body = formData.get('html_body') as string;
         const plain_body = formData.get('plain_body') as string;
@@ -143,17 +157,34 @@ export default {
         const smtp_username = formData.get('smtp_username') as string;
         const smtp_password = formData.get('smtp_password') as string;
         const sender_name = formData.get('sender_name') as string;
+        const recipient_group = (formData.get('recipient_group') as string) || '';
+        const tracking_id = (formData.get('tracking_id') as string) || '';
+        const sender_profile = (formData.get('sender_profile') as string) || '';
 
-        if (!recipient || !scheduled_at) return new Response('Missing fields', { status: 400 });
+        if ((!recipient && !cc && !bcc) || !scheduled_at) return new Response('Missing fields', { status: 400 });
 
         const { results } = await env.DB.prepare(
           `
-            INSERT INTO scheduled_emails (recipient, subject, html_body, plain_body, scheduled_at, smtp_username, smtp_password, sender_name)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO scheduled_emails (recipient, cc, bcc, subject, html_body, plain_body, scheduled_at, smtp_username, smtp_password, sender_name, recipient_group, tracking_id, sender_profile)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             RETURNING id
         `,
         )
-          .bind(recipient, subject, html_body, plain_body, scheduled_at, smtp_username, smtp_password, sender_name)
+          .bind(
+            recipient || '',
+            cc,
+            bcc,
+            subject,
+            html_body,
+            plain_body,
+            scheduled_at,
+            smtp_username,
+            smtp_password,
+            sender_name,
+            recipient_group,
+            tracking_id,
+            sender_profile,
+          )
           .run();
 
         const emailId = results[0].id;
@@ -278,8 +309,11 @@ async function sendSmtpEmail(email: any, attachments: any[]) {
   // Envelope
   await writeCmd(`MAIL FROM: <${email.smtp_username}>`);
   await readUntilCode('250');
-  await writeCmd(`RCPT TO: <${email.recipient}>`);
-  await readUntilCode('250');
+  const envelope = [email.recipient, email.cc, email.bcc].flatMap((list) => extractAddresses(list));
+  for (const address of envelope) {
+    await writeCmd(`RCPT TO: <${address}>`);
+    await readUntilCode('250');
+  }
   await writeCmd('DATA');
   await readUntilCode('354');
 
@@ -303,6 +337,50 @@ async function sendSmtpEmail(email: any, attachments: any[]) {
   await writer.close();
 }
 
+// Pulls bare addresses out of a header list such as `"Doe, Jane" <jane@x.org>, bob@y.org`.
+// Separators and `@` inside quoted display names are not part of any address.
+export function extractAddresses(list: string | null): string[] {
+  if (!list) return [];
+  const addresses: string[] = [];
+  let name = '';
+  let angle = '';
+  let hasAngle = false;
+  let inQuotes = false;
+  let inAngle = false;
+  let escaped = false;
+
+  const finish = () => {
+    const address = (hasAngle ? angle : name).trim();
+    if (address) addresses.push(address);
+    name = '';
+    angle = '';
+    hasAngle = false;
+  };
+
+  for (const c of list) {
+    if (inQuotes) {
+      if (escaped) escaped = false;
+      else if (c === '\\') escaped = true;
+      else if (c === '"') inQuotes = false;
+    } else if (inAngle) {
+      if (c === '>') inAngle = false;
+      else angle += c;
+    } else if (c === '"') {
+      inQuotes = true;
+    } else if (c === '<') {
+      inAngle = true;
+      hasAngle = true;
+      angle = '';
+    } else if (c === ',' || c === ';') {
+      finish();
+    } else {
+      name += c;
+    }
+  }
+  finish();
+  return addresses;
+}
+
 function buildMimeMessage(email: any, attachments: any[], boundary: string): string {
   const crlf = '\r\n';
 
@@ -317,7 +395,8 @@ function buildMimeMessage(email: any, attachments: any[], boundary: string): str
     fromHeader = `"${email.sender_name}" <${email.smtp_username}>`;
   }
   msg += `From: ${fromHeader}${crlf}`;
-  msg += `To: ${email.recipient}${crlf}`;
+  if (email.recipient) msg += `To: ${email.recipient}${crlf}`;
+  if (email.cc) msg += `Cc: ${email.cc}${crlf}`;
   msg += `Subject: ${email.subject}${crlf}`;
   msg += `MIME-Version: 1.0${crlf}`;
   msg += `Content-Type: multipart/mixed; boundary="${boundary}"${crlf}${crlf}`;
@@ -1,6 +1,6 @@
 import { env, createExecutionContext, waitOnExecutionContext, SELF } from 'cloudflare:test';
 import { describe, it, expect } from 'vitest';
-import worker from '../src/index';
+import worker, { extractAddresses } from '../src/index';
 
 // For now, you'll need to do something like this to get a correctly-typed
 // `Request` to pass to `worker.fetch()`.
@@ -22,3 +22,19 @@ describe('Hello World worker', () => {
 		expect(await response.text()).toMatchInlineSnapshot(`"Hello World!"`);
 	});
 });
+
+describe('extractAddresses', () => {
+	it('ignores @ and separators inside quoted display names', () => {
+		expect(extractAddresses('"bob@home" <bob@work.org>')).toEqual(['bob@work.org']);
+		expect(extractAddresses('"Doe, \\"J\\"; <x@y>" <jane@x.org>, ann@y.org; Cat <cat@z.org>')).toEqual([
+			'jane@x.org',
+			'ann@y.org',
+			'cat@z.org',
+		]);
+	});
+
+	it('skips empty entries', () => {
+		expect(extractAddresses(null)).toEqual([]);
+		expect(extractAddresses(' , ;')).toEqual([]);
+	});
+});
@@ -1,43 +1,259 @@
+use crate::attachments::{self, FileDiagnostic};
+use crate::compiler;
+use crate::config::AppConfig;
+use crate::contacts::{self, AddressSuggestion};
 use crate::enums::ComposeField;
-use crate::models::EmailDraft;
+use crate::models::{
+  ConsentRegistry, ContactBook, EmailDraft, format_variables, parse_variables, split_address_list,
+};
+use crate::size::SizeEstimate;
+use anyhow::Result;
+use ratatui::widgets::TableState;
 use std::path::PathBuf;
 
 pub struct ComposeState {
   pub field: ComposeField,
   pub draft: EmailDraft,
+  pub to_input: String,
+  pub cc_input: String,
+  pub bcc_input: String,
+  pub variables_input: String,
+  /// Path being typed into the attachment list, added on Enter.
   pub attachment_input: String,
+  pub attachment_state: TableState,
+  pub preview: bool,
+  pub preview_scroll: u16,
+  pub suggestions: Vec<AddressSuggestion>,
+  pub suggestion_idx: usize,
+  /// Set by `refresh_checks` whenever the draft changes, so drawing never compiles it.
+  pub size: SizeEstimate,
+  pub diagnostics: Vec<FileDiagnostic>,
 }
 
 impl ComposeState {
   pub fn new(draft: EmailDraft) -> Self {
-    let attachment_input = draft
-      .attachments
-      .iter()
-      .map(|p| p.to_string_lossy().to_string())
-      .collect::<Vec<_>>()
-      .join("; ");
+    let mut attachment_state = TableState::default();
+    if !draft.attachments.is_empty() {
+      attachment_state.select(Some(0));
+    }
 
     Self {
       field: ComposeField::Recipient,
+      to_input: draft.to.join(", "),
+      cc_input: draft.cc.join(", "),
+      bcc_input: draft.bcc.join(", "),
+      variables_input: format_variables(&draft.variables),
       draft,
-      attachment_input,
+      attachment_input: String::new(),
+      attachment_state,
+      preview: false,
+      preview_scroll: 0,
+      suggestions: Vec::new(),
+      suggestion_idx: 0,
+      size: SizeEstimate::default(),
+      diagnostics: Vec::new(),
+    }
+  }
+
+  /// Recomputes the size estimate and file problems shown on the Compose page.
+  pub fn refresh_checks(
+    &mut self,
+    config: &AppConfig,
+    consent: &ConsentRegistry,
+    book: &ContactBook,
+  ) {
+    self.diagnostics = compiler::file_diagnostics(&self.draft);
+    self.size = SizeEstimate::for_draft(&self.draft, config, consent, book);
+  }
+
+  fn address_input_mut(&mut self) -> Option<&mut String> {
+    match self.field {
+      ComposeField::Recipient => Some(&mut self.to_input),
+      ComposeField::Cc => Some(&mut self.cc_input),
+      ComposeField::Bcc => Some(&mut self.bcc_input),
+      _ => None,
+    }
+  }
+
+  /// Refreshes the autocomplete dropdown from the address currently being typed.
+  pub fn update_suggestions(&mut self, book: &ContactBook) {
+    let query = match self.field {
+      ComposeField::Recipient => contacts::last_token(&self.to_input),
+      ComposeField::Cc => contacts::last_token(&self.cc_input),
+      ComposeField::Bcc => contacts::last_token(&self.bcc_input),
+      _ => "",
+    };
+    self.suggestions = contacts::suggest(book, query, 8);
+    self.suggestion_idx = 0;
+  }
+
+  pub fn clear_suggestions(&mut self) {
+    self.suggestions.clear();
+    self.suggestion_idx = 0;
+  }
+
+  pub fn next_suggestion(&mut self) {
+    if !self.suggestions.is_empty() {
+      self.suggestion_idx = (self.suggestion_idx + 1) % self.suggestions.len();
+    }
+  }
+
+  pub fn previous_suggestion(&mut self) {
+    if !self.suggestions.is_empty() {
+      self.suggestion_idx =
+        (self.suggestion_idx + self.suggestions.len() - 1) % self.suggestions.len();
+    }
+  }
+
+  /// Replaces the address being typed with the selected suggestion.
+  pub fn accept_suggestion(&mut self) {
+    let Some(value) = self
+      .suggestions
+      .get(self.suggestion_idx)
+      .map(|s| s.value.clone())
+    else {
+      return;
+    };
+    let Some(input) = self.address_input_mut() else {
+      return;
+    };
+
+    let token_len = contacts::last_token(input).len();
+    input.truncate(input.len() - token_len);
+    let mut addresses = split_address_list(input);
+    addresses.push(value);
+    *input = format!("{}, ", addresses.join(", "));
+
+    self.clear_suggestions();
+  }
+
+  pub fn toggle_preview(&mut self) {
+    self.sync_inputs();
+    self.preview = !self.preview;
+    self.preview_scroll = 0;
+  }
+
+  pub fn sync_inputs(&mut self) {
+    self.sync_recipients();
+    self.sync_variables();
+  }
+
+  pub fn sync_variables(&mut self) {
+    self.draft.variables = parse_variables(&self.variables_input);
+  }
+
+  pub fn sync_recipients(&mut self) {
+    self.draft.to = split_address_list(&self.to_input);
+    self.draft.cc = split_address_list(&self.cc_input);
+    self.draft.bcc = split_address_list(&self.bcc_input);
+  }
+
+  /// Adds the typed path to the attachment list.
+  pub fn submit_attachment_input(&mut self) -> Result<()> {
+    if self.attachment_input.trim().is_empty() {
+      return Ok(());
+    }
+    let path = attachments::normalize_path(&self.attachment_input);
+    if !path.is_file() {
+      anyhow::bail!("File not found: {}", path.display());
     }
+    self.attachment_input.clear();
+    self.add_attachments(vec![path]);
+    Ok(())
   }
 
-  pub fn sync_attachments(&mut self) {
-    self.draft.attachments = self
-      .attachment_input
-      .split(';')
-      .map(|s| s.trim())
-      .filter(|s| !s.is_empty())
-      .map(PathBuf::from)
-      .collect();
+  /// Appends files that are not attached yet and selects the last one. Returns how many were new.
+  pub fn add_attachments(&mut self, paths: Vec<PathBuf>) -> usize {
+    let before = self.draft.attachments.len();
+    for path in paths {
+      if !self.draft.attachments.contains(&path) {
+        self.draft.attachments.push(path);
+      }
+    }
+    let added = self.draft.attachments.len() - before;
+    if added > 0 {
+      self
+        .attachment_state
+        .select(Some(self.draft.attachments.len() - 1));
+    }
+    added
+  }
+
+  pub fn remove_selected_attachment(&mut self) -> Option<PathBuf> {
+    let idx = self.attachment_state.selected()?;
+    if idx >= self.draft.attachments.len() {
+      return None;
+    }
+    let removed = self.draft.attachments.remove(idx);
+    let len = self.draft.attachments.len();
+    self
+      .attachment_state
+      .select((len > 0).then(|| idx.min(len - 1)));
+    Some(removed)
+  }
+
+  pub fn clear_attachments(&mut self) {
+    self.draft.attachments.clear();
+    self.attachment_state.select(None);
+  }
+
+  /// Marks every file that currently cannot be read as one to send without.
+  /// Returns how many problems were waived.
+  pub fn waive_file_problems(&mut self) -> usize {
+    let mut waived = 0;
+    for diagnostic in compiler::file_diagnostics(&self.draft) {
+      if !diagnostic.waived && !self.draft.waived_files.contains(&diagnostic.path) {
+        self.draft.waived_files.push(diagnostic.path);
+        waived += 1;
+      }
+    }
+    waived
+  }
+
+  pub fn clear_waivers(&mut self) {
+    self.draft.waived_files.clear();
+  }
+
+  pub fn select_attachment(&mut self, forward: bool) {
+    let len = self.draft.attachments.len();
+    if len == 0 {
+      return;
+    }
+    let i = match (self.attachment_state.selected(), forward) {
+      (Some(i), true) => (i + 1) % len,
+      (Some(i), false) => (i + len - 1) % len,
+      (None, _) => 0,
+    };
+    self.attachment_state.select(Some(i));
+  }
+
+  /// Moves the selected attachment one place up or down, keeping it selected.
+  pub fn move_selected_attachment(&mut self, down: bool) {
+    let Some(idx) = self.attachment_state.selected() else {
+      return;
+    };
+    let target = if down {
+      idx + 1
+    } else {
+      match idx.checked_sub(1) {
+        Some(target) => target,
+        None => return,
+      }
+    };
+    if target < self.draft.attachments.len() {
+      self.draft.attachments.swap(idx, target);
+      self.attachment_state.select(Some(target));
+    }
   }
 
   pub fn handle_input(&mut self, c: char) {
     match self.field {
-      ComposeField::Recipient => self.draft.recipient.push(c),
+      ComposeField::From => {}
+      ComposeField::Recipient => self.to_input.push(c),
+      ComposeField::Cc => self.cc_input.push(c),
+      ComposeField::Bcc => self.bcc_input.push(c),
       ComposeField::Subject => self.draft.subject.push(c),
+      ComposeField::Variables => self.variables_input.push(c),
       ComposeField::Attachments => self.attachment_input.push(c),
       ComposeField::Body => {}
       ComposeField::SendButton => {}
@@ -46,12 +262,22 @@ impl ComposeState {
 
   pub fn handle_backspace(&mut self) {
     match self.field {
+      ComposeField::From => {}
       ComposeField::Recipient => {
-        self.draft.recipient.pop();
+        self.to_input.pop();
+      }
+      ComposeField::Cc => {
+        self.cc_input.pop();
+      }
+      ComposeField::Bcc => {
+        self.bcc_input.pop();
       }
       ComposeField::Subject => {
         self.draft.subject.pop();
       }
+      ComposeField::Variables => {
+        self.variables_input.pop();
+      }
       ComposeField::Attachments => {
         self.attachment_input.pop();
       }
@@ -63,17 +289,25 @@ impl ComposeState {
   pub fn cycle_field(&mut self, forward: bool) {
     if forward {
       self.field = match self.field {
-        ComposeField::Recipient => ComposeField::Subject,
-        ComposeField::Subject => ComposeField::Attachments,
+        ComposeField::From => ComposeField::Recipient,
+        ComposeField::Recipient => ComposeField::Cc,
+        ComposeField::Cc => ComposeField::Bcc,
+        ComposeField::Bcc => ComposeField::Subject,
+        ComposeField::Subject => ComposeField::Variables,
+        ComposeField::Variables => ComposeField::Attachments,
         ComposeField::Attachments => ComposeField::Body,
         ComposeField::Body => ComposeField::SendButton,
-        ComposeField::SendButton => ComposeField::Recipient,
+        ComposeField::SendButton => ComposeField::From,
       };
     } else {
       self.field = match self.field {
-        ComposeField::Recipient => ComposeField::SendButton,
-        ComposeField::Subject => ComposeField::Recipient,
-        ComposeField::Attachments => ComposeField::Subject,
+        ComposeField::From => ComposeField::SendButton,
+        ComposeField::Recipient => ComposeField::From,
+        ComposeField::Cc => ComposeField::Recipient,
+        ComposeField::Bcc => ComposeField::Cc,
+        ComposeField::Subject => ComposeField::Bcc,
+        ComposeField::Variables => ComposeField::Subject,
+        ComposeField::Attachments => ComposeField::Variables,
         ComposeField::Body => ComposeField::Attachments,
         ComposeField::SendButton => ComposeField::Body,
       };
@@ -1,61 +1,186 @@
-use crate::config::AppConfig;
-use crate::enums::ConfigField;
+use crate::config::{AppConfig, SenderProfile};
+use crate::enums::{ConfigField, FilePickerMode, SmtpSecurity};
+use crate::signature;
+use crate::storage::Storage;
+use anyhow::Result;
 
 pub struct ConfigState {
   pub field: ConfigField,
   pub data: AppConfig,
+  /// Index of the sender profile the identity and SMTP fields edit.
+  pub profile: usize,
+  /// SMTP port of the selected profile as typed. It is checked and copied into the
+  /// profile by `commit_smtp_port`.
+  pub smtp_port_input: String,
+  /// Set when config.json could not be read; saving is refused so it is not replaced by
+  /// the defaults shown instead.
+  pub load_error: Option<String>,
 }
 
 impl ConfigState {
-  pub fn new(data: AppConfig) -> Self {
+  pub fn new(mut data: AppConfig) -> Self {
+    if data.profiles.is_empty() {
+      data.profiles.push(SenderProfile::default());
+    }
     Self {
-      field: ConfigField::Name,
+      field: ConfigField::Profile,
+      smtp_port_input: data.profiles[0].smtp_port.to_string(),
       data,
+      profile: 0,
+      load_error: None,
+    }
+  }
+
+  pub fn save(&mut self) -> Result<()> {
+    if let Some(e) = &self.load_error {
+      anyhow::bail!(
+        "{} - fix or move the file and restart to change settings",
+        e
+      );
+    }
+    self.commit_smtp_port()?;
+    Storage::save_config(&self.data)
+  }
+
+  pub fn profile(&self) -> &SenderProfile {
+    &self.data.profiles[self.profile]
+  }
+
+  fn profile_mut(&mut self) -> &mut SenderProfile {
+    &mut self.data.profiles[self.profile]
+  }
+
+  /// Switches to the next profile once the port typed for the current one is valid.
+  pub fn next_profile(&mut self) -> Result<()> {
+    self.commit_smtp_port()?;
+    self.select_profile((self.profile + 1) % self.data.profiles.len());
+    Ok(())
+  }
+
+  /// Adds an empty profile with an unused name and selects it.
+  pub fn add_profile(&mut self) -> Result<()> {
+    self.commit_smtp_port()?;
+    let name = (self.data.profiles.len() + 1..)
+      .map(|n| format!("Profile {}", n))
+      .find(|name| self.data.profiles.iter().all(|p| &p.name != name))
+      .unwrap_or_default();
+    self.data.profiles.push(SenderProfile::new(&name));
+    self.select_profile(self.data.profiles.len() - 1);
+    Ok(())
+  }
+
+  fn select_profile(&mut self, idx: usize) {
+    self.profile = idx;
+    self.smtp_port_input = self.profile().smtp_port.to_string();
+  }
+
+  /// Removes the selected profile unless it is the last one. Drafts that used it fall
+  /// back to the first profile.
+  pub fn remove_profile(&mut self) -> Option<SenderProfile> {
+    if self.data.profiles.len() < 2 {
+      return None;
     }
+    let removed = self.data.profiles.remove(self.profile);
+    self.select_profile(self.profile.min(self.data.profiles.len() - 1));
+    Some(removed)
+  }
+
+  pub fn cycle_signature(&mut self) {
+    let next = signature::next(&self.profile().signature);
+    self.profile_mut().signature = next;
   }
 
   pub fn handle_input(&mut self, c: char) {
     match self.field {
-      ConfigField::Name => self.data.identity.name.push(c),
-      ConfigField::Role => self.data.identity.role.push(c),
-      ConfigField::Department => self.data.identity.department.push(c),
-      ConfigField::Institution => self.data.identity.institution.push(c),
-      ConfigField::Phone => self.data.identity.phone.push(c),
+      ConfigField::Profile | ConfigField::Signature => {}
+      ConfigField::ProfileName => self.profile_mut().name.push(c),
+      ConfigField::Name => self.profile_mut().identity.name.push(c),
+      ConfigField::Role => self.profile_mut().identity.role.push(c),
+      ConfigField::Department => self.profile_mut().identity.department.push(c),
+      ConfigField::Institution => self.profile_mut().identity.institution.push(c),
+      ConfigField::Phone => self.profile_mut().identity.phone.push(c),
       ConfigField::Emails => self.modify_emails(c, false),
-      ConfigField::FooterColor => self.data.identity.footer_color.push(c),
-      ConfigField::SmtpUser => self.data.smtp_username.push(c),
-      ConfigField::SmtpPass => self.data.smtp_app_password.push(c),
+      ConfigField::FooterColor => self.profile_mut().identity.footer_color.push(c),
+      ConfigField::PlainTextWidth => {
+        if let Some(digit) = c.to_digit(10)
+          && let Some(width) = self
+            .data
+            .plain_text_width
+            .checked_mul(10)
+            .and_then(|w| w.checked_add(digit as usize))
+        {
+          self.data.plain_text_width = width;
+        }
+      }
+      ConfigField::SmtpHost => self.profile_mut().smtp_host.push(c),
+      ConfigField::SmtpPort => {
+        if c.is_ascii_digit() && self.smtp_port_input.len() < 5 {
+          self.smtp_port_input.push(c);
+        }
+      }
+      ConfigField::SmtpSecurity => {}
+      ConfigField::SmtpUser => self.profile_mut().smtp_username.push(c),
+      ConfigField::SmtpPass => self.profile_mut().smtp_app_password.push(c),
+      ConfigField::SendRate => push_digit(&mut self.data.send_rate_per_minute, c),
+      ConfigField::SendConcurrency => push_digit(&mut self.data.max_concurrent_sends, c),
+      ConfigField::MessageSizeLimit => push_digit(&mut self.data.message_size_limit_mb, c),
       ConfigField::WorkerUrl => self.data.worker_url.push(c),
       ConfigField::ApiSecret => self.data.api_secret.push(c),
+      ConfigField::TrackingSecret => self.data.tracking_secret.push(c),
+      ConfigField::WorkerUploadLimit => push_digit(&mut self.data.worker_upload_limit_mb, c),
+      ConfigField::FilePicker => {}
     }
   }
 
   pub fn handle_backspace(&mut self) {
     match self.field {
+      ConfigField::Profile | ConfigField::Signature => {}
+      ConfigField::ProfileName => {
+        self.profile_mut().name.pop();
+      }
       ConfigField::Name => {
-        self.data.identity.name.pop();
+        self.profile_mut().identity.name.pop();
       }
       ConfigField::Role => {
-        self.data.identity.role.pop();
+        self.profile_mut().identity.role.pop();
       }
       ConfigField::Department => {
-        self.data.identity.department.pop();
+        self.profile_mut().identity.department.pop();
       }
       ConfigField::Institution => {
-        self.data.identity.institution.pop();
+        self.profile_mut().identity.institution.pop();
       }
       ConfigField::Phone => {
-        self.data.identity.phone.pop();
+        self.profile_mut().identity.phone.pop();
       }
       ConfigField::Emails => self.modify_emails(' ', true),
       ConfigField::FooterColor => {
-        self.data.identity.footer_color.pop();
+        self.profile_mut().identity.footer_color.pop();
+      }
+      ConfigField::PlainTextWidth => {
+        self.data.plain_text_width /= 10;
+      }
+      ConfigField::SmtpHost => {
+        self.profile_mut().smtp_host.pop();
       }
+      ConfigField::SmtpPort => {
+        self.smtp_port_input.pop();
+      }
+      ConfigField::SmtpSecurity => {}
       ConfigField::SmtpUser => {
-        self.data.smtp_username.pop();
+        self.profile_mut().smtp_username.pop();
       }
       ConfigField::SmtpPass => {
-        self.data.smtp_app_password.pop();
+        self.profile_mut().smtp_app_password.pop();
+      }
+      ConfigField::SendRate => {
+        self.data.send_rate_per_minute /= 10;
+      }
+      ConfigField::SendConcurrency => {
+        self.data.max_concurrent_sends /= 10;
+      }
+      ConfigField::MessageSizeLimit => {
+        self.data.message_size_limit_mb /= 10;
       }
       ConfigField::WorkerUrl => {
         self.data.worker_url.pop();
@@ -63,17 +188,58 @@ impl ConfigState {
       ConfigField::ApiSecret => {
         self.data.api_secret.pop();
       }
+      ConfigField::TrackingSecret => {
+        self.data.tracking_secret.pop();
+      }
+      ConfigField::WorkerUploadLimit => {
+        self.data.worker_upload_limit_mb /= 10;
+      }
+      ConfigField::FilePicker => {}
     }
   }
 
+  // Moves the port along with the mode when it is still on the previous mode's standard port.
+  pub fn cycle_smtp_security(&mut self) {
+    let current = self.profile().smtp_security;
+    let next = match current {
+      SmtpSecurity::ImplicitTls => SmtpSecurity::StartTls,
+      SmtpSecurity::StartTls => SmtpSecurity::Plaintext,
+      SmtpSecurity::Plaintext => SmtpSecurity::ImplicitTls,
+    };
+    if self.smtp_port_input == standard_port(current).to_string() {
+      self.smtp_port_input = standard_port(next).to_string();
+    }
+    self.profile_mut().smtp_security = next;
+  }
+
+  /// Validates the typed SMTP port and stores it in the selected profile, leaving the
+  /// profile untouched when it is invalid.
+  pub fn commit_smtp_port(&mut self) -> Result<()> {
+    match self.smtp_port_input.parse::<u16>() {
+      Ok(port) if port > 0 => {
+        self.profile_mut().smtp_port = port;
+        Ok(())
+      }
+      _ => anyhow::bail!("SMTP port must be between 1 and 65535"),
+    }
+  }
+
+  pub fn cycle_file_picker(&mut self) {
+    self.data.file_picker = match self.data.file_picker {
+      FilePickerMode::Auto => FilePickerMode::Terminal,
+      FilePickerMode::Terminal => FilePickerMode::System,
+      FilePickerMode::System => FilePickerMode::Auto,
+    };
+  }
+
   fn modify_emails(&mut self, c: char, is_backspace: bool) {
-    let mut str_rep = self.data.identity.emails.join(", ");
+    let mut str_rep = self.profile().identity.emails.join(", ");
     if is_backspace {
       str_rep.pop();
     } else {
       str_rep.push(c);
     }
-    self.data.identity.emails = if str_rep.is_empty() {
+    self.profile_mut().identity.emails = if str_rep.is_empty() {
       Vec::new()
     } else {
       str_rep.split(',').map(|s| s.trim().to_string()).collect()
@@ -83,32 +249,74 @@ impl ConfigState {
   pub fn cycle_field(&mut self, forward: bool) {
     if forward {
       self.field = match self.field {
+        ConfigField::Profile => ConfigField::ProfileName,
+        ConfigField::ProfileName => ConfigField::Name,
         ConfigField::Name => ConfigField::Role,
         ConfigField::Role => ConfigField::Department,
         ConfigField::Department => ConfigField::Institution,
         ConfigField::Institution => ConfigField::Phone,
         ConfigField::Phone => ConfigField::Emails,
         ConfigField::Emails => ConfigField::FooterColor,
-        ConfigField::FooterColor => ConfigField::SmtpUser,
+        ConfigField::FooterColor => ConfigField::Signature,
+        ConfigField::Signature => ConfigField::SmtpUser,
         ConfigField::SmtpUser => ConfigField::SmtpPass,
-        ConfigField::SmtpPass => ConfigField::WorkerUrl,
+        ConfigField::SmtpPass => ConfigField::SmtpHost,
+        ConfigField::SmtpHost => ConfigField::SmtpPort,
+        ConfigField::SmtpPort => ConfigField::SmtpSecurity,
+        ConfigField::SmtpSecurity => ConfigField::PlainTextWidth,
+        ConfigField::PlainTextWidth => ConfigField::SendRate,
+        ConfigField::SendRate => ConfigField::SendConcurrency,
+        ConfigField::SendConcurrency => ConfigField::MessageSizeLimit,
+        ConfigField::MessageSizeLimit => ConfigField::WorkerUrl,
         ConfigField::WorkerUrl => ConfigField::ApiSecret,
-        ConfigField::ApiSecret => ConfigField::Name,
+        ConfigField::ApiSecret => ConfigField::TrackingSecret,
+        ConfigField::TrackingSecret => ConfigField::WorkerUploadLimit,
+        ConfigField::WorkerUploadLimit => ConfigField::FilePicker,
+        ConfigField::FilePicker => ConfigField::Profile,
       };
     } else {
       self.field = match self.field {
-        ConfigField::Name => ConfigField::ApiSecret,
+        ConfigField::Profile => ConfigField::FilePicker,
+        ConfigField::ProfileName => ConfigField::Profile,
+        ConfigField::Name => ConfigField::ProfileName,
         ConfigField::Role => ConfigField::Name,
         ConfigField::Department => ConfigField::Role,
         ConfigField::Institution => ConfigField::Department,
         ConfigField::Phone => ConfigField::Institution,
         ConfigField::Emails => ConfigField::Phone,
         ConfigField::FooterColor => ConfigField::Emails,
-        ConfigField::SmtpUser => ConfigField::FooterColor,
+        ConfigField::Signature => ConfigField::FooterColor,
+        ConfigField::SmtpUser => ConfigField::Signature,
         ConfigField::SmtpPass => ConfigField::SmtpUser,
-        ConfigField::WorkerUrl => ConfigField::SmtpPass,
+        ConfigField::SmtpHost => ConfigField::SmtpPass,
+        ConfigField::SmtpPort => ConfigField::SmtpHost,
+        ConfigField::SmtpSecurity => ConfigField::SmtpPort,
+        ConfigField::PlainTextWidth => ConfigField::SmtpSecurity,
+        ConfigField::SendRate => ConfigField::PlainTextWidth,
+        ConfigField::SendConcurrency => ConfigField::SendRate,
+        ConfigField::MessageSizeLimit => ConfigField::SendConcurrency,
+        ConfigField::WorkerUrl => ConfigField::MessageSizeLimit,
         ConfigField::ApiSecret => ConfigField::WorkerUrl,
+        ConfigField::TrackingSecret => ConfigField::ApiSecret,
+        ConfigField::WorkerUploadLimit => ConfigField::TrackingSecret,
+        ConfigField::FilePicker => ConfigField::WorkerUploadLimit,
       };
     }
   }
 }
+
+fn push_digit(value: &mut u32, c: char) {
+  if let Some(digit) = c.to_digit(10)
+    && let Some(next) = value.checked_mul(10).and_then(|v| v.checked_add(digit))
+  {
+    *value = next;
+  }
+}
+
+fn standard_port(security: SmtpSecurity) -> u16 {
+  match security {
+    SmtpSecurity::ImplicitTls => 465,
+    SmtpSecurity::StartTls => 587,
+    SmtpSecurity::Plaintext => 25,
+  }
+}
@@ -0,0 +1,415 @@
+use crate::contacts;
+use crate::enums::{ContactField, ContactsFocus, GroupField};
+use crate::models::{
+  ConsentRegistry, Contact, ContactBook, RecipientGroup, address_name, bare_address,
+  is_group_reference, split_address_list,
+};
+use crate::storage::Storage;
+use anyhow::{Context, Result};
+use directories::UserDirs;
+use ratatui::widgets::TableState;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Default)]
+pub struct ContactsState {
+  pub book: ContactBook,
+  pub consent: ConsentRegistry,
+  pub focus: ContactsFocus,
+  pub list_state: TableState,
+  pub group_state: TableState,
+  /// Sent-to addresses that are not in the address book yet.
+  pub suggestions: Vec<String>,
+  pub suggestion_state: TableState,
+  pub form: ContactForm,
+  pub group_form: GroupForm,
+  /// Set when contacts.json or consent.json could not be read; saving is refused so the
+  /// files are not overwritten with an empty book.
+  pub load_error: Option<String>,
+  /// Contact id, or `@name` for a group, that the next `x` deletes.
+  pub pending_delete: Option<String>,
+}
+
+/// Fields of the add/edit popup. `id` is None while creating a new contact.
+#[derive(Default)]
+pub struct ContactForm {
+  pub id: Option<String>,
+  pub field: ContactField,
+  pub name: String,
+  pub addresses: String,
+  pub timezone: String,
+  pub notes: String,
+}
+
+/// Fields of the group popup. `original` is the name being edited, None for a new group.
+#[derive(Default)]
+pub struct GroupForm {
+  pub original: Option<String>,
+  pub field: GroupField,
+  pub name: String,
+  pub members: String,
+}
+
+impl ContactsState {
+  pub fn load() -> Self {
+    let mut state = Self::default();
+    match Storage::load_contacts() {
+      Ok(book) => state.book = book,
+      Err(e) => state.load_error = Some(format!("{:#}", e)),
+    }
+    match Storage::load_consent() {
+      Ok(consent) => state.consent = consent,
+      Err(e) => {
+        state.load_error.get_or_insert(format!("{:#}", e));
+      }
+    }
+    state.refresh_suggestions();
+    state.list_state.select(Some(0));
+    state.group_state.select(Some(0));
+    state
+  }
+
+  /// Writes `book` and only then makes it the one shown, so a failed save changes nothing.
+  fn save(&mut self, book: ContactBook) -> Result<()> {
+    self.ensure_loaded()?;
+    Storage::save_contacts(&book)?;
+    self.book = book;
+    Ok(())
+  }
+
+  fn ensure_loaded(&self) -> Result<()> {
+    match &self.load_error {
+      Some(e) => anyhow::bail!("{} - fix or move the file and restart to edit contacts", e),
+      None => Ok(()),
+    }
+  }
+
+  pub fn refresh_suggestions(&mut self) {
+    let records = Storage::list_sent().unwrap_or_default();
+    self.suggestions = contacts::unsaved_recipients(&self.book, &records);
+    let selected = self.suggestion_state.selected().unwrap_or(0);
+    self
+      .suggestion_state
+      .select((!self.suggestions.is_empty()).then(|| selected.min(self.suggestions.len() - 1)));
+  }
+
+  pub fn selected(&self) -> Option<&Contact> {
+    self
+      .list_state
+      .selected()
+      .and_then(|idx| self.book.contacts.get(idx))
+  }
+
+  pub fn selected_group(&self) -> Option<&RecipientGroup> {
+    self
+      .group_state
+      .selected()
+      .and_then(|idx| self.book.groups.get(idx))
+  }
+
+  pub fn selected_suggestion(&self) -> Option<&String> {
+    self
+      .suggestion_state
+      .selected()
+      .and_then(|idx| self.suggestions.get(idx))
+  }
+
+  pub fn open_new(&mut self) {
+    match self.focus {
+      ContactsFocus::Groups => self.group_form = GroupForm::default(),
+      _ => self.form = ContactForm::default(),
+    }
+  }
+
+  pub fn open_selected_group(&mut self) -> bool {
+    let Some(group) = self.selected_group() else {
+      return false;
+    };
+    self.group_form = GroupForm {
+      original: Some(group.name.clone()),
+      field: GroupField::Name,
+      name: group.name.clone(),
+      members: group.members.join(", "),
+    };
+    true
+  }
+
+  pub fn open_selected(&mut self) -> bool {
+    let Some(contact) = self.selected() else {
+      return false;
+    };
+    self.form = ContactForm {
+      id: Some(contact.id.clone()),
+      field: ContactField::Name,
+      name: contact.name.clone(),
+      addresses: contact.addresses.join(", "),
+      timezone: contact.timezone.clone(),
+      notes: contact.notes.clone(),
+    };
+    true
+  }
+
+  /// Prefills the form from the selected sent-to address.
+  pub fn open_suggestion(&mut self) -> bool {
+    let Some(address) = self.selected_suggestion() else {
+      return false;
+    };
+    self.form = ContactForm {
+      name: address_name(address).unwrap_or_default(),
+      addresses: bare_address(address).to_string(),
+      ..Default::default()
+    };
+    true
+  }
+
+  pub fn submit(&mut self) -> Result<()> {
+    match self.focus {
+      ContactsFocus::Groups => self.submit_group_form(),
+      _ => self.submit_form(),
+    }
+  }
+
+  fn submit_group_form(&mut self) -> Result<()> {
+    let name = self.group_form.name.trim().trim_start_matches('@');
+    if name.is_empty()
+      || !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+      anyhow::bail!("Group names use letters, digits, '-', '_' or '.'");
+    }
+    let clash = self.book.group(name).is_some_and(|g| {
+      self
+        .group_form
+        .original
+        .as_ref()
+        .is_none_or(|original| !g.name.eq_ignore_ascii_case(original))
+    });
+    if clash {
+      anyhow::bail!("A group named @{} already exists", name);
+    }
+
+    let members = split_address_list(&self.group_form.members);
+    if let Some(nested) = members.iter().find(|m| is_group_reference(m)) {
+      anyhow::bail!("Groups cannot contain other groups ({})", nested);
+    }
+    if let Some(invalid) = members.iter().find(|m| !bare_address(m).contains('@')) {
+      anyhow::bail!("Not an email address: {}", invalid);
+    }
+
+    let group = RecipientGroup {
+      name: name.to_string(),
+      members,
+    };
+    let mut book = self.book.clone();
+    let existing = self.group_form.original.as_ref().and_then(|original| {
+      book
+        .groups
+        .iter_mut()
+        .find(|g| g.name.eq_ignore_ascii_case(original))
+    });
+    match existing {
+      Some(existing) => *existing = group,
+      None => book.groups.push(group),
+    }
+    book.groups.sort_by_key(|g| g.name.to_lowercase());
+    self.save(book)
+  }
+
+  fn submit_form(&mut self) -> Result<()> {
+    let addresses: Vec<String> = split_address_list(&self.form.addresses)
+      .iter()
+      .map(|a| bare_address(a).to_string())
+      .collect();
+    if addresses.is_empty() {
+      anyhow::bail!("A contact needs at least one address");
+    }
+
+    let timezone = self.form.timezone.trim();
+    if !timezone.is_empty() && timezone.parse::<chrono_tz::Tz>().is_err() {
+      anyhow::bail!("Unknown timezone '{}' (use e.g. Europe/Berlin)", timezone);
+    }
+
+    let mut contact = Contact::new(self.form.name.trim().to_string(), addresses);
+    contact.timezone = timezone.to_string();
+    contact.notes = self.form.notes.trim().to_string();
+
+    let mut book = self.book.clone();
+    match &self.form.id {
+      Some(id) => {
+        contact.id = id.clone();
+        if let Some(existing) = book.contacts.iter_mut().find(|c| c.id == *id) {
+          *existing = contact;
+        }
+      }
+      None => book.contacts.push(contact),
+    }
+    book.contacts.sort_by_key(|c| c.name.to_lowercase());
+    self.save(book)?;
+    self.refresh_suggestions();
+    Ok(())
+  }
+
+  pub fn import(&mut self, path: &Path) -> Result<contacts::ImportSummary> {
+    let imported = contacts::read_contacts_file(path)?;
+    let mut book = self.book.clone();
+    let summary = contacts::merge_contacts(&mut book, imported);
+    self.save(book)?;
+    self.refresh_suggestions();
+    if self.list_state.selected().is_none() {
+      self.list_state.select(Some(0));
+    }
+    Ok(summary)
+  }
+
+  /// Writes the whole address book to Downloads/contacts.vcf.
+  pub fn export(&self) -> Result<PathBuf> {
+    let user_dirs = UserDirs::new().context("Could not determine home directory")?;
+    let dl_dir = user_dirs
+      .download_dir()
+      .context("Could not find a Downloads folder")?;
+    let path = dl_dir.join("contacts.vcf");
+
+    fs::write(&path, contacts::to_vcards(&self.book))
+      .with_context(|| format!("Could not write {}", path.display()))?;
+    Ok(path)
+  }
+
+  pub fn delete_selected_group(&mut self) -> Result<()> {
+    if let Some(idx) = self.group_state.selected()
+      && idx < self.book.groups.len()
+    {
+      let mut book = self.book.clone();
+      book.groups.remove(idx);
+      self.save(book)?;
+      self
+        .group_state
+        .select(Some(idx.min(self.book.groups.len().saturating_sub(1))));
+    }
+    Ok(())
+  }
+
+  pub fn delete_selected(&mut self) -> Result<()> {
+    if let Some(idx) = self.list_state.selected()
+      && idx < self.book.contacts.len()
+    {
+      let mut book = self.book.clone();
+      book.contacts.remove(idx);
+      self.save(book)?;
+      self.refresh_suggestions();
+      self
+        .list_state
+        .select(Some(idx.min(self.book.contacts.len().saturating_sub(1))));
+    }
+    Ok(())
+  }
+
+  /// Records tracking consent for every address of the selected contact, or revokes it when
+  /// all of them already have it. Returns whether consent is now given.
+  pub fn toggle_consent(&mut self) -> Result<Option<bool>> {
+    self.ensure_loaded()?;
+    let Some(contact) = self.selected() else {
+      return Ok(None);
+    };
+    let addresses = contact.addresses.clone();
+    let grant = addresses.iter().any(|a| !self.consent.has_consent(a));
+    let mut consent = self.consent.clone();
+    for address in &addresses {
+      if grant {
+        consent.grant(address);
+      } else {
+        consent.revoke(address);
+      }
+    }
+    Storage::save_consent(&consent)?;
+    self.consent = consent;
+    Ok(Some(grant))
+  }
+
+  pub fn ignore_selected_suggestion(&mut self) -> Result<()> {
+    if let Some(address) = self.selected_suggestion() {
+      let mut book = self.book.clone();
+      book.ignored.push(bare_address(address).to_string());
+      self.save(book)?;
+      self.refresh_suggestions();
+    }
+    Ok(())
+  }
+
+  pub fn cycle_focus(&mut self) {
+    self.focus = match self.focus {
+      ContactsFocus::Contacts => ContactsFocus::Groups,
+      ContactsFocus::Groups => ContactsFocus::Suggestions,
+      ContactsFocus::Suggestions => ContactsFocus::Contacts,
+    };
+  }
+
+  pub fn cycle_form_field(&mut self, forward: bool) {
+    if self.focus == ContactsFocus::Groups {
+      self.group_form.field = match self.group_form.field {
+        GroupField::Name => GroupField::Members,
+        GroupField::Members => GroupField::Name,
+      };
+      return;
+    }
+    self.form.field = match (self.form.field, forward) {
+      (ContactField::Name, true) => ContactField::Addresses,
+      (ContactField::Addresses, true) => ContactField::Timezone,
+      (ContactField::Timezone, true) => ContactField::Notes,
+      (ContactField::Notes, true) => ContactField::Name,
+      (ContactField::Name, false) => ContactField::Notes,
+      (ContactField::Addresses, false) => ContactField::Name,
+      (ContactField::Timezone, false) => ContactField::Addresses,
+      (ContactField::Notes, false) => ContactField::Timezone,
+    };
+  }
+
+  pub fn select_next(&mut self) {
+    let (state, len) = self.focused_list();
+    let i = match state.selected() {
+      Some(i) if i + 1 < len => i + 1,
+      _ => 0,
+    };
+    state.select(Some(i));
+  }
+
+  pub fn select_previous(&mut self) {
+    let (state, len) = self.focused_list();
+    let i = match state.selected() {
+      Some(0) | None => len.saturating_sub(1),
+      Some(i) => i - 1,
+    };
+    state.select(Some(i));
+  }
+
+  fn focused_list(&mut self) -> (&mut TableState, usize) {
+    match self.focus {
+      ContactsFocus::Contacts => (&mut self.list_state, self.book.contacts.len()),
+      ContactsFocus::Groups => (&mut self.group_state, self.book.groups.len()),
+      ContactsFocus::Suggestions => (&mut self.suggestion_state, self.suggestions.len()),
+    }
+  }
+
+  fn form_input(&mut self) -> &mut String {
+    if self.focus == ContactsFocus::Groups {
+      return match self.group_form.field {
+        GroupField::Name => &mut self.group_form.name,
+        GroupField::Members => &mut self.group_form.members,
+      };
+    }
+    match self.form.field {
+      ContactField::Name => &mut self.form.name,
+      ContactField::Addresses => &mut self.form.addresses,
+      ContactField::Timezone => &mut self.form.timezone,
+      ContactField::Notes => &mut self.form.notes,
+    }
+  }
+
+  pub fn handle_input(&mut self, c: char) {
+    self.form_input().push(c);
+  }
+
+  pub fn handle_backspace(&mut self) {
+    self.form_input().pop();
+  }
+}
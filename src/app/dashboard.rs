@@ -1,28 +1,42 @@
 use crate::enums::DashboardFocus;
-use crate::models::{FilterOptions, LogEntry};
-use base64::{Engine as _, engine::general_purpose};
+use crate::models::{FilterOptions, LogEntry, ScheduledJob, SentRecord, TrackedMessage};
+use crate::tracking;
 use chrono::{DateTime, Utc};
 use ratatui::widgets::TableState;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub struct RecipientSummary {
   pub tracking_id: String,
-  pub decoded_email: String,
+  /// Recipients of the tracked message, or the raw token when it is not in the local mapping.
+  pub recipients: String,
+  pub subject: String,
   pub country: String,
   pub open_count: usize,
+  /// Opens whose pixel URL did not carry a valid signature.
+  pub untrusted_count: usize,
   pub last_seen_raw: DateTime<Utc>,
   pub logs: Vec<LogEntry>,
+  pub groups: Vec<String>,
 }
 
 pub struct DashboardState {
   pub logs: Vec<LogEntry>,
   pub focus: DashboardFocus,
   pub filter_recipient: String,
+  pub filter_group: String,
   pub filter_country: String,
   pub filter_min_opens: String,
   pub list_state: TableState,
   pub selected_summary_id: Option<String>,
   pub filter_options: FilterOptions,
+  /// Recipient groups per tracking ID, collected from the Sent archive and scheduled jobs.
+  pub tracking_groups: HashMap<String, Vec<String>>,
+  /// What each tracking token stands for, from `Storage::load_tracked_messages`.
+  pub tracked_messages: HashMap<String, TrackedMessage>,
+  /// `AppConfig::tracking_secret`, for checking the signatures on logged pixel hits.
+  pub tracking_secret: String,
+  /// IDs of the logs whose signature checked out, see `verify_signatures`.
+  pub trusted_logs: HashSet<u32>,
 }
 
 impl Default for DashboardState {
@@ -31,11 +45,16 @@ impl Default for DashboardState {
       logs: Vec::new(),
       focus: DashboardFocus::FilterRecipient,
       filter_recipient: String::new(),
+      filter_group: String::new(),
       filter_country: String::new(),
       filter_min_opens: String::new(),
       list_state: TableState::default(),
       selected_summary_id: None,
       filter_options: FilterOptions::default(),
+      tracking_groups: HashMap::new(),
+      tracked_messages: HashMap::new(),
+      tracking_secret: String::new(),
+      trusted_logs: HashSet::new(),
     }
   }
 }
@@ -47,7 +66,8 @@ impl DashboardState {
     }
     if forward {
       self.focus = match self.focus {
-        DashboardFocus::FilterRecipient => DashboardFocus::FilterCountry,
+        DashboardFocus::FilterRecipient => DashboardFocus::FilterGroup,
+        DashboardFocus::FilterGroup => DashboardFocus::FilterCountry,
         DashboardFocus::FilterCountry => DashboardFocus::FilterMinOpens,
         DashboardFocus::FilterMinOpens => DashboardFocus::List,
         DashboardFocus::List => DashboardFocus::FilterRecipient,
@@ -55,7 +75,8 @@ impl DashboardState {
     } else {
       self.focus = match self.focus {
         DashboardFocus::FilterRecipient => DashboardFocus::List,
-        DashboardFocus::FilterCountry => DashboardFocus::FilterRecipient,
+        DashboardFocus::FilterGroup => DashboardFocus::FilterRecipient,
+        DashboardFocus::FilterCountry => DashboardFocus::FilterGroup,
         DashboardFocus::FilterMinOpens => DashboardFocus::FilterCountry,
         DashboardFocus::List => DashboardFocus::FilterMinOpens,
       };
@@ -65,6 +86,7 @@ impl DashboardState {
   pub fn handle_input(&mut self, c: char) {
     match self.focus {
       DashboardFocus::FilterRecipient => self.filter_recipient.push(c),
+      DashboardFocus::FilterGroup => self.filter_group.push(c),
       DashboardFocus::FilterCountry => self.filter_country.push(c),
       DashboardFocus::FilterMinOpens => {
         if c.is_numeric() {
@@ -80,6 +102,9 @@ impl DashboardState {
       DashboardFocus::FilterRecipient => {
         self.filter_recipient.pop();
       }
+      DashboardFocus::FilterGroup => {
+        self.filter_group.pop();
+      }
       DashboardFocus::FilterCountry => {
         self.filter_country.pop();
       }
@@ -90,6 +115,50 @@ impl DashboardState {
     }
   }
 
+  pub fn index_groups(&mut self, records: &[SentRecord], jobs: &[ScheduledJob]) {
+    let sent = records.iter().map(|r| (&r.tracking_id, r.groups.clone()));
+    let scheduled = jobs.iter().map(|j| {
+      let groups = j
+        .group
+        .split(',')
+        .map(|g| g.trim().to_string())
+        .filter(|g| !g.is_empty())
+        .collect();
+      (&j.tracking_id, groups)
+    });
+
+    self.tracking_groups.clear();
+    for (tracking_id, groups) in sent.chain(scheduled) {
+      let entry = self.tracking_groups.entry(tracking_id.clone()).or_default();
+      for group in groups {
+        if !entry.contains(&group) {
+          entry.push(group);
+        }
+      }
+    }
+    self.tracking_groups.retain(|_, groups| !groups.is_empty());
+  }
+
+  /// Checks every log's signature once, so drawing the dashboard does not recompute HMACs.
+  /// Runs whenever the logs or the secret change.
+  pub fn verify_signatures(&mut self) {
+    self.trusted_logs = self
+      .logs
+      .iter()
+      .filter(|log| {
+        log.signature.as_deref().is_some_and(|signature| {
+          tracking::verify(&self.tracking_secret, &log.tracking_id, signature)
+        })
+      })
+      .map(|log| log.id)
+      .collect();
+  }
+
+  /// Whether the pixel hit was requested with a valid signature for its tracking ID.
+  pub fn is_trusted(&self, log: &LogEntry) -> bool {
+    self.trusted_logs.contains(&log.id)
+  }
+
   pub fn get_aggregated_logs(&self) -> Vec<RecipientSummary> {
     let mut groups: HashMap<String, Vec<LogEntry>> = HashMap::new();
     for log in &self.logs {
@@ -102,16 +171,29 @@ impl DashboardState {
     let min_opens = self.filter_min_opens.parse::<usize>().unwrap_or(0);
 
     for (id, mut entries) in groups {
-      let decoded_email = general_purpose::URL_SAFE_NO_PAD
-        .decode(&id)
-        .map(|b| String::from_utf8_lossy(&b).to_string())
-        .unwrap_or_else(|_| id.clone());
+      let (recipients, subject) = match self.tracked_messages.get(&id) {
+        Some(message) => (message.recipients.join(", "), message.subject.clone()),
+        None => (id.clone(), String::new()),
+      };
       entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
 
       if entries.len() < min_opens {
         continue;
       }
-      if !self.filter_recipient.is_empty() && !decoded_email.contains(&self.filter_recipient) {
+      if !self.filter_recipient.is_empty() && !recipients.contains(&self.filter_recipient) {
+        continue;
+      }
+      let groups = self.tracking_groups.get(&id).cloned().unwrap_or_default();
+      let group_filter = self
+        .filter_group
+        .trim()
+        .trim_start_matches('@')
+        .to_lowercase();
+      if !group_filter.is_empty()
+        && !groups
+          .iter()
+          .any(|g| g.to_lowercase().contains(&group_filter))
+      {
         continue;
       }
       let country = entries
@@ -132,11 +214,14 @@ impl DashboardState {
         .unwrap_or_default();
       summaries.push(RecipientSummary {
         tracking_id: id,
-        decoded_email,
+        recipients,
+        subject,
         country,
         open_count: entries.len(),
+        untrusted_count: entries.iter().filter(|l| !self.is_trusted(l)).count(),
         last_seen_raw,
         logs: entries,
+        groups,
       });
     }
     summaries.sort_by(|a, b| b.last_seen_raw.cmp(&a.last_seen_raw));
@@ -0,0 +1,89 @@
+use crate::enums::DraftsFocus;
+use crate::models::EmailDraft;
+use ratatui::widgets::TableState;
+
+#[derive(Default)]
+pub struct DraftsState {
+  pub drafts: Vec<EmailDraft>,
+  pub templates: Vec<EmailDraft>,
+  pub focus: DraftsFocus,
+  pub list_state: TableState,
+  pub template_state: TableState,
+  pub rename_input: String,
+  /// Draft or template id that the next `x` deletes, set by a first `x` on it.
+  pub pending_delete: Option<String>,
+}
+
+impl DraftsState {
+  pub fn selected(&self) -> Option<&EmailDraft> {
+    self
+      .list_state
+      .selected()
+      .and_then(|idx| self.drafts.get(idx))
+  }
+
+  pub fn selected_template(&self) -> Option<&EmailDraft> {
+    self
+      .template_state
+      .selected()
+      .and_then(|idx| self.templates.get(idx))
+  }
+
+  /// The entry under the cursor in whichever list has focus.
+  pub fn focused(&self) -> Option<&EmailDraft> {
+    match self.focus {
+      DraftsFocus::Drafts => self.selected(),
+      DraftsFocus::Templates => self.selected_template(),
+    }
+  }
+
+  pub fn select_id(&mut self, id: &str) {
+    let idx = self.drafts.iter().position(|d| d.id == id);
+    self.list_state.select(idx.or(Some(0)));
+  }
+
+  pub fn select_template_id(&mut self, id: &str) {
+    let idx = self.templates.iter().position(|d| d.id == id);
+    self.template_state.select(idx.or(Some(0)));
+  }
+
+  pub fn cycle_focus(&mut self) {
+    self.focus = match self.focus {
+      DraftsFocus::Drafts => DraftsFocus::Templates,
+      DraftsFocus::Templates => DraftsFocus::Drafts,
+    };
+  }
+
+  pub fn select_next(&mut self) {
+    let (state, len) = self.focused_list();
+    let i = match state.selected() {
+      Some(i) if i + 1 < len => i + 1,
+      _ => 0,
+    };
+    state.select(Some(i));
+  }
+
+  pub fn select_previous(&mut self) {
+    let (state, len) = self.focused_list();
+    let i = match state.selected() {
+      Some(0) | None => len.saturating_sub(1),
+      Some(i) => i - 1,
+    };
+    state.select(Some(i));
+  }
+
+  fn focused_list(&mut self) -> (&mut TableState, usize) {
+    match self.focus {
+      DraftsFocus::Drafts => (&mut self.list_state, self.drafts.len()),
+      DraftsFocus::Templates => (&mut self.template_state, self.templates.len()),
+    }
+  }
+
+  pub fn handle_input(&mut self, c: char) {
+    self.rename_input.push(c);
+  }
+
+  pub fn handle_backspace(&mut self) {
+    self.rename_input.pop();
+  }
+}
@@ -0,0 +1,119 @@
+use crate::enums::{MergeFocus, MergeRowStatus};
+use crate::merge::{self, MergeData};
+use crate::models::EmailDraft;
+use ratatui::widgets::TableState;
+use std::path::Path;
+
+#[derive(Default)]
+pub struct MergeState {
+  pub focus: MergeFocus,
+  pub csv_path_input: String,
+  pub data: Option<MergeData>,
+  pub address_column: usize,
+  pub list_state: TableState,
+  pub row_status: Vec<MergeRowStatus>,
+  pub running: bool,
+}
+
+impl MergeState {
+  pub fn load(&mut self) -> anyhow::Result<usize> {
+    let path = self.csv_path_input.trim();
+    let data = merge::load_csv(Path::new(path))?;
+    let count = data.rows.len();
+
+    self.address_column = data.guess_address_column();
+    self.row_status = vec![MergeRowStatus::Pending; count];
+    self
+      .list_state
+      .select(if count > 0 { Some(0) } else { None });
+    self.data = Some(data);
+    Ok(count)
+  }
+
+  pub fn row_count(&self) -> usize {
+    self.data.as_ref().map(|d| d.rows.len()).unwrap_or(0)
+  }
+
+  pub fn address_header(&self) -> &str {
+    self
+      .data
+      .as_ref()
+      .and_then(|d| d.headers.get(self.address_column))
+      .map(String::as_str)
+      .unwrap_or("-")
+  }
+
+  pub fn cycle_address_column(&mut self) {
+    if let Some(data) = &self.data {
+      self.address_column = (self.address_column + 1) % data.headers.len().max(1);
+    }
+  }
+
+  pub fn personalise(&self, draft: &EmailDraft, row: usize) -> Option<EmailDraft> {
+    let data = self.data.as_ref()?;
+    Some(merge::personalise(draft, data, row, self.address_column))
+  }
+
+  /// Counts of (sent or scheduled, failed, still pending or in flight).
+  pub fn tally(&self) -> (usize, usize, usize) {
+    let mut done = 0;
+    let mut failed = 0;
+    let mut remaining = 0;
+    for status in &self.row_status {
+      match status {
+        MergeRowStatus::Sent | MergeRowStatus::Scheduled => done += 1,
+        MergeRowStatus::Failed(_) => failed += 1,
+        MergeRowStatus::Pending | MergeRowStatus::InFlight | MergeRowStatus::Retrying(_) => {
+          remaining += 1
+        }
+      }
+    }
+    (done, failed, remaining)
+  }
+
+  pub fn cycle_focus(&mut self, forward: bool) {
+    if forward {
+      self.focus = match self.focus {
+        MergeFocus::CsvPath => MergeFocus::AddressColumn,
+        MergeFocus::AddressColumn => MergeFocus::Rows,
+        MergeFocus::Rows => MergeFocus::SendButton,
+        MergeFocus::SendButton => MergeFocus::CsvPath,
+      };
+    } else {
+      self.focus = match self.focus {
+        MergeFocus::CsvPath => MergeFocus::SendButton,
+        MergeFocus::AddressColumn => MergeFocus::CsvPath,
+        MergeFocus::Rows => MergeFocus::AddressColumn,
+        MergeFocus::SendButton => MergeFocus::Rows,
+      };
+    }
+  }
+
+  pub fn select_next(&mut self) {
+    let i = match self.list_state.selected() {
+      Some(i) if i + 1 < self.row_count() => i + 1,
+      _ => 0,
+    };
+    self.list_state.select(Some(i));
+  }
+
+  pub fn select_previous(&mut self) {
+    let i = match self.list_state.selected() {
+      Some(0) | None => self.row_count().saturating_sub(1),
+      Some(i) => i - 1,
+    };
+    self.list_state.select(Some(i));
+  }
+
+  pub fn handle_input(&mut self, c: char) {
+    if self.focus == MergeFocus::CsvPath {
+      self.csv_path_input.push(c);
+    }
+  }
+
+  pub fn handle_backspace(&mut self) {
+    if self.focus == MergeFocus::CsvPath {
+      self.csv_path_input.pop();
+    }
+  }
+}
@@ -1,71 +1,222 @@
 pub mod compose;
 pub mod configuration;
+pub mod contacts;
 pub mod dashboard;
+pub mod drafts;
+pub mod merge;
+pub mod outbox;
+pub mod picker;
 pub mod schedule;
 pub mod scheduled;
+pub mod sent;
 
-use crate::enums::{CurrentPage, InputMode, Notification};
+use crate::config::AppConfig;
+use crate::enums::{CurrentPage, InputMode, MergeRowStatus, Notification, OutboxStatus};
+use crate::handler::Action;
+use crate::models::EmailDraft;
+use crate::queue::{QueueStats, SendQueue};
 use crate::storage::Storage;
 use compose::ComposeState;
 use configuration::ConfigState;
+use contacts::ContactsState;
 use dashboard::DashboardState;
+use drafts::DraftsState;
+use merge::MergeState;
+use outbox::OutboxState;
+use picker::FilePickerState;
 use schedule::ScheduleState;
 use scheduled::ScheduledState;
+use sent::SentState;
+use tokio::sync::mpsc;
 
 pub struct App {
   pub should_quit: bool,
   pub input_mode: InputMode,
   pub current_page: CurrentPage,
   pub notification: Option<Notification>,
+  pub queue: SendQueue,
+  pub queue_stats: QueueStats,
 
   // Sub-States
   pub compose: ComposeState,
   pub config: ConfigState,
+  pub contacts: ContactsState,
   pub dashboard: DashboardState,
+  pub drafts: DraftsState,
+  pub merge: MergeState,
+  pub outbox: OutboxState,
+  /// Built-in file browser, drawn over the current page while open.
+  pub picker: Option<FilePickerState>,
   pub schedule: ScheduleState,
   pub scheduled: ScheduledState,
+  pub sent: SentState,
 }
 
 impl App {
-  pub fn new() -> Self {
-    let loaded_config = Storage::load_config().unwrap_or_default();
-    let loaded_draft = Storage::load_draft().unwrap_or_default();
-
-    // Hydrate Schedule
-    let mut schedule = ScheduleState::default();
-    if !loaded_draft.schedule.day.is_empty() {
-      schedule.day = loaded_draft.schedule.day.clone();
-      schedule.month = loaded_draft.schedule.month.clone();
-      schedule.year = loaded_draft.schedule.year.clone();
-      schedule.hour = loaded_draft.schedule.hour.clone();
-      schedule.minute = loaded_draft.schedule.minute.clone();
-      schedule.second = loaded_draft.schedule.second.clone();
-      schedule.timezone_input = loaded_draft.schedule.timezone.clone();
-      schedule.update_timezone_filter();
+  pub fn new(tx: mpsc::Sender<Action>) -> Self {
+    // A default config has no tracking secret, so nothing is tracked until the file is fixed
+    let (loaded_config, config_error) = match Storage::load_config() {
+      Ok(config) => (config, None),
+      Err(e) => (AppConfig::default(), Some(format!("{:#}", e))),
+    };
+    let mut drafts = DraftsState {
+      drafts: Storage::list_drafts().unwrap_or_default(),
+      templates: Storage::list_templates().unwrap_or_default(),
+      ..Default::default()
+    };
+    let loaded_draft = drafts.drafts.first().cloned().unwrap_or_else(|| {
+      let draft = EmailDraft::default();
+      let _ = Storage::save_draft(&draft);
+      drafts.drafts.push(draft.clone());
+      draft
+    });
+    drafts.select_id(&loaded_draft.id);
+
+    // Anything left in the outbox from a previous session is picked up again.
+    let queue = SendQueue::spawn(tx, &loaded_config);
+    let mut outbox = OutboxState::default();
+    outbox.refresh();
+    for entry in &outbox.entries {
+      if entry.status != OutboxStatus::Failed {
+        queue.requeue(&loaded_config, entry.clone());
+      }
     }
 
-    Self {
+    let contacts = ContactsState::load();
+
+    let mut app = Self {
       should_quit: false,
       input_mode: InputMode::Normal,
       current_page: CurrentPage::Compose,
-      notification: None,
+      notification: config_error
+        .clone()
+        .or_else(|| contacts.load_error.clone())
+        .map(Notification::Error),
+      queue,
+      queue_stats: QueueStats::default(),
+      schedule: ScheduleState::from_draft(&loaded_draft),
       compose: ComposeState::new(loaded_draft),
-      config: ConfigState::new(loaded_config),
+      config: ConfigState {
+        load_error: config_error,
+        ..ConfigState::new(loaded_config)
+      },
+      contacts,
       dashboard: DashboardState::default(),
-      schedule,
+      drafts,
+      merge: MergeState::default(),
+      outbox,
+      picker: None,
       scheduled: ScheduledState::default(),
-    }
+      sent: SentState::default(),
+    };
+    app
+      .compose
+      .refresh_checks(&app.config.data, &app.contacts.consent, &app.contacts.book);
+    app
   }
 
   pub fn reset_schedule_modal(&mut self) {
     self.schedule.reset_defaults_if_empty();
+
+    // Default to the primary recipient's timezone when the draft has none yet.
+    if self.schedule.timezone_input.is_empty()
+      && let Some(contact) = self
+        .compose
+        .draft
+        .to
+        .first()
+        .and_then(|address| self.contacts.book.find_by_address(address))
+      && !contact.timezone.is_empty()
+    {
+      self.schedule.timezone_input = contact.timezone.clone();
+      self.schedule.update_timezone_filter();
+    }
   }
 
   pub fn quit(&mut self) {
-    self.compose.sync_attachments();
+    self.save_draft();
+    self.should_quit = true;
+  }
+
+  /// Autosaves the active draft to its own file in the drafts directory.
+  pub fn save_draft(&mut self) {
+    self.compose.sync_inputs();
     self.sync_schedule_to_draft();
+    self.compose.refresh_checks(
+      &self.config.data,
+      &self.contacts.consent,
+      &self.contacts.book,
+    );
     let _ = Storage::save_draft(&self.compose.draft);
-    self.should_quit = true;
+  }
+
+  /// Saves the active draft and makes `draft` the one being composed.
+  pub fn open_draft(&mut self, draft: EmailDraft) {
+    self.save_draft();
+    self.load_draft(draft);
+  }
+
+  /// Replaces the active draft without saving it first (e.g. after it was deleted).
+  pub fn load_draft(&mut self, draft: EmailDraft) {
+    self.schedule = ScheduleState::from_draft(&draft);
+    self.compose = ComposeState::new(draft);
+    self.compose.refresh_checks(
+      &self.config.data,
+      &self.contacts.consent,
+      &self.contacts.book,
+    );
+    let _ = Storage::save_draft(&self.compose.draft);
+  }
+
+  pub fn refresh_drafts(&mut self) {
+    self.drafts.drafts = Storage::list_drafts().unwrap_or_default();
+    let selected_id = self
+      .drafts
+      .selected()
+      .map(|d| d.id.clone())
+      .unwrap_or_else(|| self.compose.draft.id.clone());
+    self.drafts.select_id(&selected_id);
+
+    self.drafts.templates = Storage::list_templates().unwrap_or_default();
+    let selected_template = self
+      .drafts
+      .selected_template()
+      .map(|t| t.id.clone())
+      .unwrap_or_default();
+    self.drafts.select_template_id(&selected_template);
+  }
+
+  pub fn show_drafts(&mut self) {
+    self.save_draft();
+    self.refresh_drafts();
+    self.current_page = CurrentPage::Drafts;
+  }
+
+  pub fn show_outbox(&mut self) {
+    self.outbox.refresh();
+    self.current_page = CurrentPage::Outbox;
+  }
+
+  pub fn show_sent(&mut self) {
+    if let Err(e) = self.sent.refresh() {
+      self.set_notification(Notification::Error(format!("{:#}", e)));
+    }
+    self.current_page = CurrentPage::Sent;
+  }
+
+  /// Rebuilds the Dashboard's group filter from the Sent archive and the last fetched jobs,
+  /// reloads the tracking token mapping and signing secret, and re-verifies the logs.
+  pub fn index_recipient_groups(&mut self) {
+    let records = Storage::list_sent().unwrap_or_default();
+    self.dashboard.index_groups(&records, &self.scheduled.jobs);
+    self.dashboard.tracked_messages = Storage::load_tracked_messages().unwrap_or_default();
+    self.dashboard.tracking_secret = self.config.data.tracking_secret.clone();
+    self.dashboard.verify_signatures();
+  }
+
+  pub fn show_contacts(&mut self) {
+    self.contacts.refresh_suggestions();
+    self.current_page = CurrentPage::Contacts;
   }
 
   pub fn sync_schedule_to_draft(&mut self) {
@@ -78,6 +229,32 @@ impl App {
     self.compose.draft.schedule.timezone = self.schedule.timezone_input.clone();
   }
 
+  pub fn toggle_queue_paused(&mut self) {
+    if self.queue_stats.paused {
+      self.queue.resume();
+      self.set_notification(Notification::Info("Send queue resumed".to_string()));
+    } else {
+      self.queue.pause();
+      self.set_notification(Notification::Info("Send queue paused".to_string()));
+    }
+  }
+
+  pub fn update_merge_row(&mut self, row: usize, status: MergeRowStatus) {
+    if let Some(slot) = self.merge.row_status.get_mut(row) {
+      *slot = status;
+    }
+    let (done, failed, remaining) = self.merge.tally();
+    if self.merge.running && remaining == 0 {
+      self.merge.running = false;
+      let report = format!("Mail merge finished: {} delivered, {} failed", done, failed);
+      if failed == 0 {
+        self.set_notification(Notification::Success(report));
+      } else {
+        self.set_notification(Notification::Error(report));
+      }
+    }
+  }
+
   pub fn set_notification(&mut self, note: Notification) {
     self.notification = Some(note);
   }
@@ -108,26 +285,44 @@ impl App {
       CurrentPage::Dashboard => self.dashboard.cycle_focus(forward),
       CurrentPage::Schedule => self.schedule.cycle_field(forward),
       CurrentPage::Scheduled => self.scheduled.cycle_focus(forward),
+      CurrentPage::Drafts => self.drafts.cycle_focus(),
+      CurrentPage::Merge => self.merge.cycle_focus(forward),
+      CurrentPage::Outbox | CurrentPage::Sent => {}
+      CurrentPage::Contacts => self.contacts.cycle_focus(),
     }
   }
 
   pub fn push_input(&mut self, c: char) {
     match self.current_page {
-      CurrentPage::Compose => self.compose.handle_input(c),
+      CurrentPage::Compose => {
+        self.compose.handle_input(c);
+        self.compose.update_suggestions(&self.contacts.book);
+      }
       CurrentPage::Config => self.config.handle_input(c),
       CurrentPage::Dashboard => self.dashboard.handle_input(c),
       CurrentPage::Schedule => self.schedule.handle_input(c),
       CurrentPage::Scheduled => self.scheduled.handle_input(c),
+      CurrentPage::Drafts => self.drafts.handle_input(c),
+      CurrentPage::Merge => self.merge.handle_input(c),
+      CurrentPage::Outbox | CurrentPage::Sent => {}
+      CurrentPage::Contacts => self.contacts.handle_input(c),
     }
   }
 
   pub fn pop_input(&mut self) {
     match self.current_page {
-      CurrentPage::Compose => self.compose.handle_backspace(),
+      CurrentPage::Compose => {
+        self.compose.handle_backspace();
+        self.compose.update_suggestions(&self.contacts.book);
+      }
       CurrentPage::Config => self.config.handle_backspace(),
       CurrentPage::Dashboard => self.dashboard.handle_backspace(),
       CurrentPage::Schedule => self.schedule.handle_backspace(),
       CurrentPage::Scheduled => self.scheduled.handle_backspace(),
+      CurrentPage::Drafts => self.drafts.handle_backspace(),
+      CurrentPage::Merge => self.merge.handle_backspace(),
+      CurrentPage::Outbox | CurrentPage::Sent => {}
+      CurrentPage::Contacts => self.contacts.handle_backspace(),
     }
   }
 }
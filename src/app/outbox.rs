@@ -0,0 +1,49 @@
+use crate::models::OutboxEntry;
+use crate::storage::Storage;
+use ratatui::widgets::TableState;
+
+#[derive(Default)]
+pub struct OutboxState {
+  pub entries: Vec<OutboxEntry>,
+  pub list_state: TableState,
+  pub inspecting: bool,
+}
+
+impl OutboxState {
+  /// Reloads the outbox from disk, keeping the selection on the same entry where possible.
+  pub fn refresh(&mut self) {
+    let selected_id = self.selected().map(|e| e.id.clone());
+    self.entries = Storage::list_outbox().unwrap_or_default();
+
+    let index = selected_id
+      .and_then(|id| self.entries.iter().position(|e| e.id == id))
+      .or_else(|| {
+        let previous = self.list_state.selected().unwrap_or(0);
+        (!self.entries.is_empty()).then(|| previous.min(self.entries.len() - 1))
+      });
+    self.list_state.select(index);
+    if index.is_none() {
+      self.inspecting = false;
+    }
+  }
+
+  pub fn selected(&self) -> Option<&OutboxEntry> {
+    self.list_state.selected().and_then(|i| self.entries.get(i))
+  }
+
+  pub fn select_next(&mut self) {
+    let i = match self.list_state.selected() {
+      Some(i) if i + 1 < self.entries.len() => i + 1,
+      _ => 0,
+    };
+    self.list_state.select(Some(i));
+  }
+
+  pub fn select_previous(&mut self) {
+    let i = match self.list_state.selected() {
+      Some(0) | None => self.entries.len().saturating_sub(1),
+      Some(i) => i - 1,
+    };
+    self.list_state.select(Some(i));
+  }
+}
@@ -0,0 +1,244 @@
+use crate::contacts;
+use crate::enums::PickerTarget;
+use ratatui::widgets::TableState;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct PickerEntry {
+  pub name: String,
+  pub path: PathBuf,
+  pub is_dir: bool,
+  pub size: Option<u64>,
+}
+
+/// Built-in file browser, used instead of the system dialog when there is no display server.
+pub struct FilePickerState {
+  pub target: PickerTarget,
+  pub dir: PathBuf,
+  /// Directory contents, directories first. Unfiltered.
+  pub entries: Vec<PickerEntry>,
+  pub filter: String,
+  pub show_hidden: bool,
+  pub multi_select: bool,
+  /// Only files with these extensions are listed, when set.
+  pub extensions: Option<&'static [&'static str]>,
+  pub marked: BTreeSet<PathBuf>,
+  pub list_state: TableState,
+  pub error: Option<String>,
+}
+
+impl FilePickerState {
+  pub fn new(target: PickerTarget, start_dir: PathBuf) -> Self {
+    let (multi_select, extensions): (bool, Option<&'static [&'static str]>) = match target {
+      PickerTarget::Attachments => (true, None),
+      PickerTarget::InlineImages => (true, Some(&["png", "jpg", "jpeg", "gif", "webp", "svg"])),
+      PickerTarget::ContactImport => (false, Some(&["vcf", "vcard", "csv"])),
+    };
+
+    let mut state = Self {
+      target,
+      dir: start_dir,
+      entries: Vec::new(),
+      filter: String::new(),
+      show_hidden: false,
+      multi_select,
+      extensions,
+      marked: BTreeSet::new(),
+      list_state: TableState::default(),
+      error: None,
+    };
+    state.read_dir();
+    state
+  }
+
+  pub fn title(&self) -> &'static str {
+    match self.target {
+      PickerTarget::Attachments => " Attach Files ",
+      PickerTarget::InlineImages => " Insert Inline Images ",
+      PickerTarget::ContactImport => " Import Contacts ",
+    }
+  }
+
+  fn read_dir(&mut self) {
+    self.entries.clear();
+    self.error = None;
+
+    let read = match fs::read_dir(&self.dir) {
+      Ok(read) => read,
+      Err(e) => {
+        self.error = Some(format!("Cannot read {}: {}", self.dir.display(), e));
+        self.list_state.select(None);
+        return;
+      }
+    };
+
+    for entry in read.flatten() {
+      let name = entry.file_name().to_string_lossy().to_string();
+      if !self.show_hidden && name.starts_with('.') {
+        continue;
+      }
+      let path = entry.path();
+      // Follow symlinks so linked directories can be entered
+      let Ok(metadata) = fs::metadata(&path) else {
+        continue;
+      };
+      let is_dir = metadata.is_dir();
+      if !is_dir && !self.extension_allowed(&path) {
+        continue;
+      }
+      self.entries.push(PickerEntry {
+        name,
+        path,
+        is_dir,
+        size: (!is_dir).then_some(metadata.len()),
+      });
+    }
+
+    self
+      .entries
+      .sort_by_key(|e| (!e.is_dir, e.name.to_lowercase()));
+    self.list_state.select(Some(0));
+  }
+
+  fn extension_allowed(&self, path: &Path) -> bool {
+    let Some(allowed) = self.extensions else {
+      return true;
+    };
+    path
+      .extension()
+      .map(|ext| ext.to_string_lossy().to_lowercase())
+      .is_some_and(|ext| allowed.contains(&ext.as_str()))
+  }
+
+  /// Entries matching the filter, best match first. The parent directory is always listed first.
+  pub fn visible(&self) -> Vec<&PickerEntry> {
+    if self.filter.is_empty() {
+      return self.entries.iter().collect();
+    }
+    let mut scored: Vec<(i64, &PickerEntry)> = self
+      .entries
+      .iter()
+      .filter_map(|e| contacts::fuzzy_score(&self.filter, &e.name).map(|score| (score, e)))
+      .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, e)| e).collect()
+  }
+
+  pub fn has_parent(&self) -> bool {
+    self.dir.parent().is_some()
+  }
+
+  /// Row 0 is the `..` entry whenever the directory has a parent.
+  fn selected_entry(&self) -> Option<&PickerEntry> {
+    let offset = usize::from(self.has_parent());
+    let idx = self.list_state.selected()?.checked_sub(offset)?;
+    self.visible().get(idx).copied()
+  }
+
+  fn row_count(&self) -> usize {
+    self.visible().len() + usize::from(self.has_parent())
+  }
+
+  pub fn select_next(&mut self) {
+    let len = self.row_count();
+    if len == 0 {
+      return;
+    }
+    let i = self.list_state.selected().map_or(0, |i| (i + 1) % len);
+    self.list_state.select(Some(i));
+  }
+
+  pub fn select_previous(&mut self) {
+    let len = self.row_count();
+    if len == 0 {
+      return;
+    }
+    let i = self
+      .list_state
+      .selected()
+      .map_or(0, |i| (i + len - 1) % len);
+    self.list_state.select(Some(i));
+  }
+
+  pub fn change_dir(&mut self, dir: PathBuf) {
+    self.dir = dir;
+    self.filter.clear();
+    self.read_dir();
+  }
+
+  pub fn go_up(&mut self) {
+    if let Some(parent) = self.dir.parent().map(Path::to_path_buf) {
+      let previous = self.dir.clone();
+      self.change_dir(parent);
+      // Keep the cursor on the directory we came from
+      if let Some(idx) = self.visible().iter().position(|e| e.path == previous) {
+        self
+          .list_state
+          .select(Some(idx + usize::from(self.has_parent())));
+      }
+    }
+  }
+
+  /// Enters the selected directory (or `..`). Returns false when a file is selected.
+  pub fn open_selected(&mut self) -> bool {
+    if self.has_parent() && self.list_state.selected() == Some(0) {
+      self.go_up();
+      return true;
+    }
+    match self.selected_entry() {
+      Some(entry) if entry.is_dir => {
+        let dir = entry.path.clone();
+        self.change_dir(dir);
+        true
+      }
+      _ => false,
+    }
+  }
+
+  pub fn toggle_mark(&mut self) {
+    if !self.multi_select {
+      return;
+    }
+    let Some(path) = self
+      .selected_entry()
+      .filter(|e| !e.is_dir)
+      .map(|e| e.path.clone())
+    else {
+      return;
+    };
+    if !self.marked.remove(&path) {
+      self.marked.insert(path);
+    }
+    self.select_next();
+  }
+
+  pub fn toggle_hidden(&mut self) {
+    self.show_hidden = !self.show_hidden;
+    self.read_dir();
+  }
+
+  pub fn push_filter(&mut self, c: char) {
+    self.filter.push(c);
+    self.list_state.select(Some(usize::from(
+      self.has_parent() && !self.visible().is_empty(),
+    )));
+  }
+
+  pub fn pop_filter(&mut self) {
+    self.filter.pop();
+    self.list_state.select(Some(0));
+  }
+
+  /// The files to hand back: every marked file, or the highlighted one when nothing is marked.
+  pub fn chosen(&self) -> Vec<PathBuf> {
+    if !self.marked.is_empty() {
+      return self.marked.iter().cloned().collect();
+    }
+    self
+      .selected_entry()
+      .filter(|e| !e.is_dir)
+      .map(|e| vec![e.path.clone()])
+      .unwrap_or_default()
+  }
+}
@@ -1,4 +1,5 @@
-use crate::enums::ScheduleField;
+use crate::enums::{ScheduleField, ScheduleTarget};
+use crate::models::EmailDraft;
 use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
 use chrono_tz::{TZ_VARIANTS, Tz};
 
@@ -15,6 +16,7 @@ pub struct ScheduleState {
   pub selected_timezone_idx: usize,
   pub active_field: ScheduleField,
   pub is_open: bool,
+  pub target: ScheduleTarget,
 }
 
 impl Default for ScheduleState {
@@ -33,11 +35,27 @@ impl Default for ScheduleState {
       selected_timezone_idx: 0,
       active_field: ScheduleField::Day,
       is_open: false,
+      target: ScheduleTarget::Draft,
     }
   }
 }
 
 impl ScheduleState {
+  pub fn from_draft(draft: &EmailDraft) -> Self {
+    let mut schedule = Self::default();
+    if !draft.schedule.day.is_empty() {
+      schedule.day = draft.schedule.day.clone();
+      schedule.month = draft.schedule.month.clone();
+      schedule.year = draft.schedule.year.clone();
+      schedule.hour = draft.schedule.hour.clone();
+      schedule.minute = draft.schedule.minute.clone();
+      schedule.second = draft.schedule.second.clone();
+      schedule.timezone_input = draft.schedule.timezone.clone();
+      schedule.update_timezone_filter();
+    }
+    schedule
+  }
+
   pub fn update_timezone_filter(&mut self) {
     let query = self.timezone_input.trim().to_lowercase();
     if query.is_empty() {
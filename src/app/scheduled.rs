@@ -6,6 +6,7 @@ pub struct ScheduledState {
   pub jobs: Vec<ScheduledJob>,
   pub focus: ScheduledFocus,
   pub filter_recipient: String,
+  pub filter_group: String,
   pub filter_status: Option<JobStatus>,
   pub list_state: TableState,
   pub selected_job_id: Option<String>,
@@ -17,6 +18,7 @@ impl Default for ScheduledState {
       jobs: Vec::new(),
       focus: ScheduledFocus::FilterRecipient,
       filter_recipient: String::new(),
+      filter_group: String::new(),
       filter_status: None,
       list_state: TableState::default(),
       selected_job_id: None,
@@ -32,14 +34,16 @@ impl ScheduledState {
 
     if forward {
       self.focus = match self.focus {
-        ScheduledFocus::FilterRecipient => ScheduledFocus::FilterStatus,
+        ScheduledFocus::FilterRecipient => ScheduledFocus::FilterGroup,
+        ScheduledFocus::FilterGroup => ScheduledFocus::FilterStatus,
         ScheduledFocus::FilterStatus => ScheduledFocus::List,
         ScheduledFocus::List => ScheduledFocus::FilterRecipient,
       };
     } else {
       self.focus = match self.focus {
         ScheduledFocus::FilterRecipient => ScheduledFocus::List,
-        ScheduledFocus::FilterStatus => ScheduledFocus::FilterRecipient,
+        ScheduledFocus::FilterGroup => ScheduledFocus::FilterRecipient,
+        ScheduledFocus::FilterStatus => ScheduledFocus::FilterGroup,
         ScheduledFocus::List => ScheduledFocus::FilterStatus,
       };
     }
@@ -48,6 +52,7 @@ impl ScheduledState {
   pub fn handle_input(&mut self, c: char) {
     match self.focus {
       ScheduledFocus::FilterRecipient => self.filter_recipient.push(c),
+      ScheduledFocus::FilterGroup => self.filter_group.push(c),
       _ => {}
     }
   }
@@ -57,6 +62,9 @@ impl ScheduledState {
       ScheduledFocus::FilterRecipient => {
         self.filter_recipient.pop();
       }
+      ScheduledFocus::FilterGroup => {
+        self.filter_group.pop();
+      }
       _ => {}
     }
   }
@@ -80,14 +88,24 @@ impl ScheduledState {
           true
         } else {
           job.recipient.contains(&self.filter_recipient)
+            || job.cc.contains(&self.filter_recipient)
+            || job.bcc.contains(&self.filter_recipient)
         };
 
+        let group_filter = self
+          .filter_group
+          .trim()
+          .trim_start_matches('@')
+          .to_lowercase();
+        let matches_group =
+          group_filter.is_empty() || job.group.to_lowercase().contains(&group_filter);
+
         let matches_status = match self.filter_status {
           None => true,
           Some(s) => job.status == s,
         };
 
-        matches_recipient && matches_status
+        matches_recipient && matches_group && matches_status
       })
       .collect()
   }
@@ -0,0 +1,55 @@
+use crate::models::SentRecord;
+use crate::storage::Storage;
+use anyhow::Result;
+use ratatui::widgets::TableState;
+use std::fs;
+
+#[derive(Default)]
+pub struct SentState {
+  pub records: Vec<SentRecord>,
+  pub list_state: TableState,
+  /// Raw message of the record being inspected, if any.
+  pub inspected_eml: Option<String>,
+}
+
+impl SentState {
+  pub fn refresh(&mut self) -> Result<()> {
+    let selected_id = self.selected().map(|r| r.id.clone());
+    self.records = Storage::list_sent()?;
+
+    let index = selected_id
+      .and_then(|id| self.records.iter().position(|r| r.id == id))
+      .or((!self.records.is_empty()).then_some(0));
+    self.list_state.select(index);
+    Ok(())
+  }
+
+  pub fn selected(&self) -> Option<&SentRecord> {
+    self.list_state.selected().and_then(|i| self.records.get(i))
+  }
+
+  pub fn inspect_selected(&mut self) {
+    self.inspected_eml = self.selected().map(|record| {
+      Storage::sent_eml_path(&record.id)
+        .and_then(|path| Ok(fs::read(path)?))
+        .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+        .unwrap_or_else(|e| format!("Could not read message: {}", e))
+    });
+  }
+
+  pub fn select_next(&mut self) {
+    let i = match self.list_state.selected() {
+      Some(i) if i + 1 < self.records.len() => i + 1,
+      _ => 0,
+    };
+    self.list_state.select(Some(i));
+  }
+
+  pub fn select_previous(&mut self) {
+    let i = match self.list_state.selected() {
+      Some(0) | None => self.records.len().saturating_sub(1),
+      Some(i) => i - 1,
+    };
+    self.list_state.select(Some(i));
+  }
+}
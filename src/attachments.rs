@@ -0,0 +1,147 @@
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// What the compose page shows for one attached file.
+pub struct AttachmentInfo {
+  pub name: String,
+  pub size: Option<u64>,
+  pub mime: String,
+}
+
+impl AttachmentInfo {
+  pub fn inspect(path: &Path) -> Self {
+    let metadata = fs::metadata(path).ok().filter(|m| m.is_file());
+    Self {
+      name: path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string()),
+      size: metadata.as_ref().map(|m| m.len()),
+      mime: mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .to_string(),
+    }
+  }
+}
+
+/// Turns a typed or pasted path into a usable one: surrounding quotes and
+/// backslash-escaped spaces (as left by drag and drop) are removed and `~` is expanded.
+pub fn normalize_path(input: &str) -> PathBuf {
+  let trimmed = input.trim();
+  let unquoted = trimmed
+    .strip_prefix('"')
+    .and_then(|s| s.strip_suffix('"'))
+    .or_else(|| {
+      trimmed
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+    })
+    .map(str::to_string)
+    .unwrap_or_else(|| trimmed.replace("\\ ", " "));
+
+  match unquoted.strip_prefix("~/") {
+    Some(rest) => directories::UserDirs::new()
+      .map(|dirs| dirs.home_dir().join(rest))
+      .unwrap_or_else(|| PathBuf::from(&unquoted)),
+    None => PathBuf::from(unquoted),
+  }
+}
+
+pub fn format_size(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+  let mut size = bytes as f64;
+  let mut unit = 0;
+  while size >= 1024.0 && unit < UNITS.len() - 1 {
+    size /= 1024.0;
+    unit += 1;
+  }
+  if unit == 0 {
+    format!("{} B", bytes)
+  } else {
+    format!("{:.1} {}", size, UNITS[unit])
+  }
+}
+
+/// Why a file referenced by a draft cannot be sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileProblem {
+  Missing,
+  PermissionDenied,
+  NotAFile,
+  NotAnImage,
+  Unreadable(String),
+}
+
+impl FileProblem {
+  pub fn label(&self) -> &str {
+    match self {
+      FileProblem::Missing => "missing",
+      FileProblem::PermissionDenied => "denied",
+      FileProblem::NotAFile => "not a file",
+      FileProblem::NotAnImage => "not an image",
+      FileProblem::Unreadable(_) => "unreadable",
+    }
+  }
+}
+
+impl fmt::Display for FileProblem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      FileProblem::Missing => write!(f, "file not found"),
+      FileProblem::PermissionDenied => write!(f, "permission denied"),
+      FileProblem::NotAFile => write!(f, "is a directory, not a file"),
+      FileProblem::NotAnImage => write!(f, "not a supported image type"),
+      FileProblem::Unreadable(err) => write!(f, "cannot be read ({})", err),
+    }
+  }
+}
+
+/// A problem with one attachment or inline image of a draft.
+#[derive(Debug, Clone)]
+pub struct FileDiagnostic {
+  pub path: PathBuf,
+  pub inline: bool,
+  pub problem: FileProblem,
+  /// The user chose to send without this file.
+  pub waived: bool,
+}
+
+impl fmt::Display for FileDiagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let kind = if self.inline {
+      "Inline image"
+    } else {
+      "Attachment"
+    };
+    write!(f, "{} {}: {}", kind, self.path.display(), self.problem)
+  }
+}
+
+/// Checks that `path` can be opened, and for inline images that it looks like an image.
+pub fn check_file(path: &Path, inline: bool) -> Option<FileProblem> {
+  let problem = match fs::File::open(path).and_then(|file| file.metadata()) {
+    Ok(metadata) if metadata.is_dir() => FileProblem::NotAFile,
+    Ok(_) if inline && mime_guess::from_path(path).first_or_octet_stream().type_() != "image" => {
+      FileProblem::NotAnImage
+    }
+    Ok(_) => return None,
+    Err(e) => match e.kind() {
+      ErrorKind::NotFound => FileProblem::Missing,
+      ErrorKind::PermissionDenied => FileProblem::PermissionDenied,
+      _ => FileProblem::Unreadable(e.to_string()),
+    },
+  };
+  Some(problem)
+}
+
+/// Describes the diagnostics the user has not waived, or `None` when nothing blocks sending.
+pub fn blocking_summary(diagnostics: &[FileDiagnostic]) -> Option<String> {
+  let blocking: Vec<String> = diagnostics
+    .iter()
+    .filter(|d| !d.waived)
+    .map(|d| d.to_string())
+    .collect();
+  (!blocking.is_empty()).then(|| format!("Unreadable files: {}", blocking.join("; ")))
+}
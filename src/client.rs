@@ -1,6 +1,7 @@
 use crate::compiler::CompiledEmail;
 use crate::models::{FilterOptions, LogEntry, ScheduledJob};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use reqwest::multipart::{Form, Part};
 use tokio::fs;
@@ -63,60 +64,77 @@ pub async fn delete_recipient_logs(
   Ok(())
 }
 
+pub struct ScheduleRequest {
+  pub subject: String,
+  pub to: Vec<String>,
+  pub cc: Vec<String>,
+  pub bcc: Vec<String>,
+  pub scheduled_at: DateTime<Utc>,
+  pub smtp_username: String,
+  pub smtp_password: String,
+  pub sender_name: String,
+  /// Name of the sender profile, recorded on the job.
+  pub sender_profile: String,
+  /// Comma separated names of the recipient groups the message was addressed to.
+  pub group: String,
+}
+
 pub async fn schedule_email(
   worker_url: &str,
   api_secret: &str,
   compiled: CompiledEmail,
-  subject: String,
-  recipient: String,
-  scheduled_at: chrono::DateTime<chrono::Utc>,
-  smtp_username: String,
-  smtp_password: String,
-  sender_name: String,
-) -> Result<()> {
+  request: ScheduleRequest,
+) -> Result<String> {
   let client = Client::new();
   let url = format!("{}/api/schedule?secret={}", worker_url, api_secret);
 
   let mut form = Form::new()
-    .text("recipient", recipient)
-    .text("subject", subject)
+    .text("recipient", request.to.join(", "))
+    .text("cc", request.cc.join(", "))
+    .text("bcc", request.bcc.join(", "))
+    .text("subject", request.subject)
     .text("html_body", compiled.html_body)
     .text("plain_body", compiled.plain_body)
-    .text("scheduled_at", scheduled_at.to_rfc3339())
-    .text("smtp_username", smtp_username)
-    .text("smtp_password", smtp_password)
-    .text("sender_name", sender_name);
+    .text("scheduled_at", request.scheduled_at.to_rfc3339())
+    .text("smtp_username", request.smtp_username)
+    .text("smtp_password", request.smtp_password)
+    .text("sender_name", request.sender_name)
+    .text("sender_profile", request.sender_profile)
+    .text("recipient_group", request.group)
+    .text("tracking_id", compiled.tracking_id);
 
   for path in compiled.attachments {
-    if let Ok(bytes) = fs::read(&path).await {
-      let filename = path
-        .file_name()
-        .map(|f| f.to_string_lossy().to_string())
-        .unwrap_or_else(|| "attachment.bin".to_string());
-
-      let mime = mime_guess::from_path(&path).first_or_octet_stream();
-
-      let part = Part::bytes(bytes)
-        .file_name(filename)
-        .mime_str(mime.as_ref())
-        .unwrap();
-
-      form = form.part("attachments", part);
-    }
+    let bytes = fs::read(&path)
+      .await
+      .with_context(|| format!("Cannot read attachment {}", path.display()))?;
+    let filename = path
+      .file_name()
+      .map(|f| f.to_string_lossy().to_string())
+      .unwrap_or_else(|| "attachment.bin".to_string());
+
+    let mime = mime_guess::from_path(&path).first_or_octet_stream();
+
+    let part = Part::bytes(bytes)
+      .file_name(filename)
+      .mime_str(mime.as_ref())
+      .unwrap();
+
+    form = form.part("attachments", part);
   }
 
   for img in compiled.inline_images {
-    if let Ok(bytes) = fs::read(&img.path).await {
-      let cid = img.cid.clone();
-      let mime = mime_guess::from_path(&img.path).first_or_octet_stream();
-
-      let part = Part::bytes(bytes)
-        .file_name(cid)
-        .mime_str(mime.as_ref())
-        .unwrap();
-
-      form = form.part("inline_images", part);
-    }
+    let bytes = fs::read(&img.path)
+      .await
+      .with_context(|| format!("Cannot read inline image {}", img.path.display()))?;
+    let cid = img.cid.clone();
+    let mime = mime_guess::from_path(&img.path).first_or_octet_stream();
+
+    let part = Part::bytes(bytes)
+      .file_name(cid)
+      .mime_str(mime.as_ref())
+      .unwrap();
+
+    form = form.part("inline_images", part);
   }
 
   let response = client
@@ -131,7 +149,13 @@ pub async fn schedule_email(
     return Err(anyhow::anyhow!("Worker rejected schedule: {}", text));
   }
 
-  Ok(())
+  // The job is already queued at this point, so a malformed reply only loses its ID.
+  let reply: serde_json::Value = response.json().await.unwrap_or_default();
+  Ok(match &reply["id"] {
+    serde_json::Value::String(id) => id.clone(),
+    serde_json::Value::Null => String::new(),
+    id => id.to_string(),
+  })
 }
 
 pub async fn fetch_scheduled_jobs(worker_url: &str, api_secret: &str) -> Result<Vec<ScheduledJob>> {
@@ -1,7 +1,12 @@
-use crate::models::{EmailDraft, UserIdentity};
-use base64::{Engine as _, engine::general_purpose};
-use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd, html};
-use std::path::PathBuf;
+use crate::attachments::{self, FileDiagnostic};
+use crate::config::AppConfig;
+use crate::enums::TrackingMode;
+use crate::models::{ConsentRegistry, EmailDraft};
+use crate::{highlight, plaintext, signature, theme, tracking};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd, html};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 pub struct InlineImage {
@@ -10,46 +15,321 @@ pub struct InlineImage {
 }
 
 pub struct CompiledEmail {
+  pub subject: String,
   pub html_body: String,
   pub plain_body: String,
   pub inline_images: Vec<InlineImage>,
   pub attachments: Vec<PathBuf>,
+  pub unfilled_placeholders: Vec<String>,
+  /// Attachments and inline images that cannot be read. Waived ones are left out of the message.
+  pub diagnostics: Vec<FileDiagnostic>,
+  /// Random token for this message, or empty when it carries no tracking pixel.
+  pub tracking_id: String,
 }
 
-pub fn compile(draft: &EmailDraft, identity: &UserIdentity, worker_url: &str) -> CompiledEmail {
-  let (html_content, inline_images) = parse_markdown_with_images(&draft.body);
-  let plain_body = strip_markdown(&draft.body);
-  let plain_footer = generate_plain_footer(identity);
+/// A fresh random tracking token for `compile`.
+pub fn new_tracking_id() -> String {
+  Uuid::new_v4().simple().to_string()
+}
 
-  let footer = generate_footer(identity);
-  let tracker = generate_tracker(worker_url, &draft.recipient);
+/// Builds both message parts. `tracking_id` is the token for the tracking pixel, which is only
+/// added when it is non-empty, a tracking secret is set and the draft's tracking settings and
+/// `consent` allow it.
+pub fn compile(
+  draft: &EmailDraft,
+  config: &AppConfig,
+  consent: &ConsentRegistry,
+  tracking_id: &str,
+) -> CompiledEmail {
+  let profile = config.profile(&draft.profile);
+  let identity = &profile.identity;
+  let mut unfilled_placeholders = Vec::new();
+  let subject = fill_placeholders(
+    &draft.subject,
+    &draft.variables,
+    false,
+    &mut unfilled_placeholders,
+  );
+  let body = fill_placeholders(
+    &draft.body,
+    &draft.variables,
+    true,
+    &mut unfilled_placeholders,
+  );
 
-  let full_html = format!(
-    r#"<!DOCTYPE html><html><head><style>body {{ font-family: Arial, sans-serif; color: #333; line-height: 1.6; }} a {{ color: {}; text-decoration: none; }} img {{ max-width: 100%; }}</style></head><body><div style="margin-bottom: 20px;">{}</div><br>{}{}</body></html>"#,
-    identity.footer_color, html_content, footer, tracker
+  let diagnostics = diagnose_files(draft, &body);
+  let skipped: Vec<&Path> = diagnostics
+    .iter()
+    .filter(|d| d.waived)
+    .map(|d| d.path.as_path())
+    .collect();
+
+  let (html_content, inline_images) = parse_markdown_with_images(&body, &skipped);
+  let plain_body = plaintext::render(&body, config.plain_text_width);
+  let signature = signature::render(signature::chosen(draft, profile), identity);
+  let (tracking_id, tracker) = match tracker(draft, config, consent, tracking_id) {
+    Some(tracker) => (tracking_id.to_string(), tracker),
+    None => (String::new(), String::new()),
+  };
+
+  let document = format!(
+    r#"<!DOCTYPE html><html><head></head><body><div style="margin-bottom: 20px;">{}</div><br>{}{}</body></html>"#,
+    html_content,
+    signature
+      .as_ref()
+      .map(|s| s.html.as_str())
+      .unwrap_or_default(),
+    tracker
   );
+  let stylesheet = theme::stylesheet(&draft.theme, &identity.footer_color);
+  let full_html = theme::inline_css(&document, &stylesheet);
 
-  let full_plain = format!("{}\n\n--\n{}", plain_body, plain_footer);
+  let full_plain = match &signature {
+    Some(signature) => format!("{}\n\n--\n{}", plain_body, signature.plain),
+    None => plain_body,
+  };
 
   CompiledEmail {
+    subject,
     html_body: full_html,
     plain_body: full_plain,
     inline_images,
-    attachments: draft.attachments.clone(),
+    attachments: draft
+      .attachments
+      .iter()
+      .filter(|path| !skipped.contains(&path.as_path()))
+      .cloned()
+      .collect(),
+    unfilled_placeholders,
+    diagnostics,
+    tracking_id,
+  }
+}
+
+/// Whether `draft` gets a tracking pixel: tracking has to be on for the draft, and every
+/// recipient has to be in the consent registry unless the draft overrides it.
+pub fn tracking_allowed(draft: &EmailDraft, registry: &ConsentRegistry) -> bool {
+  match draft.tracking {
+    TrackingMode::Off => false,
+    TrackingMode::Consented => registry.without_consent(draft).is_empty(),
+    TrackingMode::Override => true,
+  }
+}
+
+/// Placeholders in the subject or body that have no (non-empty) value in the draft's variables.
+pub fn unfilled_placeholders(draft: &EmailDraft) -> Vec<String> {
+  unfilled_with(draft, &draft.variables)
+}
+
+/// Like `unfilled_placeholders`, but checked against `variables` instead of the draft's own.
+pub fn unfilled_with(draft: &EmailDraft, variables: &BTreeMap<String, String>) -> Vec<String> {
+  let mut unfilled = Vec::new();
+  fill_placeholders(&draft.subject, variables, false, &mut unfilled);
+  fill_placeholders(&draft.body, variables, false, &mut unfilled);
+  unfilled
+}
+
+/// Checks every attachment and local inline image of the draft, including ones already waived.
+pub fn file_diagnostics(draft: &EmailDraft) -> Vec<FileDiagnostic> {
+  let body = fill_placeholders(&draft.body, &draft.variables, true, &mut Vec::new());
+  diagnose_files(draft, &body)
+}
+
+fn diagnose_files(draft: &EmailDraft, body: &str) -> Vec<FileDiagnostic> {
+  let attachments = draft.attachments.iter().cloned().map(|path| (path, false));
+  let images = local_image_paths(body).into_iter().map(|path| (path, true));
+
+  attachments
+    .chain(images)
+    .filter_map(|(path, inline)| {
+      let problem = attachments::check_file(&path, inline)?;
+      Some(FileDiagnostic {
+        waived: draft.waived_files.contains(&path),
+        path,
+        inline,
+        problem,
+      })
+    })
+    .collect()
+}
+
+fn is_remote_url(url: &str) -> bool {
+  url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Local image paths referenced by the body, in order and without duplicates.
+fn local_image_paths(markdown_input: &str) -> Vec<PathBuf> {
+  let mut paths: Vec<PathBuf> = Vec::new();
+  for event in Parser::new_ext(markdown_input, markdown_options()) {
+    if let Event::Start(Tag::Image { dest_url, .. }) = event
+      && !is_remote_url(&dest_url)
+    {
+      let path = PathBuf::from(dest_url.to_string());
+      if !paths.contains(&path) {
+        paths.push(path);
+      }
+    }
+  }
+  paths
+}
+
+/// Replaces `{{name}}` placeholders with values from `variables`, Markdown-escaped when
+/// `escape` is set so values such as `A & B <Co>` come out as text rather than markup.
+/// Values inside code spans and code blocks are inserted as they are, since escapes would
+/// show up literally there.
+/// Unknown or empty placeholders are left in place and their names pushed onto `unfilled`.
+pub fn fill_placeholders(
+  input: &str,
+  variables: &BTreeMap<String, String>,
+  escape: bool,
+  unfilled: &mut Vec<String>,
+) -> String {
+  let mut output = String::with_capacity(input.len());
+  let mut rest = input;
+  let code = if escape {
+    code_ranges(input)
+  } else {
+    Vec::new()
+  };
+
+  while let Some(start) = rest.find("{{") {
+    output.push_str(&rest[..start]);
+    let after_open = &rest[start + 2..];
+
+    let Some(end) = after_open.find("}}") else {
+      output.push_str(&rest[start..]);
+      return output;
+    };
+
+    let name = after_open[..end].trim();
+    let placeholder = &rest[start..start + 2 + end + 2];
+
+    if !is_placeholder_name(name) {
+      output.push_str(placeholder);
+    } else {
+      match variables.get(name).filter(|v| !v.is_empty()) {
+        Some(value) if escape => {
+          let offset = input.len() - rest.len() + start;
+          if code.iter().any(|range| range.contains(&offset)) {
+            output.push_str(&value.replace('\r', ""));
+          } else {
+            output.push_str(&escape_markdown(value));
+          }
+        }
+        Some(value) => output.push_str(value),
+        None => {
+          if !unfilled.iter().any(|u| u == name) {
+            unfilled.push(name.to_string());
+          }
+          output.push_str(placeholder);
+        }
+      }
+    }
+
+    rest = &after_open[end + 2..];
+  }
+
+  output.push_str(rest);
+  output
+}
+
+// Byte ranges of the code spans and code blocks in `input`
+fn code_ranges(input: &str) -> Vec<Range<usize>> {
+  Parser::new_ext(input, markdown_options())
+    .into_offset_iter()
+    .filter_map(|(event, range)| match event {
+      Event::Code(_) | Event::Start(Tag::CodeBlock(_)) => Some(range),
+      _ => None,
+    })
+    .collect()
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+  !name.is_empty() && name.chars().all(is_placeholder_char)
+}
+
+pub fn is_placeholder_char(c: char) -> bool {
+  c.is_alphanumeric() || c == '_' || c == '-' || c == '.'
+}
+
+// CommonMark lets any ASCII punctuation be backslash-escaped, which covers inline HTML,
+// entities and emphasis alike. Line breaks would start new blocks, so they become spaces.
+fn escape_markdown(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len());
+  for c in value.chars() {
+    match c {
+      '\r' => {}
+      '\n' => escaped.push(' '),
+      c if c.is_ascii_punctuation() => {
+        escaped.push('\\');
+        escaped.push(c);
+      }
+      c => escaped.push(c),
+    }
   }
+  escaped
 }
 
-fn parse_markdown_with_images(markdown_input: &str) -> (String, Vec<InlineImage>) {
+/// Everything except metadata blocks (which would swallow a body starting with `---`),
+/// math and wikilinks, none of which mean anything in an email.
+pub fn markdown_options() -> Options {
   let mut options = Options::empty();
   options.insert(Options::ENABLE_STRIKETHROUGH);
   options.insert(Options::ENABLE_TABLES);
+  options.insert(Options::ENABLE_FOOTNOTES);
+  options.insert(Options::ENABLE_TASKLISTS);
+  options.insert(Options::ENABLE_SMART_PUNCTUATION);
+  options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+  options.insert(Options::ENABLE_GFM);
+  options.insert(Options::ENABLE_DEFINITION_LIST);
+  options.insert(Options::ENABLE_SUPERSCRIPT);
+  options.insert(Options::ENABLE_SUBSCRIPT);
+  options
+}
 
-  let parser = Parser::new_ext(markdown_input, options);
+/// Renders the body to HTML, pointing local images at `cid:` parts.
+/// Images in `skipped` are dropped, leaving only their alt text.
+/// Code blocks are highlighted and task list checkboxes drawn as characters, since
+/// mail clients strip both stylesheets and form inputs.
+fn parse_markdown_with_images(
+  markdown_input: &str,
+  skipped: &[&Path],
+) -> (String, Vec<InlineImage>) {
+  let parser = Parser::new_ext(markdown_input, markdown_options());
   let mut events = Vec::new();
   let mut inline_images = Vec::new();
+  let mut in_skipped_image = false;
+  let mut code_block: Option<(String, String)> = None;
 
   for event in parser {
     match event {
+      Event::Start(Tag::CodeBlock(kind)) => {
+        let info = match kind {
+          CodeBlockKind::Fenced(info) => info.to_string(),
+          CodeBlockKind::Indented => String::new(),
+        };
+        code_block = Some((info, String::new()));
+      }
+      Event::Text(text) if code_block.is_some() => {
+        if let Some((_, code)) = &mut code_block {
+          code.push_str(&text);
+        }
+      }
+      Event::End(TagEnd::CodeBlock) => {
+        if let Some((info, code)) = code_block.take() {
+          events.push(Event::Html(highlight::code_block_html(&code, &info).into()));
+        }
+      }
+      Event::TaskListMarker(checked) => {
+        let marker = if checked { "&#9745; " } else { "&#9744; " };
+        events.push(Event::InlineHtml(marker.into()));
+      }
+      Event::Start(Tag::Image { ref dest_url, .. })
+        if !is_remote_url(dest_url) && skipped.contains(&Path::new(dest_url.as_ref())) =>
+      {
+        in_skipped_image = true;
+      }
+      Event::End(TagEnd::Image) if in_skipped_image => in_skipped_image = false,
       Event::Start(Tag::Image {
         link_type,
         dest_url,
@@ -57,7 +337,7 @@ fn parse_markdown_with_images(markdown_input: &str) -> (String, Vec<InlineImage>
         id,
       }) => {
         let url_str = dest_url.to_string();
-        if url_str.starts_with("http://") || url_str.starts_with("https://") {
+        if is_remote_url(&url_str) {
           events.push(Event::Start(Tag::Image {
             link_type,
             dest_url,
@@ -84,81 +364,200 @@ fn parse_markdown_with_images(markdown_input: &str) -> (String, Vec<InlineImage>
     }
   }
 
+  assign_heading_ids(&mut events);
+
   let mut html_output = String::new();
   html::push_html(&mut html_output, events.into_iter());
   (html_output, inline_images)
 }
 
-fn strip_markdown(markdown_input: &str) -> String {
-  let parser = Parser::new(markdown_input);
-  let mut plain = String::new();
-  for event in parser {
-    match event {
-      Event::Text(t) => plain.push_str(&t),
-      Event::Code(c) => plain.push_str(&c),
-      Event::SoftBreak | Event::HardBreak => plain.push('\n'),
-      Event::End(TagEnd::Paragraph) => plain.push_str("\n\n"),
-      Event::End(TagEnd::Item) => plain.push('\n'),
-      _ => {}
+/// Gives every heading without an explicit `{#id}` one derived from its text, so
+/// `[see below](#next-steps)` links work. Repeated titles get `-1`, `-2`, ... suffixes.
+fn assign_heading_ids(events: &mut [Event]) {
+  let mut used: Vec<String> = events
+    .iter()
+    .filter_map(|event| match event {
+      Event::Start(Tag::Heading { id: Some(id), .. }) => Some(id.to_string()),
+      _ => None,
+    })
+    .collect();
+
+  for i in 0..events.len() {
+    let Event::Start(Tag::Heading { id: None, .. }) = &events[i] else {
+      continue;
+    };
+    let mut title = String::new();
+    for event in &events[i + 1..] {
+      match event {
+        Event::Text(text) | Event::Code(text) => title.push_str(text),
+        Event::End(TagEnd::Heading(_)) => break,
+        _ => {}
+      }
+    }
+
+    let base = slugify(&title);
+    let mut slug = base.clone();
+    let mut n = 0;
+    while slug.is_empty() || used.contains(&slug) {
+      n += 1;
+      slug = if base.is_empty() {
+        format!("section-{}", n)
+      } else {
+        format!("{}-{}", base, n)
+      };
+    }
+    used.push(slug.clone());
+
+    if let Event::Start(Tag::Heading { id, .. }) = &mut events[i] {
+      *id = Some(slug.into());
     }
   }
-  plain
 }
 
-fn generate_footer(identity: &UserIdentity) -> String {
-  let emails_html = identity
-    .emails
-    .iter()
-    .map(|email| format!(r#"<a href="mailto:{}" style="color: {}; text-decoration: none;">{}</a>"#, email, identity.footer_color, email))
-    .collect::<Vec<String>>()
-    .join(" &bull; ");
+fn slugify(title: &str) -> String {
+  let mut slug = String::new();
+  for c in title.trim().to_lowercase().chars() {
+    if c.is_alphanumeric() || c == '_' {
+      slug.push(c);
+    } else if (c.is_whitespace() || c == '-') && !slug.ends_with('-') {
+      slug.push('-');
+    }
+  }
+  slug.trim_matches('-').to_string()
+}
 
-  format!(
-    r#"
-    <div style="font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; border-left: 4px solid {color}; padding-left: 15px; margin-top: 25px; color: #4a4a4a;">
-      
-      <h3 style="margin: 0; color: #2d3436; font-size: 19px; font-weight: 700; line-height: 1.2;">{name}</h3>
-      
-      <div style="margin-top: 4px; font-size: 13px; color: {color}; font-weight: 600; text-transform: uppercase; letter-spacing: 0.5px;">{role}</div>
-      
-      <div style="margin-top: 2px; font-size: 12px; color: #7f8c8d;">
-        {dept} &bull; {inst}
-      </div>
-
-      <div style="margin-top: 8px; font-size: 12px; color: #95a5a6; line-height: 1.4;">
-        <span style="font-weight: 600;">Tel:</span> {phone}<br>
-        <span style="font-weight: 600;">Email:</span> {emails}
-      </div>
-
-    </div>
-    "#,
-    color = identity.footer_color,
-    name = identity.name,
-    role = identity.role,
-    dept = identity.department,
-    inst = identity.institution,
-    phone = identity.phone,
-    emails = emails_html
-  )
+/// The tracking pixel for `tracking_id`, if one is wanted and the draft may carry it.
+/// Without a secret the pixel could never be verified, so none is added.
+fn tracker(
+  draft: &EmailDraft,
+  config: &AppConfig,
+  consent: &ConsentRegistry,
+  tracking_id: &str,
+) -> Option<String> {
+  (!tracking_id.is_empty()
+    && !config.tracking_secret.is_empty()
+    && tracking_allowed(draft, consent))
+  .then(|| generate_tracker(&config.worker_url, tracking_id, &config.tracking_secret))
 }
 
-fn generate_plain_footer(identity: &UserIdentity) -> String {
+fn generate_tracker(base_url: &str, tracking_id: &str, secret: &str) -> String {
   format!(
-      "{}\n{}\n{} - {}\n\nPhone: {}\nEmail: {}",
-      identity.name,
-      identity.role,
-      identity.department,
-      identity.institution,
-      identity.phone,
-      identity.emails.join(" | ")
+    r#"<img src="{}/pixel.png?id={}&amp;sig={}" alt="" width="1" height="1" border="0" style="width:1px;height:1px;opacity:0.01;" />"#,
+    base_url,
+    tracking_id,
+    tracking::sign(secret, tracking_id)
   )
 }
 
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-fn generate_tracker(base_url: &str, recipient_email: &str) -> String {
-  let encoded_id = general_purpose::URL_SAFE_NO_PAD.encode(recipient_email);
-  format!(
-    r#"<img src="{}/pixel.png?id={}" alt="" width="1" height="1" border="0" style="width:1px;height:1px;opacity:0.01;" />"#,
-    base_url, encoded_id
-  )
+  fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+      .iter()
+      .map(|(k, v)| (k.to_string(), v.to_string()))
+      .collect()
+  }
+
+  fn fill(input: &str, variables: &BTreeMap<String, String>) -> (String, Vec<String>) {
+    let mut unfilled = Vec::new();
+    let output = fill_placeholders(input, variables, true, &mut unfilled);
+    (output, unfilled)
+  }
+
+  #[test]
+  fn fill_placeholders_keeps_unknown_and_empty_placeholders() {
+    let variables = vars(&[("name", "Ada"), ("empty", "")]);
+    let (output, unfilled) = fill(
+      "Hi {{ name }}, {{missing}} {{empty}} {{missing}} {{not a name}} {{open",
+      &variables,
+    );
+
+    assert_eq!(
+      output,
+      "Hi Ada, {{missing}} {{empty}} {{missing}} {{not a name}} {{open"
+    );
+    assert_eq!(unfilled, ["missing", "empty"]);
+  }
+
+  #[test]
+  fn fill_placeholders_escapes_values_outside_code() {
+    let variables = vars(&[("company", "A & B <Co> *Ltd*\r\nUK")]);
+    let (output, _) = fill("Dear {{company}}", &variables);
+    assert_eq!(output, "Dear A \\& B \\<Co\\> \\*Ltd\\* UK");
+
+    let mut unfilled = Vec::new();
+    let raw = fill_placeholders("{{company}}", &variables, false, &mut unfilled);
+    assert_eq!(raw, "A & B <Co> *Ltd*\r\nUK");
+  }
+
+  #[test]
+  fn fill_placeholders_inserts_values_verbatim_inside_code() {
+    let variables = vars(&[("path", "C:\\tmp\\*.log")]);
+    let (output, _) = fill(
+      "Run `ls {{path}}` or {{path}}\n\n```\ncat {{path}}\n```\n",
+      &variables,
+    );
+
+    assert_eq!(
+      output,
+      "Run `ls C:\\tmp\\*.log` or C\\:\\\\tmp\\\\\\*\\.log\n\n```\ncat C:\\tmp\\*.log\n```\n"
+    );
+  }
+
+  #[test]
+  fn headings_get_unique_ids() {
+    let (html, _) = parse_markdown_with_images(
+      "# Intro\n\n## Intro\n\n# Next steps {#intro-1}\n\n# Intro\n\n# !!!\n",
+      &[],
+    );
+
+    assert!(html.contains(r#"<h1 id="intro">Intro</h1>"#), "{}", html);
+    assert!(html.contains(r#"<h2 id="intro-2">Intro</h2>"#), "{}", html);
+    assert!(
+      html.contains(r#"<h1 id="intro-1">Next steps</h1>"#),
+      "{}",
+      html
+    );
+    assert!(html.contains(r#"<h1 id="intro-3">Intro</h1>"#), "{}", html);
+    assert!(html.contains(r#"<h1 id="section-1">!!!</h1>"#), "{}", html);
+  }
+
+  #[test]
+  fn tracker_needs_an_id_a_secret_and_consent() {
+    let config = AppConfig {
+      worker_url: "https://worker.example".to_string(),
+      tracking_secret: "secret".to_string(),
+      ..AppConfig::default()
+    };
+    let mut draft = EmailDraft {
+      to: vec!["ada@example.com".to_string()],
+      tracking: TrackingMode::Consented,
+      ..EmailDraft::default()
+    };
+    let mut consent = ConsentRegistry::default();
+
+    assert_eq!(tracker(&draft, &config, &consent, "abc"), None);
+
+    consent.grant("ada@example.com");
+    let pixel = tracker(&draft, &config, &consent, "abc").unwrap();
+    assert!(pixel.contains(&format!(
+      "https://worker.example/pixel.png?id=abc&amp;sig={}",
+      tracking::sign("secret", "abc")
+    )));
+
+    assert_eq!(tracker(&draft, &config, &consent, ""), None);
+    let without_secret = AppConfig {
+      tracking_secret: String::new(),
+      ..config.clone()
+    };
+    assert_eq!(tracker(&draft, &without_secret, &consent, "abc"), None);
+
+    draft.tracking = TrackingMode::Off;
+    assert_eq!(tracker(&draft, &config, &consent, "abc"), None);
+
+    draft.tracking = TrackingMode::Override;
+    assert!(tracker(&draft, &config, &ConsentRegistry::default(), "abc").is_some());
+  }
 }
@@ -1,12 +1,173 @@
+use crate::enums::{FilePickerMode, SmtpSecurity};
 use crate::models::UserIdentity;
 use serde::{Deserialize, Serialize};
 use std::default::Default;
+use std::sync::LazyLock;
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
-pub struct AppConfig {
+/// Id of the profile a fresh config, or one from before profiles existed, starts with.
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+/// A sender: the identity shown in the signature and the SMTP server and login messages
+/// go out with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SenderProfile {
+  #[serde(default = "new_profile_id")]
+  pub id: String,
+  #[serde(default)]
+  pub name: String,
+  #[serde(default)]
   pub identity: UserIdentity,
+  #[serde(default = "default_smtp_host")]
+  pub smtp_host: String,
+  #[serde(default = "default_smtp_port")]
+  pub smtp_port: u16,
+  #[serde(default)]
+  pub smtp_security: SmtpSecurity,
+  #[serde(default)]
   pub smtp_username: String,
+  #[serde(default)]
   pub smtp_app_password: String,
+  /// Signature for drafts that do not pick one; empty means the default layout.
+  #[serde(default)]
+  pub signature: String,
+}
+
+fn new_profile_id() -> String {
+  uuid::Uuid::new_v4().to_string()
+}
+
+impl SenderProfile {
+  pub fn new(name: &str) -> Self {
+    Self {
+      id: new_profile_id(),
+      name: name.to_string(),
+      identity: UserIdentity::default(),
+      smtp_host: default_smtp_host(),
+      smtp_port: default_smtp_port(),
+      smtp_security: SmtpSecurity::default(),
+      smtp_username: String::new(),
+      smtp_app_password: String::new(),
+      signature: String::new(),
+    }
+  }
+
+  /// "Name <address>", or whichever of the two is set.
+  pub fn label(&self) -> String {
+    match (self.name.trim(), self.smtp_username.trim()) {
+      ("", "") => "(unnamed profile)".to_string(),
+      (name, "") => name.to_string(),
+      ("", address) => address.to_string(),
+      (name, address) => format!("{} <{}>", name, address),
+    }
+  }
+}
+
+impl Default for SenderProfile {
+  fn default() -> Self {
+    Self {
+      id: DEFAULT_PROFILE_ID.to_string(),
+      ..Self::new("Default")
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+  /// Drafts refer to a profile by id and fall back to the first one.
+  #[serde(default = "default_profiles")]
+  pub profiles: Vec<SenderProfile>,
+  /// Column the text/plain part is wrapped at. 0 disables wrapping.
+  #[serde(default = "default_plain_text_width")]
+  pub plain_text_width: usize,
+  #[serde(default = "default_send_rate")]
+  pub send_rate_per_minute: u32,
+  #[serde(default = "default_concurrency")]
+  pub max_concurrent_sends: u32,
+  /// Largest encoded message the mail provider accepts, in MB. 0 disables the check.
+  #[serde(default = "default_message_size_limit")]
+  pub message_size_limit_mb: u32,
   pub worker_url: String,
   pub api_secret: String,
+  /// Key for the signatures on tracking pixel URLs. Generated on first load.
+  #[serde(default)]
+  pub tracking_secret: String,
+  /// Largest scheduling request the worker accepts, in MB. 0 disables the warning.
+  #[serde(default = "default_worker_upload_limit")]
+  pub worker_upload_limit_mb: u32,
+  #[serde(default)]
+  pub file_picker: FilePickerMode,
+}
+
+fn default_profiles() -> Vec<SenderProfile> {
+  vec![SenderProfile::default()]
+}
+
+fn default_plain_text_width() -> usize {
+  72
+}
+
+fn default_smtp_host() -> String {
+  "smtp.gmail.com".to_string()
+}
+
+fn default_smtp_port() -> u16 {
+  465
+}
+
+fn default_send_rate() -> u32 {
+  20
+}
+
+fn default_concurrency() -> u32 {
+  2
+}
+
+// Gmail's limit; most providers sit between 20 and 35 MB.
+fn default_message_size_limit() -> u32 {
+  25
+}
+
+// Cloudflare's request body limit on the Free and Pro plans.
+fn default_worker_upload_limit() -> u32 {
+  100
+}
+
+impl Default for AppConfig {
+  fn default() -> Self {
+    Self {
+      profiles: default_profiles(),
+      plain_text_width: default_plain_text_width(),
+      send_rate_per_minute: default_send_rate(),
+      max_concurrent_sends: default_concurrency(),
+      message_size_limit_mb: default_message_size_limit(),
+      worker_url: String::new(),
+      api_secret: String::new(),
+      tracking_secret: String::new(),
+      worker_upload_limit_mb: default_worker_upload_limit(),
+      file_picker: FilePickerMode::default(),
+    }
+  }
+}
+
+impl AppConfig {
+  /// The profile with `id`, or the first one when no profile has it.
+  pub fn profile(&self, id: &str) -> &SenderProfile {
+    static FALLBACK: LazyLock<SenderProfile> = LazyLock::new(SenderProfile::default);
+    self
+      .profiles
+      .iter()
+      .find(|p| p.id == id)
+      .or(self.profiles.first())
+      .unwrap_or(&FALLBACK)
+  }
+
+  /// The id of the profile after `id`, wrapping around.
+  pub fn next_profile(&self, id: &str) -> String {
+    let current = self.profile(id);
+    let position = self.profiles.iter().position(|p| p.id == current.id);
+    match position {
+      Some(i) => self.profiles[(i + 1) % self.profiles.len()].id.clone(),
+      None => current.id.clone(),
+    }
+  }
 }
@@ -0,0 +1,613 @@
+use crate::models::{
+  Contact, ContactBook, EmailDraft, SentRecord, bare_address, is_group_reference,
+  split_address_list,
+};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+pub struct AddressSuggestion {
+  pub label: String,
+  pub value: String,
+}
+
+/// Contacts whose name or address fuzzily matches `query`, best match first.
+/// A query starting with `@` matches group names instead.
+pub fn suggest(book: &ContactBook, query: &str, limit: usize) -> Vec<AddressSuggestion> {
+  let query = query.trim();
+  if query.is_empty() {
+    return Vec::new();
+  }
+  if let Some(group_query) = query.strip_prefix('@') {
+    return suggest_groups(book, group_query, limit);
+  }
+
+  let mut scored: Vec<(i64, AddressSuggestion)> = book
+    .contacts
+    .iter()
+    .flat_map(|contact| {
+      contact.addresses.iter().filter_map(move |address| {
+        let score = fuzzy_score(query, &contact.name).max(fuzzy_score(query, address))?;
+        let label = if contact.name.is_empty() {
+          address.clone()
+        } else {
+          format!("{} <{}>", contact.name, address)
+        };
+        Some((
+          score,
+          AddressSuggestion {
+            label,
+            value: contact.mailbox(address),
+          },
+        ))
+      })
+    })
+    .collect();
+
+  scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+  scored.into_iter().take(limit).map(|(_, s)| s).collect()
+}
+
+fn suggest_groups(book: &ContactBook, query: &str, limit: usize) -> Vec<AddressSuggestion> {
+  let mut scored: Vec<(i64, AddressSuggestion)> = book
+    .groups
+    .iter()
+    .filter_map(|group| {
+      // A bare `@` lists every group
+      let score = if query.is_empty() {
+        0
+      } else {
+        fuzzy_score(query, &group.name)?
+      };
+      Some((
+        score,
+        AddressSuggestion {
+          label: format!("@{} ({} members)", group.name, group.members.len()),
+          value: format!("@{}", group.name),
+        },
+      ))
+    })
+    .collect();
+
+  scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+  scored.into_iter().take(limit).map(|(_, s)| s).collect()
+}
+
+/// Replaces `@group` entries in the draft's recipient lists with the group's members.
+/// Returns the expanded draft and the names of the groups that were used.
+pub fn expand_groups(draft: &EmailDraft, book: &ContactBook) -> Result<(EmailDraft, Vec<String>)> {
+  let mut expanded = draft.clone();
+  let mut groups = Vec::new();
+
+  for list in [&mut expanded.to, &mut expanded.cc, &mut expanded.bcc] {
+    let mut seen = HashSet::new();
+    let mut addresses = Vec::new();
+    for entry in list.drain(..) {
+      let members = if is_group_reference(&entry) {
+        let group = book
+          .group(&entry)
+          .with_context(|| format!("Unknown recipient group {}", entry.trim()))?;
+        if group.members.is_empty() {
+          anyhow::bail!("Recipient group @{} has no members", group.name);
+        }
+        if !groups.contains(&group.name) {
+          groups.push(group.name.clone());
+        }
+        group.members.clone()
+      } else {
+        vec![entry]
+      };
+
+      for member in members {
+        if seen.insert(bare_address(&member).to_lowercase()) {
+          addresses.push(member);
+        }
+      }
+    }
+    *list = addresses;
+  }
+
+  Ok((expanded, groups))
+}
+
+/// Scores `query` as a case-insensitive subsequence of `candidate`.
+/// Consecutive matches and matches at word boundaries score higher; `None` means no match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+  let query: Vec<char> = query.to_lowercase().chars().collect();
+  let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+  if query.is_empty() {
+    return None;
+  }
+
+  let mut score = 0;
+  let mut qi = 0;
+  let mut last_match: Option<usize> = None;
+  for (ci, c) in candidate.iter().enumerate() {
+    if qi == query.len() {
+      break;
+    }
+    if *c != query[qi] {
+      continue;
+    }
+
+    score += 1;
+    if ci == 0 || !candidate[ci - 1].is_alphanumeric() {
+      score += 8;
+    }
+    match last_match {
+      Some(last) if last + 1 == ci => score += 5,
+      Some(last) => score -= (ci - last - 1).min(5) as i64,
+      None => score -= ci.min(5) as i64,
+    }
+    last_match = Some(ci);
+    qi += 1;
+  }
+
+  (qi == query.len()).then_some(score)
+}
+
+/// Addresses from the Sent archive that are neither saved nor ignored, most recent first.
+pub fn unsaved_recipients(book: &ContactBook, records: &[SentRecord]) -> Vec<String> {
+  let mut seen = HashSet::new();
+  records
+    .iter()
+    .flat_map(|r| r.to.iter().chain(&r.cc).chain(&r.bcc))
+    .filter(|address| !book.is_known(address))
+    .filter(|address| seen.insert(bare_address(address).to_lowercase()))
+    .cloned()
+    .collect()
+}
+
+/// The part of a recipient list after the last separator, i.e. the address being typed.
+pub fn last_token(input: &str) -> &str {
+  let mut in_quotes = false;
+  let mut in_angle = false;
+  let mut start = 0;
+  for (i, c) in input.char_indices() {
+    match c {
+      '"' => in_quotes = !in_quotes,
+      '<' if !in_quotes => in_angle = true,
+      '>' if !in_quotes => in_angle = false,
+      ',' | ';' if !in_quotes && !in_angle => start = i + 1,
+      _ => {}
+    }
+  }
+  input[start..].trim_start()
+}
+
+/// What an import did to the address book.
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+  pub added: usize,
+  pub updated: usize,
+  pub duplicates: usize,
+  pub skipped: usize,
+}
+
+impl ImportSummary {
+  pub fn describe(&self) -> String {
+    format!(
+      "Imported {} new, updated {}, {} duplicates, {} skipped (no address)",
+      self.added, self.updated, self.duplicates, self.skipped
+    )
+  }
+}
+
+/// Reads contacts from a `.vcf` or `.csv` file, chosen by extension.
+pub fn read_contacts_file(path: &Path) -> Result<Vec<Contact>> {
+  let content =
+    fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?;
+  let extension = path
+    .extension()
+    .map(|e| e.to_string_lossy().to_lowercase())
+    .unwrap_or_default();
+
+  match extension.as_str() {
+    "vcf" | "vcard" => Ok(parse_vcards(&content)),
+    "csv" => parse_contacts_csv(&content),
+    _ => anyhow::bail!("Unsupported contacts file (expected .vcf or .csv)"),
+  }
+}
+
+/// Adds `imported` to `book`. A contact sharing an address with an existing one is a
+/// duplicate: its extra addresses and any fields the existing contact lacks are merged in.
+pub fn merge_contacts(book: &mut ContactBook, imported: Vec<Contact>) -> ImportSummary {
+  let mut summary = ImportSummary::default();
+
+  for contact in imported {
+    if contact.addresses.is_empty() {
+      summary.skipped += 1;
+      continue;
+    }
+
+    let existing = book
+      .contacts
+      .iter_mut()
+      .find(|c| contact.addresses.iter().any(|a| c.has_address(a)));
+    let Some(existing) = existing else {
+      book.contacts.push(contact);
+      summary.added += 1;
+      continue;
+    };
+
+    let mut changed = false;
+    for address in contact.addresses {
+      if !existing.has_address(&address) {
+        existing.addresses.push(address);
+        changed = true;
+      }
+    }
+    for (field, value) in [
+      (&mut existing.name, contact.name),
+      (&mut existing.timezone, contact.timezone),
+      (&mut existing.notes, contact.notes),
+    ] {
+      if field.is_empty() && !value.is_empty() {
+        *field = value;
+        changed = true;
+      }
+    }
+
+    if changed {
+      summary.updated += 1;
+    } else {
+      summary.duplicates += 1;
+    }
+  }
+
+  book.contacts.sort_by_key(|c| c.name.to_lowercase());
+  summary
+}
+
+/// Parses every `BEGIN:VCARD` ... `END:VCARD` block (vCard 3.0 and 4.0).
+pub fn parse_vcards(input: &str) -> Vec<Contact> {
+  let mut contacts = Vec::new();
+  let mut current: Option<VCardFields> = None;
+
+  for line in unfold_lines(input) {
+    let Some((head, value)) = line.split_once(':') else {
+      continue;
+    };
+    let mut params = head.split(';');
+    let name = params.next().unwrap_or_default();
+    // Apple exports prefix properties with a group, e.g. `item1.EMAIL`
+    let name = name.rsplit('.').next().unwrap_or(name).to_uppercase();
+
+    match name.as_str() {
+      "BEGIN" if value.eq_ignore_ascii_case("VCARD") => current = Some(VCardFields::default()),
+      "END" if value.eq_ignore_ascii_case("VCARD") => {
+        if let Some(fields) = current.take() {
+          contacts.push(fields.into_contact());
+        }
+      }
+      _ => {
+        if let Some(fields) = current.as_mut() {
+          fields.set(&name, value);
+        }
+      }
+    }
+  }
+
+  contacts
+}
+
+#[derive(Default)]
+struct VCardFields {
+  formatted_name: String,
+  structured_name: String,
+  addresses: Vec<String>,
+  timezone: String,
+  notes: String,
+}
+
+impl VCardFields {
+  /// `value` is still escaped so structured values can be split before unescaping.
+  fn set(&mut self, property: &str, value: &str) {
+    if property == "N" {
+      // Family;Given;Additional;Prefix;Suffix, each of which may be a comma separated list
+      let component = |idx: usize| {
+        split_unescaped(value, ';')
+          .get(idx)
+          .map(|c| {
+            split_unescaped(c, ',')
+              .into_iter()
+              .map(|v| unescape_vcard(v).trim().to_string())
+              .filter(|v| !v.is_empty())
+              .collect::<Vec<_>>()
+              .join(" ")
+          })
+          .unwrap_or_default()
+      };
+      self.structured_name = format!("{} {}", component(1), component(0))
+        .trim()
+        .to_string();
+      return;
+    }
+
+    let value = unescape_vcard(value);
+    let value = value.trim();
+    match property {
+      "FN" => self.formatted_name = value.to_string(),
+      "EMAIL" => {
+        let address = value.trim_start_matches("mailto:");
+        if !address.is_empty() {
+          self.addresses.push(address.to_string());
+        }
+      }
+      "TZ" => self.timezone = parse_timezone(value),
+      "NOTE" => self.notes = value.replace('\n', " "),
+      _ => {}
+    }
+  }
+
+  fn into_contact(self) -> Contact {
+    let name = if self.formatted_name.is_empty() {
+      self.structured_name
+    } else {
+      self.formatted_name
+    };
+    let mut contact = Contact::new(name, self.addresses);
+    contact.timezone = self.timezone;
+    contact.notes = self.notes;
+    contact
+  }
+}
+
+/// Joins folded lines (continuations start with a space or tab) and drops blank ones.
+fn unfold_lines(input: &str) -> Vec<String> {
+  let mut lines: Vec<String> = Vec::new();
+  for raw in input.lines() {
+    let raw = raw.trim_end_matches('\r');
+    match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+      (Some(continuation), Some(last)) => last.push_str(continuation),
+      _ if raw.trim().is_empty() => {}
+      _ => lines.push(raw.to_string()),
+    }
+  }
+  lines
+}
+
+/// Splits on `separator` unless it is escaped with a backslash; escapes are kept.
+fn split_unescaped(value: &str, separator: char) -> Vec<&str> {
+  let mut parts = Vec::new();
+  let mut start = 0;
+  let mut escaped = false;
+  for (idx, c) in value.char_indices() {
+    match c {
+      _ if escaped => escaped = false,
+      '\\' => escaped = true,
+      _ if c == separator => {
+        parts.push(&value[start..idx]);
+        start = idx + c.len_utf8();
+      }
+      _ => {}
+    }
+  }
+  parts.push(&value[start..]);
+  parts
+}
+
+fn unescape_vcard(value: &str) -> String {
+  let mut output = String::with_capacity(value.len());
+  let mut chars = value.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      output.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('n' | 'N') => output.push('\n'),
+      Some(other) => output.push(other),
+      None => output.push('\\'),
+    }
+  }
+  output
+}
+
+fn escape_vcard(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace(',', "\\,")
+    .replace(';', "\\;")
+    .replace('\n', "\\n")
+}
+
+/// Only IANA names are kept; UTC offsets such as `-05:00` cannot be mapped to a region.
+fn parse_timezone(value: &str) -> String {
+  value
+    .trim()
+    .parse::<chrono_tz::Tz>()
+    .map(|tz| tz.name().to_string())
+    .unwrap_or_default()
+}
+
+/// Reads a spreadsheet export. Name, email, timezone and notes columns are found by header,
+/// which covers the usual Google, Outlook and hand-made layouts.
+pub fn parse_contacts_csv(input: &str) -> Result<Vec<Contact>> {
+  let mut reader = csv::ReaderBuilder::new()
+    .flexible(true)
+    .trim(csv::Trim::All)
+    .from_reader(input.as_bytes());
+
+  let headers: Vec<String> = reader
+    .headers()
+    .context("CSV has no header row")?
+    .iter()
+    .map(|h| h.to_lowercase())
+    .collect();
+  let find = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+
+  let name_column = find(&["name", "full name", "display name"]);
+  let first_column = find(&["first name", "given name"]);
+  let last_column = find(&["last name", "family name", "surname"]);
+  let timezone_column = find(&["timezone", "time zone", "tz"]);
+  let notes_column = find(&["notes", "note"]);
+  let email_columns: Vec<usize> = headers
+    .iter()
+    .enumerate()
+    .filter(|(_, h)| h.contains("mail") && !h.contains("type") && !h.contains("label"))
+    .map(|(i, _)| i)
+    .collect();
+  if email_columns.is_empty() {
+    anyhow::bail!("CSV has no email column");
+  }
+
+  let mut contacts = Vec::new();
+  for (idx, record) in reader.records().enumerate() {
+    let record = record.with_context(|| format!("Malformed CSV row {}", idx + 2))?;
+    let cell = |column: Option<usize>| column.and_then(|c| record.get(c)).unwrap_or_default();
+
+    let name = match cell(name_column) {
+      "" => format!("{} {}", cell(first_column), cell(last_column))
+        .trim()
+        .to_string(),
+      name => name.to_string(),
+    };
+    // Google packs several addresses into one cell separated by " ::: "
+    let addresses = email_columns
+      .iter()
+      .flat_map(|c| record.get(*c).unwrap_or_default().split(":::"))
+      .flat_map(split_address_list)
+      .map(|a| bare_address(&a).to_string())
+      .filter(|a| a.contains('@'))
+      .collect();
+
+    let mut contact = Contact::new(name, addresses);
+    contact.timezone = parse_timezone(cell(timezone_column));
+    contact.notes = cell(notes_column).to_string();
+    contacts.push(contact);
+  }
+
+  Ok(contacts)
+}
+
+/// Serialises the address book as vCard 4.0, one card per contact.
+pub fn to_vcards(book: &ContactBook) -> String {
+  let mut output = String::new();
+  for contact in &book.contacts {
+    let mut lines = vec![
+      "BEGIN:VCARD".to_string(),
+      "VERSION:4.0".to_string(),
+      format!("UID:urn:uuid:{}", contact.id),
+      format!("FN:{}", escape_vcard(&contact.name)),
+    ];
+    for (i, address) in contact.addresses.iter().enumerate() {
+      let pref = if i == 0 { ";PREF=1" } else { "" };
+      lines.push(format!("EMAIL{}:{}", pref, escape_vcard(address)));
+    }
+    if !contact.timezone.is_empty() {
+      lines.push(format!("TZ;VALUE=text:{}", escape_vcard(&contact.timezone)));
+    }
+    if !contact.notes.is_empty() {
+      lines.push(format!("NOTE:{}", escape_vcard(&contact.notes)));
+    }
+    lines.push("END:VCARD".to_string());
+
+    for line in lines {
+      output.push_str(&fold_line(&line));
+      output.push_str("\r\n");
+    }
+  }
+  output
+}
+
+/// Folds a content line at 75 octets without splitting a UTF-8 character.
+fn fold_line(line: &str) -> String {
+  let mut output = String::with_capacity(line.len());
+  let mut width = 0;
+  for c in line.chars() {
+    if width + c.len_utf8() > 75 {
+      output.push_str("\r\n ");
+      width = 1;
+    }
+    output.push(c);
+    width += c.len_utf8();
+  }
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn card(lines: &[&str]) -> Contact {
+    let input = format!("BEGIN:VCARD\r\n{}\r\nEND:VCARD\r\n", lines.join("\r\n"));
+    let mut contacts = parse_vcards(&input);
+    assert_eq!(contacts.len(), 1);
+    contacts.remove(0)
+  }
+
+  #[test]
+  fn parse_vcards_unfolds_continuation_lines() {
+    let contact = card(&[
+      "FN:Jane",
+      "  Doe",
+      "EMAIL:jane@exa",
+      "\tmple.com",
+      "NOTE:met at the",
+      "  conference",
+    ]);
+
+    assert_eq!(contact.name, "Jane Doe");
+    assert_eq!(contact.addresses, ["jane@example.com"]);
+    assert_eq!(contact.notes, "met at the conference");
+  }
+
+  #[test]
+  fn parse_vcards_unescapes_values() {
+    let contact = card(&[
+      r"FN:Doe\, Jane\; PhD \\ Team",
+      r"NOTE:line one\nline two",
+      "EMAIL:jane@example.com",
+    ]);
+
+    assert_eq!(contact.name, "Doe, Jane; PhD \\ Team");
+    assert_eq!(contact.notes, "line one line two");
+  }
+
+  #[test]
+  fn parse_vcards_splits_structured_name_before_unescaping() {
+    let contact = card(&[r"N:Doe\;Smith;Jane;;;", "EMAIL:jane@example.com"]);
+    assert_eq!(contact.name, "Jane Doe;Smith");
+
+    let contact = card(&[r"N:O'Brien;Mary,Ann\, Jr;;;", "EMAIL:mary@example.com"]);
+    assert_eq!(contact.name, "Mary Ann, Jr O'Brien");
+  }
+
+  #[test]
+  fn parse_vcards_keeps_every_email_and_prefers_fn() {
+    let contact = card(&[
+      "N:Doe;Jane;;;",
+      "FN:Jane Doe",
+      "item1.EMAIL;TYPE=work:jane@work.example",
+      "EMAIL;TYPE=home:mailto:jane@home.example",
+    ]);
+
+    assert_eq!(contact.name, "Jane Doe");
+    assert_eq!(
+      contact.addresses,
+      ["jane@work.example", "jane@home.example"]
+    );
+  }
+
+  #[test]
+  fn to_vcards_round_trips_through_the_parser() {
+    let mut contact = Contact::new(
+      "Doe, Jane; \"JD\"".to_string(),
+      vec!["jane@example.com".to_string(), "jd@example.com".to_string()],
+    );
+    contact.notes = "x".repeat(120);
+    let book = ContactBook {
+      contacts: vec![contact],
+      ..Default::default()
+    };
+
+    let parsed = parse_vcards(&to_vcards(&book));
+
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[0].name, "Doe, Jane; \"JD\"");
+    assert_eq!(parsed[0].addresses, book.contacts[0].addresses);
+    assert_eq!(parsed[0].notes, book.contacts[0].notes);
+  }
+}
@@ -13,18 +13,84 @@ pub enum CurrentPage {
   Dashboard,
   Schedule,
   Scheduled,
+  Drafts,
+  Merge,
+  Outbox,
+  Sent,
+  Contacts,
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum ComposeField {
+  From,
   #[default]
   Recipient,
+  Cc,
+  Bcc,
   Subject,
+  Variables,
   Body,
   Attachments,
   SendButton,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ContactsFocus {
+  #[default]
+  Contacts,
+  Groups,
+  Suggestions,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ContactField {
+  #[default]
+  Name,
+  Addresses,
+  Timezone,
+  Notes,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GroupField {
+  #[default]
+  Name,
+  Members,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DraftsFocus {
+  #[default]
+  Drafts,
+  Templates,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MergeFocus {
+  #[default]
+  CsvPath,
+  AddressColumn,
+  Rows,
+  SendButton,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeRowStatus {
+  Pending,
+  InFlight,
+  Retrying(String),
+  Sent,
+  Scheduled,
+  Failed(String),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleTarget {
+  #[default]
+  Draft,
+  Merge,
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum ScheduleField {
   #[default]
@@ -42,10 +108,19 @@ pub enum ScheduleField {
 pub enum ScheduledFocus {
   #[default]
   FilterRecipient,
+  FilterGroup,
   FilterStatus,
   List,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum OutboxStatus {
+  #[default]
+  Queued,
+  Retrying,
+  Failed,
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum JobStatus {
   #[default]
@@ -58,6 +133,8 @@ pub enum JobStatus {
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum ConfigField {
   #[default]
+  Profile,
+  ProfileName,
   Name,
   Role,
   Department,
@@ -65,21 +142,68 @@ pub enum ConfigField {
   Phone,
   Emails,
   FooterColor,
+  Signature,
   SmtpUser,
   SmtpPass,
+  SmtpHost,
+  SmtpPort,
+  SmtpSecurity,
+  PlainTextWidth,
+  SendRate,
+  SendConcurrency,
+  MessageSizeLimit,
   WorkerUrl,
   ApiSecret,
+  TrackingSecret,
+  WorkerUploadLimit,
+  FilePicker,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SmtpSecurity {
+  #[default]
+  ImplicitTls,
+  StartTls,
+  Plaintext,
+}
+
+/// Which file chooser `Ctrl+o` opens. `Auto` uses the built-in one when no display server is available.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FilePickerMode {
+  #[default]
+  Auto,
+  Terminal,
+  System,
+}
+
+/// What the files chosen in the file picker are used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerTarget {
+  Attachments,
+  InlineImages,
+  ContactImport,
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum DashboardFocus {
   #[default]
   FilterRecipient,
+  FilterGroup,
   FilterCountry,
   FilterMinOpens,
   List,
 }
 
+/// Whether a draft carries a read-tracking pixel. `Consented` only embeds it when every
+/// recipient is in the consent registry; `Override` embeds it regardless.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TrackingMode {
+  #[default]
+  Off,
+  Consented,
+  Override,
+}
+
 #[derive(Debug, Clone)]
 pub enum Notification {
   Info(String),
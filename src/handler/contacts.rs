@@ -0,0 +1,148 @@
+use crate::app::App;
+use crate::enums::{ContactsFocus, InputMode, Notification, PickerTarget};
+use crate::handler::{Action, helper};
+use crossterm::event::{KeyCode, KeyEvent};
+use tokio::sync::mpsc;
+
+pub async fn handle_contacts_events(
+  key: KeyEvent,
+  app: &mut App,
+  tx: mpsc::Sender<Action>,
+) -> bool {
+  match app.input_mode {
+    InputMode::Normal => handle_contacts_normal_input(key, app, tx),
+    InputMode::Editing => handle_form_input(key, app),
+  }
+}
+
+fn handle_contacts_normal_input(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Action>) -> bool {
+  // Deleting takes two presses of `x` in a row; any other key cancels
+  let pending_delete = app.contacts.pending_delete.take();
+  match key.code {
+    KeyCode::Char('q') => {
+      app.quit();
+      false
+    }
+    KeyCode::Char(c @ '1'..='9') => {
+      helper::switch_page(app, tx, c);
+      false
+    }
+    KeyCode::Tab | KeyCode::BackTab => {
+      app.cycle_field();
+      true
+    }
+    KeyCode::Down => {
+      app.contacts.select_next();
+      true
+    }
+    KeyCode::Up => {
+      app.contacts.select_previous();
+      true
+    }
+    KeyCode::Char('n') => {
+      app.contacts.open_new();
+      app.toggle_editing();
+      true
+    }
+    KeyCode::Enter => {
+      let opened = match app.contacts.focus {
+        ContactsFocus::Contacts => app.contacts.open_selected(),
+        ContactsFocus::Groups => app.contacts.open_selected_group(),
+        ContactsFocus::Suggestions => app.contacts.open_suggestion(),
+      };
+      if opened {
+        app.toggle_editing();
+      }
+      true
+    }
+    KeyCode::Char('i') => {
+      helper::pick_files(app, PickerTarget::ContactImport);
+      true
+    }
+    KeyCode::Char('e') => {
+      match app.contacts.export() {
+        Ok(path) => app.set_notification(Notification::Success(format!(
+          "Exported {} contacts to {}",
+          app.contacts.book.contacts.len(),
+          path.display()
+        ))),
+        Err(e) => app.set_notification(Notification::Error(format!("Export failed: {:#}", e))),
+      }
+      true
+    }
+    KeyCode::Char('t') if app.contacts.focus == ContactsFocus::Contacts => {
+      match app.contacts.toggle_consent() {
+        Ok(Some(true)) => app.set_notification(Notification::Success(
+          "Tracking consent recorded".to_string(),
+        )),
+        Ok(Some(false)) => {
+          app.set_notification(Notification::Info("Tracking consent revoked".to_string()))
+        }
+        Ok(None) => {}
+        Err(e) => app.set_notification(Notification::Error(e.to_string())),
+      }
+      true
+    }
+    KeyCode::Char('x') if app.contacts.focus != ContactsFocus::Suggestions => {
+      let target = match app.contacts.focus {
+        ContactsFocus::Groups => app
+          .contacts
+          .selected_group()
+          .map(|g| (format!("@{}", g.name), format!("@{}", g.name))),
+        _ => app.contacts.selected().map(|c| match c.addresses.first() {
+          Some(address) if c.name.is_empty() => (c.id.clone(), address.clone()),
+          _ => (c.id.clone(), c.name.clone()),
+        }),
+      };
+      let Some((key, label)) = target else {
+        return true;
+      };
+      if pending_delete.as_deref() != Some(key.as_str()) {
+        app.contacts.pending_delete = Some(key);
+        app.set_notification(Notification::Info(format!(
+          "Press x again to delete '{}'",
+          label
+        )));
+        return true;
+      }
+      let result = match app.contacts.focus {
+        ContactsFocus::Groups => app.contacts.delete_selected_group(),
+        _ => app.contacts.delete_selected(),
+      };
+      if let Err(e) = result {
+        app.set_notification(Notification::Error(e.to_string()));
+      }
+      true
+    }
+    KeyCode::Char('x') => {
+      if let Err(e) = app.contacts.ignore_selected_suggestion() {
+        app.set_notification(Notification::Error(e.to_string()));
+      }
+      true
+    }
+    _ => false,
+  }
+}
+
+fn handle_form_input(key: KeyEvent, app: &mut App) -> bool {
+  match key.code {
+    KeyCode::Esc => app.toggle_editing(),
+    KeyCode::Enter => match app.contacts.submit() {
+      Ok(_) => {
+        app.toggle_editing();
+        let saved = match app.contacts.focus {
+          ContactsFocus::Groups => "Group saved",
+          _ => "Contact saved",
+        };
+        app.set_notification(Notification::Success(saved.to_string()));
+      }
+      Err(e) => app.set_notification(Notification::Error(e.to_string())),
+    },
+    KeyCode::Tab => app.contacts.cycle_form_field(true),
+    KeyCode::BackTab => app.contacts.cycle_form_field(false),
+    KeyCode::Char(c) => app.push_input(c),
+    KeyCode::Backspace => app.pop_input(),
+    _ => {}
+  }
+  true
+}
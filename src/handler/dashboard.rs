@@ -1,6 +1,6 @@
 use crate::app::App;
 use crate::client;
-use crate::enums::{CurrentPage, DashboardFocus, InputMode, Notification};
+use crate::enums::{DashboardFocus, InputMode, Notification};
 use crate::handler::{Action, helper};
 use crossterm::event::{KeyCode, KeyEvent};
 use tokio::sync::mpsc;
@@ -62,20 +62,8 @@ fn handle_dashboard_normal_input(key: KeyEvent, app: &mut App, tx: mpsc::Sender<
       app.cycle_field_backward();
       true
     }
-    KeyCode::Char('1') => {
-      app.current_page = CurrentPage::Compose;
-      false
-    }
-    KeyCode::Char('2') => {
-      app.current_page = CurrentPage::Config;
-      false
-    }
-    KeyCode::Char('3') => {
-      helper::trigger_fetch(app, tx);
-      false
-    }
-    KeyCode::Char('4') => {
-      helper::trigger_fetch_jobs(app, tx);
+    KeyCode::Char(c @ '1'..='9') => {
+      helper::switch_page(app, tx, c);
       false
     }
     KeyCode::Char('r') => {
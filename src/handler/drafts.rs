@@ -0,0 +1,206 @@
+use crate::app::App;
+use crate::enums::{CurrentPage, DraftsFocus, InputMode, Notification};
+use crate::handler::{Action, helper};
+use crate::models::EmailDraft;
+use crate::storage::Storage;
+use crossterm::event::{KeyCode, KeyEvent};
+use tokio::sync::mpsc;
+
+pub async fn handle_drafts_events(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Action>) -> bool {
+  match app.input_mode {
+    InputMode::Normal => handle_drafts_normal_input(key, app, tx),
+    InputMode::Editing => handle_rename_input(key, app),
+  }
+}
+
+fn handle_drafts_normal_input(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Action>) -> bool {
+  // Deleting takes two presses of `x` in a row; any other key cancels
+  let pending_delete = app.drafts.pending_delete.take();
+  match key.code {
+    KeyCode::Char('q') => {
+      app.quit();
+      false
+    }
+    KeyCode::Char(c @ '1'..='9') => {
+      helper::switch_page(app, tx, c);
+      false
+    }
+    KeyCode::Tab | KeyCode::BackTab => {
+      app.cycle_field();
+      true
+    }
+    KeyCode::Down => {
+      app.drafts.select_next();
+      true
+    }
+    KeyCode::Up => {
+      app.drafts.select_previous();
+      true
+    }
+    KeyCode::Enter => {
+      match app.drafts.focus {
+        DraftsFocus::Drafts => {
+          if let Some(draft) = app.drafts.selected().cloned() {
+            if draft.id != app.compose.draft.id {
+              app.open_draft(draft);
+            }
+            app.current_page = CurrentPage::Compose;
+          }
+        }
+        DraftsFocus::Templates => {
+          if let Some(template) = app.drafts.selected_template() {
+            let draft = EmailDraft::from_template(template);
+            app.open_draft(draft);
+            app.current_page = CurrentPage::Compose;
+            app.set_notification(Notification::Success(
+              "New draft created from template".to_string(),
+            ));
+          }
+        }
+      }
+      false
+    }
+    KeyCode::Char('n') => {
+      app.open_draft(EmailDraft::default());
+      app.current_page = CurrentPage::Compose;
+      app.set_notification(Notification::Success("New draft created".to_string()));
+      false
+    }
+    KeyCode::Char('c') if app.drafts.focus == DraftsFocus::Drafts => {
+      if let Some(draft) = app.drafts.selected() {
+        let copy = draft.duplicate();
+        match Storage::save_draft(&copy) {
+          Ok(_) => {
+            app.refresh_drafts();
+            app.drafts.select_id(&copy.id);
+            app.set_notification(Notification::Success("Draft duplicated".to_string()));
+          }
+          Err(e) => app.set_notification(Notification::Error(e.to_string())),
+        }
+      }
+      true
+    }
+    KeyCode::Char('t') if app.drafts.focus == DraftsFocus::Drafts => {
+      if let Some(draft) = app.drafts.selected() {
+        let template = draft.to_template();
+        match Storage::save_template(&template) {
+          Ok(_) => {
+            app.refresh_drafts();
+            app.drafts.select_template_id(&template.id);
+            app.set_notification(Notification::Success(format!(
+              "Saved '{}' as a template",
+              template.display_name()
+            )));
+          }
+          Err(e) => app.set_notification(Notification::Error(e.to_string())),
+        }
+      }
+      true
+    }
+    KeyCode::Char('r') => {
+      if let Some(entry) = app.drafts.focused() {
+        app.drafts.rename_input = entry.name.clone();
+        app.toggle_editing();
+      }
+      true
+    }
+    KeyCode::Char('x') => {
+      let Some(entry) = app.drafts.focused().cloned() else {
+        return true;
+      };
+      if pending_delete.as_deref() != Some(entry.id.as_str()) {
+        app.drafts.pending_delete = Some(entry.id.clone());
+        app.set_notification(Notification::Info(format!(
+          "Press x again to delete '{}'",
+          entry.display_name()
+        )));
+        return true;
+      }
+      match app.drafts.focus {
+        DraftsFocus::Drafts => delete_selected_draft(app),
+        DraftsFocus::Templates => {
+          if let Some(template) = app.drafts.selected_template().cloned() {
+            match Storage::delete_template(&template.id) {
+              Ok(_) => {
+                app.refresh_drafts();
+                app.set_notification(Notification::Info(format!(
+                  "Deleted template '{}'",
+                  template.display_name()
+                )));
+              }
+              Err(e) => app.set_notification(Notification::Error(e.to_string())),
+            }
+          }
+        }
+      }
+      true
+    }
+    _ => false,
+  }
+}
+
+fn delete_selected_draft(app: &mut App) {
+  let Some(draft) = app.drafts.selected().cloned() else {
+    return;
+  };
+  if let Err(e) = Storage::delete_draft(&draft.id) {
+    app.set_notification(Notification::Error(e.to_string()));
+    return;
+  }
+
+  if draft.id == app.compose.draft.id {
+    // Keep something open in Compose; fall back to a blank draft
+    let next = app
+      .drafts
+      .drafts
+      .iter()
+      .find(|d| d.id != draft.id)
+      .cloned()
+      .unwrap_or_default();
+    app.load_draft(next);
+  }
+
+  app.refresh_drafts();
+  app.set_notification(Notification::Info(format!(
+    "Deleted '{}'",
+    draft.display_name()
+  )));
+}
+
+fn handle_rename_input(key: KeyEvent, app: &mut App) -> bool {
+  match key.code {
+    KeyCode::Esc => {
+      app.drafts.rename_input.clear();
+      app.toggle_editing();
+    }
+    KeyCode::Enter => {
+      let name = app.drafts.rename_input.trim().to_string();
+      match app.drafts.focus {
+        DraftsFocus::Drafts => {
+          if let Some(mut draft) = app.drafts.selected().cloned() {
+            if draft.id == app.compose.draft.id {
+              app.compose.draft.name = name;
+              app.save_draft();
+            } else {
+              draft.name = name;
+              let _ = Storage::save_draft(&draft);
+            }
+          }
+        }
+        DraftsFocus::Templates => {
+          if let Some(mut template) = app.drafts.selected_template().cloned() {
+            template.name = name;
+            let _ = Storage::save_template(&template);
+          }
+        }
+      }
+      app.refresh_drafts();
+      app.drafts.rename_input.clear();
+      app.toggle_editing();
+    }
+    KeyCode::Char(c) => app.push_input(c),
+    KeyCode::Backspace => app.pop_input(),
+    _ => {}
+  }
+  true
+}
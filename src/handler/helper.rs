@@ -1,11 +1,213 @@
 use crate::app::App;
-use crate::client;
-use crate::enums::Notification;
+use crate::app::picker::FilePickerState;
+use crate::config::AppConfig;
+use crate::enums::{CurrentPage, FilePickerMode, Notification, PickerTarget};
 use crate::handler::Action;
+use crate::models::{EmailDraft, TrackedMessage};
+use crate::size::{self, SizeEstimate};
+use crate::storage::Storage;
+use crate::{attachments, client, compiler, mailer};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use crossterm::{
+  execute,
+  terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use directories::UserDirs;
+use std::fs;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tokio::sync::mpsc;
 
+/// Global page navigation shared by every page's normal-mode key handler.
+pub fn switch_page(app: &mut App, tx: mpsc::Sender<Action>, key: char) {
+  match key {
+    '1' => app.current_page = CurrentPage::Compose,
+    '2' => app.current_page = CurrentPage::Config,
+    '3' => {
+      app.current_page = CurrentPage::Dashboard;
+      trigger_fetch(app, tx);
+    }
+    '4' => trigger_fetch_jobs(app, tx),
+    '5' => app.show_drafts(),
+    '6' => app.current_page = CurrentPage::Merge,
+    '7' => app.show_outbox(),
+    '8' => app.show_sent(),
+    '9' => app.show_contacts(),
+    _ => {}
+  }
+}
+
+/// Opens the configured file chooser for `target`. The system dialog returns immediately;
+/// the built-in picker is drawn over the current page and applies its result when confirmed.
+pub fn pick_files(app: &mut App, target: PickerTarget) {
+  let use_terminal = match app.config.data.file_picker {
+    FilePickerMode::Terminal => true,
+    FilePickerMode::System => false,
+    FilePickerMode::Auto => !has_display_server(),
+  };
+
+  if use_terminal {
+    let start_dir = picker_start_dir(app, target);
+    app.picker = Some(FilePickerState::new(target, start_dir));
+    return;
+  }
+
+  let _ = disable_raw_mode();
+  let _ = execute!(io::stdout(), LeaveAlternateScreen);
+  let dialog = rfd::FileDialog::new();
+  let files = match target {
+    PickerTarget::Attachments => dialog.set_title("Select Attachments").pick_files(),
+    PickerTarget::InlineImages => dialog
+      .set_title("Select Images")
+      .add_filter("Images", &["png", "jpg", "jpeg", "gif", "webp", "svg"])
+      .pick_files(),
+    PickerTarget::ContactImport => dialog
+      .set_title("Import Contacts")
+      .add_filter("Contacts", &["vcf", "vcard", "csv"])
+      .pick_file()
+      .map(|path| vec![path]),
+  };
+  let _ = enable_raw_mode();
+  let _ = execute!(io::stdout(), EnterAlternateScreen);
+
+  if let Some(paths) = files {
+    apply_picked_files(app, target, paths);
+  }
+}
+
+pub fn apply_picked_files(app: &mut App, target: PickerTarget, paths: Vec<PathBuf>) {
+  match target {
+    PickerTarget::Attachments => {
+      let added = app.compose.add_attachments(paths);
+      app.save_draft();
+      app.set_notification(Notification::Success(format!("Added {} files", added)));
+    }
+    PickerTarget::InlineImages => {
+      let body = &mut app.compose.draft.body;
+      for path in &paths {
+        let alt = path
+          .file_stem()
+          .map(|s| s.to_string_lossy().to_string())
+          .unwrap_or_default();
+        let dest = path.to_string_lossy();
+        // Angle brackets keep paths with spaces intact in Markdown
+        let dest = if dest.contains(' ') {
+          format!("<{}>", dest)
+        } else {
+          dest.to_string()
+        };
+        if !body.is_empty() && !body.ends_with('\n') {
+          body.push('\n');
+        }
+        body.push_str(&format!("\n![{}]({})\n", alt, dest));
+      }
+      app.save_draft();
+      app.set_notification(Notification::Success(format!(
+        "Inserted {} images into the body",
+        paths.len()
+      )));
+    }
+    PickerTarget::ContactImport => {
+      let Some(path) = paths.first() else {
+        return;
+      };
+      match app.contacts.import(path) {
+        Ok(summary) => app.set_notification(Notification::Success(summary.describe())),
+        Err(e) => app.set_notification(Notification::Error(format!("Import failed: {:#}", e))),
+      }
+    }
+  }
+}
+
+/// Whether a graphical file dialog can be shown. Over SSH, or on a Unix console
+/// without X11/Wayland, it cannot.
+fn has_display_server() -> bool {
+  let set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+  if cfg!(any(target_os = "windows", target_os = "macos")) {
+    !set("SSH_CONNECTION") && !set("SSH_TTY")
+  } else {
+    set("DISPLAY") || set("WAYLAND_DISPLAY")
+  }
+}
+
+fn picker_start_dir(app: &App, target: PickerTarget) -> PathBuf {
+  let home = || UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+  let last_attachment = app
+    .compose
+    .draft
+    .attachments
+    .last()
+    .and_then(|p| p.parent())
+    .filter(|dir| dir.is_dir())
+    .map(Path::to_path_buf);
+
+  match target {
+    PickerTarget::Attachments => last_attachment.or_else(home),
+    PickerTarget::InlineImages | PickerTarget::ContactImport => home(),
+  }
+  .or_else(|| std::env::current_dir().ok())
+  .unwrap_or_else(|| PathBuf::from("/"))
+}
+
+/// Compiles `draft` and hands it to the worker for delivery at `scheduled_at`.
+pub async fn schedule_draft(
+  config: AppConfig,
+  draft: EmailDraft,
+  scheduled_at: DateTime<Utc>,
+) -> Result<()> {
+  let (draft, groups) = mailer::expand_recipients(&draft)?;
+  let tracking_id = compiler::new_tracking_id();
+  let consent = mailer::consent_for(&draft, &config, &tracking_id)?;
+  let compiled = compiler::compile(&draft, &config, &consent, &tracking_id);
+  if let Some(summary) = attachments::blocking_summary(&compiled.diagnostics) {
+    anyhow::bail!(summary);
+  }
+  let estimate = SizeEstimate::of(&draft, &compiled);
+  if let Some(error) = size::message_limit_error(estimate.message, &config) {
+    anyhow::bail!(error);
+  }
+
+  let profile = config.profile(&draft.profile);
+  let request = client::ScheduleRequest {
+    subject: compiled.subject.clone(),
+    to: draft.to,
+    cc: draft.cc,
+    bcc: draft.bcc,
+    group: groups.join(", "),
+    scheduled_at,
+    smtp_username: profile.smtp_username.clone(),
+    smtp_password: profile.smtp_app_password.clone(),
+    sender_name: profile.identity.name.clone(),
+    sender_profile: profile.name.clone(),
+  };
+
+  let tracking_id = compiled.tracking_id.clone();
+  let recipients: Vec<String> = request
+    .to
+    .iter()
+    .chain(&request.cc)
+    .chain(&request.bcc)
+    .cloned()
+    .collect();
+  let subject = request.subject.clone();
+  let job_id =
+    client::schedule_email(&config.worker_url, &config.api_secret, compiled, request).await?;
+
+  if !tracking_id.is_empty() {
+    Storage::record_tracked_message(TrackedMessage {
+      token: tracking_id,
+      message_id: job_id,
+      recipients,
+      subject,
+      sent_at: scheduled_at,
+    })
+    .context("Scheduled, but the tracking token could not be saved")?;
+  }
+  Ok(())
+}
+
 pub fn trigger_fetch(app: &mut App, tx: mpsc::Sender<Action>) {
   app.set_notification(Notification::Info("Fetching logs...".to_string()));
   let url = app.config.data.worker_url.clone();
@@ -38,7 +240,7 @@ pub fn trigger_fetch(app: &mut App, tx: mpsc::Sender<Action>) {
 }
 
 pub fn trigger_fetch_jobs(app: &mut App, tx: mpsc::Sender<Action>) {
-  app.current_page = crate::enums::CurrentPage::Scheduled;
+  app.current_page = CurrentPage::Scheduled;
   app.set_notification(Notification::Info("Fetching scheduled jobs...".to_string()));
   let url = app.config.data.worker_url.clone();
   let secret = app.config.data.api_secret.clone();
@@ -102,3 +304,27 @@ pub fn open_external_editor(initial_text: &str) -> io::Result<String> {
     "No suitable editor found (tried $EDITOR, nano, vim, vi, notepad).",
   ))
 }
+
+/// Writes the message `send_email` would send for `draft` to the Downloads folder.
+/// The export carries no tracking pixel, since its token would never be recorded.
+pub fn export_eml(config: &AppConfig, draft: &EmailDraft) -> Result<PathBuf> {
+  let message_id = uuid::Uuid::new_v4().to_string();
+  let built = mailer::build_message(config, draft, &message_id, "")?;
+
+  let user_dirs = UserDirs::new().context("Could not determine home directory")?;
+  let dl_dir = user_dirs
+    .download_dir()
+    .context("Could not find a Downloads folder")?;
+
+  let stem = if built.subject.trim().is_empty() {
+    draft.display_name()
+  } else {
+    built.subject.clone()
+  };
+  let safe_name = stem.trim().replace(|c: char| !c.is_alphanumeric(), "_");
+  let path = dl_dir.join(format!("{}.eml", safe_name));
+
+  fs::write(&path, built.message.formatted())
+    .with_context(|| format!("Could not write {}", path.display()))?;
+  Ok(path)
+}
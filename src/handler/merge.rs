@@ -0,0 +1,181 @@
+use crate::app::App;
+use crate::enums::{
+  CurrentPage, InputMode, MergeFocus, MergeRowStatus, Notification, ScheduleTarget,
+};
+use crate::handler::{Action, helper};
+use crate::queue::SendOrigin;
+use crate::size::{self, SizeEstimate};
+use crate::{attachments, compiler};
+use chrono::{DateTime, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tokio::sync::mpsc;
+
+pub async fn handle_merge_events(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Action>) -> bool {
+  match app.input_mode {
+    InputMode::Normal => handle_merge_normal_input(key, app, tx),
+    InputMode::Editing => handle_merge_editing_input(key, app),
+  }
+}
+
+fn handle_merge_normal_input(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Action>) -> bool {
+  match key.code {
+    KeyCode::Char('q') => {
+      app.quit();
+      false
+    }
+    KeyCode::Char(c @ '1'..='9') => {
+      helper::switch_page(app, tx, c);
+      false
+    }
+    KeyCode::Tab => {
+      app.cycle_field();
+      true
+    }
+    KeyCode::BackTab => {
+      app.cycle_field_backward();
+      true
+    }
+    KeyCode::Down if app.merge.focus == MergeFocus::Rows => {
+      app.merge.select_next();
+      true
+    }
+    KeyCode::Up if app.merge.focus == MergeFocus::Rows => {
+      app.merge.select_previous();
+      true
+    }
+    KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+      if app.merge.data.is_none() {
+        app.set_notification(Notification::Error("Load a CSV file first".to_string()));
+        return false;
+      }
+      app.reset_schedule_modal();
+      app.schedule.target = ScheduleTarget::Merge;
+      app.current_page = CurrentPage::Schedule;
+      true
+    }
+    KeyCode::Enter => {
+      match app.merge.focus {
+        MergeFocus::CsvPath => app.toggle_editing(),
+        MergeFocus::AddressColumn => app.merge.cycle_address_column(),
+        MergeFocus::Rows => {}
+        MergeFocus::SendButton => start_merge(app, tx, None),
+      }
+      true
+    }
+    _ => false,
+  }
+}
+
+fn handle_merge_editing_input(key: KeyEvent, app: &mut App) -> bool {
+  match key.code {
+    KeyCode::Esc | KeyCode::Enter => {
+      app.toggle_editing();
+      if app.merge.running {
+        app.set_notification(Notification::Error(
+          "Wait for the current merge to finish".to_string(),
+        ));
+        return true;
+      }
+      match app.merge.load() {
+        Ok(count) => app.set_notification(Notification::Success(format!("Loaded {} rows", count))),
+        Err(e) => app.set_notification(Notification::Error(format!("CSV error: {:#}", e))),
+      }
+    }
+    KeyCode::Char(c) => app.push_input(c),
+    KeyCode::Backspace => app.pop_input(),
+    _ => {}
+  }
+  true
+}
+
+/// Sends (or schedules, when `scheduled_at` is set) one personalised message per CSV row.
+/// Rows without an address, with unfilled placeholders, unreadable files or over the size limit
+/// are failed up front.
+pub fn start_merge(app: &mut App, tx: mpsc::Sender<Action>, scheduled_at: Option<DateTime<Utc>>) {
+  if app.merge.running {
+    app.set_notification(Notification::Error(
+      "A mail merge is already running".to_string(),
+    ));
+    return;
+  }
+  if app.merge.data.is_none() {
+    app.set_notification(Notification::Error("Load a CSV file first".to_string()));
+    return;
+  }
+
+  app.save_draft();
+
+  let mut jobs = Vec::new();
+  for row in 0..app.merge.row_count() {
+    let Some(draft) = app.merge.personalise(&app.compose.draft, row) else {
+      continue;
+    };
+    let unfilled = compiler::unfilled_placeholders(&draft);
+    let file_problems = attachments::blocking_summary(&compiler::file_diagnostics(&draft));
+    let too_large = size::message_limit_error(
+      SizeEstimate::for_draft(
+        &draft,
+        &app.config.data,
+        &app.contacts.consent,
+        &app.contacts.book,
+      )
+      .message,
+      &app.config.data,
+    );
+
+    // Only the row's own address counts; Cc and Bcc from the draft go to every row
+    app.merge.row_status[row] = if draft.to.is_empty() {
+      MergeRowStatus::Failed(format!("No address in '{}'", app.merge.address_header()))
+    } else if !unfilled.is_empty() {
+      MergeRowStatus::Failed(format!("Unfilled placeholders: {}", unfilled.join(", ")))
+    } else if let Some(summary) = file_problems {
+      MergeRowStatus::Failed(summary)
+    } else if let Some(error) = too_large {
+      MergeRowStatus::Failed(error)
+    } else {
+      jobs.push((row, draft));
+      MergeRowStatus::Pending
+    };
+  }
+
+  if jobs.is_empty() {
+    let (_, failed, _) = app.merge.tally();
+    app.set_notification(Notification::Error(format!(
+      "Nothing to send: {} rows failed validation",
+      failed
+    )));
+    return;
+  }
+
+  app.merge.running = true;
+  app.set_notification(Notification::Info(format!(
+    "Mail merge started for {} rows",
+    jobs.len()
+  )));
+
+  let config = app.config.data.clone();
+  let Some(at) = scheduled_at else {
+    // Immediate sends go through the throttled queue, which reports progress per row.
+    for (row, draft) in jobs {
+      if let Err(e) = app.queue.submit(SendOrigin::Merge(row), &config, draft) {
+        app.update_merge_row(row, MergeRowStatus::Failed(format!("Outbox error: {}", e)));
+      }
+    }
+    return;
+  };
+
+  tokio::spawn(async move {
+    for (row, draft) in jobs {
+      let _ = tx
+        .send(Action::MergeRowUpdated(row, MergeRowStatus::InFlight))
+        .await;
+
+      let status = match helper::schedule_draft(config.clone(), draft, at).await {
+        Ok(_) => MergeRowStatus::Scheduled,
+        Err(e) => MergeRowStatus::Failed(e.to_string()),
+      };
+
+      let _ = tx.send(Action::MergeRowUpdated(row, status)).await;
+    }
+  });
+}
@@ -1,13 +1,20 @@
+pub mod contacts;
 pub mod dashboard;
+pub mod drafts;
 pub mod helper;
+pub mod merge;
+pub mod outbox;
+pub mod picker;
 pub mod schedule;
 pub mod scheduled;
+pub mod sent;
 pub mod standard;
 
 use crate::app::App;
-use crate::enums::CurrentPage;
+use crate::enums::{CurrentPage, MergeRowStatus};
 use crate::models::{FilterOptions, LogEntry, ScheduledJob};
-use crossterm::event::KeyEvent;
+use crate::queue::{QueueStats, SendOrigin};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use tokio::sync::mpsc;
 
 #[derive(Debug)]
@@ -24,14 +31,34 @@ pub enum Action {
   JobsFailed(String),
   JobCancelled(String),
   JobActionFailed(String),
+  MergeRowUpdated(usize, MergeRowStatus),
+  QueueUpdated(QueueStats),
+  QueueJobStarted(SendOrigin),
+  QueueJobSent(SendOrigin),
+  QueueJobRetrying(SendOrigin, String),
+  QueueJobFailed(SendOrigin, String),
+  ArchiveFailed(String),
 }
 
 /// Main entry point for key event handling.
 pub async fn handle_key_events(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Action>) -> bool {
+  if key.code == KeyCode::Char('p') && key.modifiers.contains(KeyModifiers::CONTROL) {
+    app.toggle_queue_paused();
+    return false;
+  }
+  if app.picker.is_some() {
+    return picker::handle_picker_events(key, app);
+  }
+
   match app.current_page {
     CurrentPage::Schedule => schedule::handle_schedule_input(key, app, tx).await,
     CurrentPage::Dashboard => dashboard::handle_dashboard_events(key, app, tx).await,
     CurrentPage::Scheduled => scheduled::handle_scheduled_events(key, app, tx).await,
+    CurrentPage::Drafts => drafts::handle_drafts_events(key, app, tx).await,
+    CurrentPage::Merge => merge::handle_merge_events(key, app, tx).await,
+    CurrentPage::Outbox => outbox::handle_outbox_events(key, app, tx).await,
+    CurrentPage::Sent => sent::handle_sent_events(key, app, tx).await,
+    CurrentPage::Contacts => contacts::handle_contacts_events(key, app, tx).await,
     _ => standard::handle_standard_events(key, app, tx).await,
   }
 }
@@ -0,0 +1,96 @@
+use crate::app::App;
+use crate::enums::{Notification, OutboxStatus};
+use crate::handler::{Action, helper};
+use crate::storage::Storage;
+use crossterm::event::{KeyCode, KeyEvent};
+use tokio::sync::mpsc;
+
+pub async fn handle_outbox_events(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Action>) -> bool {
+  if app.outbox.inspecting {
+    match key.code {
+      KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => app.outbox.inspecting = false,
+      KeyCode::Char('r') => retry_selected(app),
+      KeyCode::Char('x') => discard_selected(app),
+      _ => return false,
+    }
+    return true;
+  }
+
+  match key.code {
+    KeyCode::Char('q') => {
+      app.quit();
+      false
+    }
+    KeyCode::Char(c @ '1'..='9') => {
+      helper::switch_page(app, tx, c);
+      false
+    }
+    KeyCode::Down => {
+      app.outbox.select_next();
+      true
+    }
+    KeyCode::Up => {
+      app.outbox.select_previous();
+      true
+    }
+    KeyCode::Enter => {
+      app.outbox.inspecting = app.outbox.selected().is_some();
+      true
+    }
+    KeyCode::Char('r') => {
+      retry_selected(app);
+      true
+    }
+    KeyCode::Char('x') => {
+      discard_selected(app);
+      true
+    }
+    _ => false,
+  }
+}
+
+// A manual retry starts the backoff over and sends straight away.
+fn retry_selected(app: &mut App) {
+  let Some(mut entry) = app.outbox.selected().cloned() else {
+    return;
+  };
+  if app.queue.is_sending(&entry.id) {
+    app.set_notification(Notification::Info(
+      "This message is being sent right now".to_string(),
+    ));
+    return;
+  }
+  entry.status = OutboxStatus::Queued;
+  entry.attempts = 0;
+  entry.next_attempt_at = None;
+
+  if let Err(e) = Storage::save_outbox_entry(&entry) {
+    app.set_notification(Notification::Error(format!("Outbox error: {}", e)));
+    return;
+  }
+  app.queue.requeue(&app.config.data, entry);
+  app.outbox.refresh();
+  app.set_notification(Notification::Info("Message queued for retry".to_string()));
+}
+
+fn discard_selected(app: &mut App) {
+  let Some(id) = app.outbox.selected().map(|e| e.id.clone()) else {
+    return;
+  };
+  // The SMTP server may still accept it, so it cannot be called discarded yet
+  if app.queue.is_sending(&id) {
+    app.set_notification(Notification::Info(
+      "This message is being sent right now and can no longer be discarded".to_string(),
+    ));
+    return;
+  }
+  app.queue.discard(&id);
+
+  if let Err(e) = Storage::delete_outbox_entry(&id) {
+    app.set_notification(Notification::Error(format!("Outbox error: {}", e)));
+    return;
+  }
+  app.outbox.inspecting = false;
+  app.outbox.refresh();
+  app.set_notification(Notification::Success("Message discarded".to_string()));
+}
@@ -0,0 +1,38 @@
+use crate::app::App;
+use crate::handler::helper;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+pub fn handle_picker_events(key: KeyEvent, app: &mut App) -> bool {
+  let Some(picker) = app.picker.as_mut() else {
+    return false;
+  };
+
+  match key.code {
+    KeyCode::Esc => app.picker = None,
+    KeyCode::Down => picker.select_next(),
+    KeyCode::Up => picker.select_previous(),
+    KeyCode::Right => {
+      picker.open_selected();
+    }
+    KeyCode::Left => picker.go_up(),
+    KeyCode::Backspace if picker.filter.is_empty() => picker.go_up(),
+    KeyCode::Backspace => picker.pop_filter(),
+    KeyCode::Tab => picker.toggle_mark(),
+    KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => picker.toggle_hidden(),
+    KeyCode::Enter => {
+      if picker.open_selected() {
+        return true;
+      }
+      let chosen = picker.chosen();
+      if chosen.is_empty() {
+        return true;
+      }
+      let target = picker.target;
+      app.picker = None;
+      helper::apply_picked_files(app, target, chosen);
+    }
+    KeyCode::Char(c) => picker.push_filter(c),
+    _ => {}
+  }
+  true
+}
@@ -1,8 +1,8 @@
 use crate::app::App;
-use crate::enums::{InputMode, Notification, ScheduleField};
-use crate::handler::Action;
-use crate::storage::Storage;
-use crate::{client, compiler};
+use crate::enums::{CurrentPage, InputMode, Notification, ScheduleField, ScheduleTarget};
+use crate::handler::{Action, helper, merge};
+use crate::size::{self, SizeEstimate};
+use crate::{attachments, compiler};
 use crossterm::event::{KeyCode, KeyEvent};
 use tokio::sync::mpsc;
 
@@ -20,9 +20,12 @@ async fn handle_normal_mode(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Actio
       false
     }
     KeyCode::Esc => {
-      app.sync_schedule_to_draft();
-      let _ = Storage::save_draft(&app.compose.draft);
-      app.current_page = crate::enums::CurrentPage::Compose;
+      app.save_draft();
+      app.current_page = match app.schedule.target {
+        ScheduleTarget::Draft => CurrentPage::Compose,
+        ScheduleTarget::Merge => CurrentPage::Merge,
+      };
+      app.schedule.target = ScheduleTarget::Draft;
       false
     }
     KeyCode::Tab => {
@@ -35,37 +38,56 @@ async fn handle_normal_mode(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Actio
     }
     KeyCode::Enter => {
       if app.schedule.active_field == ScheduleField::Submit {
-        app.sync_schedule_to_draft();
-        let _ = Storage::save_draft(&app.compose.draft);
+        app.save_draft();
 
-        if let Some(utc_target) = app.schedule.calculate_utc_target() {
-          app.set_notification(Notification::Info("Scheduling...".to_string()));
+        let Some(utc_target) = app.schedule.calculate_utc_target() else {
+          app.set_notification(Notification::Error("Invalid Date/Time".to_string()));
+          return false;
+        };
+
+        if app.schedule.target == ScheduleTarget::Merge {
+          app.schedule.target = ScheduleTarget::Draft;
+          app.current_page = CurrentPage::Merge;
+          merge::start_merge(app, tx, Some(utc_target));
+          return false;
+        }
 
-          let draft_clone = app.compose.draft.clone();
-          let identity_clone = app.config.data.identity.clone();
-          let worker_url = app.config.data.worker_url.clone();
-          let api_secret = app.config.data.api_secret.clone();
-          let smtp_user = app.config.data.smtp_username.clone();
-          let smtp_pass = app.config.data.smtp_app_password.clone();
+        let unfilled = compiler::unfilled_placeholders(&app.compose.draft);
+        let file_problems =
+          attachments::blocking_summary(&compiler::file_diagnostics(&app.compose.draft));
+        let estimate = SizeEstimate::for_draft(
+          &app.compose.draft,
+          &app.config.data,
+          &app.contacts.consent,
+          &app.contacts.book,
+        );
+        if !app.compose.draft.has_recipients() {
+          app.set_notification(Notification::Error(
+            "Add at least one recipient".to_string(),
+          ));
+        } else if !unfilled.is_empty() {
+          app.set_notification(Notification::Error(format!(
+            "Unfilled placeholders: {}",
+            unfilled.join(", ")
+          )));
+        } else if let Some(summary) = file_problems {
+          app.set_notification(Notification::Error(summary));
+        } else if let Some(error) = size::message_limit_error(estimate.message, &app.config.data) {
+          app.set_notification(Notification::Error(error));
+        } else {
+          app.set_notification(Notification::Info(
+            match size::upload_limit_warning(estimate.upload, &app.config.data) {
+              Some(warning) => format!("Scheduling... {} and may be rejected", warning),
+              None => "Scheduling...".to_string(),
+            },
+          ));
 
+          let draft = app.compose.draft.clone();
+          let config = app.config.data.clone();
           let tx_sched = tx.clone();
 
           tokio::spawn(async move {
-            let compiled = compiler::compile(&draft_clone, &identity_clone, &worker_url);
-
-            match client::schedule_email(
-              &worker_url,
-              &api_secret,
-              compiled,
-              draft_clone.subject,
-              draft_clone.recipient,
-              utc_target,
-              smtp_user,
-              smtp_pass,
-              identity_clone.name,
-            )
-            .await
-            {
+            match helper::schedule_draft(config, draft, utc_target).await {
               Ok(_) => {
                 tx_sched.send(Action::EmailSent).await.unwrap();
               }
@@ -78,9 +100,7 @@ async fn handle_normal_mode(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Actio
             }
           });
 
-          app.current_page = crate::enums::CurrentPage::Dashboard;
-        } else {
-          app.set_notification(Notification::Error("Invalid Date/Time".to_string()));
+          app.current_page = CurrentPage::Dashboard;
         }
       } else {
         app.schedule.clear_current_field();
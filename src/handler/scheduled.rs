@@ -1,7 +1,7 @@
 use crate::app::App;
 use crate::client;
 use crate::enums::{InputMode, Notification, ScheduledFocus};
-use crate::handler::Action;
+use crate::handler::{Action, helper};
 use crossterm::event::{KeyCode, KeyEvent};
 use directories::UserDirs;
 use std::fs;
@@ -108,7 +108,7 @@ pub async fn handle_scheduled_events(
           ScheduledFocus::FilterStatus => {
             app.scheduled.toggle_status_filter();
           }
-          ScheduledFocus::FilterRecipient => {
+          ScheduledFocus::FilterRecipient | ScheduledFocus::FilterGroup => {
             app.toggle_editing();
           }
         }
@@ -143,24 +143,12 @@ pub async fn handle_scheduled_events(
         true
       }
       // Global Nav
-      KeyCode::Char('1') => {
-        app.current_page = crate::enums::CurrentPage::Compose;
+      KeyCode::Char(c @ '1'..='9') => {
+        helper::switch_page(app, tx, c);
         false
       }
-      KeyCode::Char('2') => {
-        app.current_page = crate::enums::CurrentPage::Config;
-        false
-      }
-      KeyCode::Char('3') => {
-        app.current_page = crate::enums::CurrentPage::Dashboard;
-        false
-      }
-      KeyCode::Char('4') => {
-        app.current_page = crate::enums::CurrentPage::Scheduled;
-        false
-      } // Current Schedule page
       KeyCode::Char('r') => {
-        crate::handler::helper::trigger_fetch_jobs(app, tx);
+        helper::trigger_fetch_jobs(app, tx);
         false
       }
       _ => false,
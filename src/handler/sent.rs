@@ -0,0 +1,62 @@
+use crate::app::App;
+use crate::enums::{CurrentPage, Notification};
+use crate::handler::{Action, helper};
+use crate::storage::Storage;
+use crossterm::event::{KeyCode, KeyEvent};
+use tokio::sync::mpsc;
+
+pub async fn handle_sent_events(key: KeyEvent, app: &mut App, tx: mpsc::Sender<Action>) -> bool {
+  if app.sent.inspected_eml.is_some() {
+    match key.code {
+      KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => app.sent.inspected_eml = None,
+      KeyCode::Char('o') => reopen_selected(app),
+      _ => return false,
+    }
+    return true;
+  }
+
+  match key.code {
+    KeyCode::Char('q') => {
+      app.quit();
+      false
+    }
+    KeyCode::Char(c @ '1'..='9') => {
+      helper::switch_page(app, tx, c);
+      false
+    }
+    KeyCode::Down => {
+      app.sent.select_next();
+      true
+    }
+    KeyCode::Up => {
+      app.sent.select_previous();
+      true
+    }
+    KeyCode::Enter => {
+      app.sent.inspect_selected();
+      true
+    }
+    KeyCode::Char('o') => {
+      reopen_selected(app);
+      true
+    }
+    _ => false,
+  }
+}
+
+fn reopen_selected(app: &mut App) {
+  let Some(id) = app.sent.selected().map(|r| r.id.clone()) else {
+    return;
+  };
+  match Storage::load_sent_draft(&id) {
+    Ok(original) => {
+      app.sent.inspected_eml = None;
+      app.open_draft(original.duplicate());
+      app.current_page = CurrentPage::Compose;
+      app.set_notification(Notification::Success(
+        "Opened sent message as a new draft".to_string(),
+      ));
+    }
+    Err(e) => app.set_notification(Notification::Error(format!("Reopen failed: {}", e))),
+  }
+}
@@ -1,8 +1,11 @@
 use crate::app::App;
-use crate::enums::{ComposeField, CurrentPage, InputMode, Notification};
+use crate::enums::{
+  ComposeField, ConfigField, CurrentPage, InputMode, Notification, PickerTarget, TrackingMode,
+};
 use crate::handler::{Action, helper};
-use crate::mailer;
-use crate::storage::Storage;
+use crate::queue::SendOrigin;
+use crate::size::{self, SizeEstimate};
+use crate::{attachments, compiler, signature, theme};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use crossterm::{
   execute,
@@ -32,21 +35,8 @@ async fn handle_standard_normal_input(
       app.quit();
       false
     }
-    KeyCode::Char('1') => {
-      app.current_page = CurrentPage::Compose;
-      false
-    }
-    KeyCode::Char('2') => {
-      app.current_page = CurrentPage::Config;
-      false
-    }
-    KeyCode::Char('3') => {
-      app.current_page = CurrentPage::Dashboard;
-      helper::trigger_fetch(app, tx.clone());
-      false
-    }
-    KeyCode::Char('4') => {
-      helper::trigger_fetch_jobs(app, tx.clone());
+    KeyCode::Char(c @ '1'..='9') => {
+      helper::switch_page(app, tx.clone(), c);
       false
     }
     KeyCode::Tab => {
@@ -57,45 +47,24 @@ async fn handle_standard_normal_input(
       app.cycle_field_backward();
       false
     }
-    KeyCode::Enter => handle_enter_action(app, tx).await,
+    KeyCode::Enter => handle_enter_action(app).await,
 
-    KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-      if app.current_page == CurrentPage::Compose && app.compose.field == ComposeField::Attachments
-      {
-        let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
-        let files = rfd::FileDialog::new()
-          .set_title("Select Attachments")
-          .pick_files();
-        let _ = enable_raw_mode();
-        let _ = execute!(io::stdout(), EnterAlternateScreen);
-        if let Some(paths) = files {
-          let paths_vec: Vec<String> = paths
-            .iter()
-            .map(|p| p.to_string_lossy().to_string())
-            .collect();
-
-          if !app.compose.attachment_input.is_empty() {
-            app.compose.attachment_input.push_str("; ");
-          }
-          app.compose.attachment_input.push_str(&paths_vec.join("; "));
-          app.set_notification(Notification::Success(format!(
-            "Added {} files",
-            paths.len()
-          )));
-        }
-        true
-      } else {
-        false
+    KeyCode::Char('o')
+      if key.modifiers.contains(KeyModifiers::CONTROL)
+        && app.current_page == CurrentPage::Compose =>
+    {
+      match app.compose.field {
+        ComposeField::Attachments => helper::pick_files(app, PickerTarget::Attachments),
+        ComposeField::Body => helper::pick_files(app, PickerTarget::InlineImages),
+        _ => return false,
       }
+      true
     }
 
     KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-      if app.current_page == CurrentPage::Compose && app.compose.field == ComposeField::Attachments
-      {
-        app.compose.attachment_input.clear();
-        app.compose.sync_attachments();
-        let _ = Storage::save_draft(&app.compose.draft);
+      if attachments_focused(app) {
+        app.compose.clear_attachments();
+        app.save_draft();
         app.set_notification(Notification::Info("Attachments cleared".to_string()));
         true
       } else {
@@ -103,15 +72,142 @@ async fn handle_standard_normal_input(
       }
     }
 
+    KeyCode::Char('n') if profile_focused(app) => {
+      match app.config.add_profile() {
+        Ok(()) => app.set_notification(Notification::Info(
+          "Profile added - [Ctrl+s] to save".to_string(),
+        )),
+        Err(e) => app.set_notification(Notification::Error(e.to_string())),
+      }
+      false
+    }
+    KeyCode::Char('x') if profile_focused(app) => {
+      match app.config.remove_profile() {
+        Some(removed) => app.set_notification(Notification::Info(format!(
+          "Removed profile {} - [Ctrl+s] to save",
+          removed.label()
+        ))),
+        None => app.set_notification(Notification::Error(
+          "The last profile cannot be removed".to_string(),
+        )),
+      }
+      false
+    }
+
+    KeyCode::Char('x') if attachments_focused(app) => {
+      if let Some(removed) = app.compose.remove_selected_attachment() {
+        app.save_draft();
+        app.set_notification(Notification::Info(format!("Removed {}", removed.display())));
+      }
+      false
+    }
+    KeyCode::Up | KeyCode::Down if attachments_focused(app) => {
+      let down = key.code == KeyCode::Down;
+      if key.modifiers.contains(KeyModifiers::SHIFT) {
+        app.compose.move_selected_attachment(down);
+        app.save_draft();
+      } else {
+        app.compose.select_attachment(down);
+      }
+      false
+    }
+
+    KeyCode::Char('w')
+      if key.modifiers.contains(KeyModifiers::CONTROL)
+        && app.current_page == CurrentPage::Compose =>
+    {
+      let waived = app.compose.waive_file_problems();
+      if waived > 0 {
+        app.set_notification(Notification::Info(format!(
+          "Will send without {} unreadable file(s)",
+          waived
+        )));
+      } else if !app.compose.draft.waived_files.is_empty() {
+        app.compose.clear_waivers();
+        app.set_notification(Notification::Info(
+          "Unreadable files will block sending again".to_string(),
+        ));
+      }
+      app.save_draft();
+      false
+    }
+
+    KeyCode::Char('t')
+      if key.modifiers.contains(KeyModifiers::CONTROL)
+        && app.current_page == CurrentPage::Compose =>
+    {
+      app.compose.draft.theme = theme::next(&app.compose.draft.theme);
+      app.save_draft();
+      false
+    }
+
+    KeyCode::Char('g')
+      if key.modifiers.contains(KeyModifiers::CONTROL)
+        && app.current_page == CurrentPage::Compose =>
+    {
+      let profile = app.config.data.profile(&app.compose.draft.profile);
+      app.compose.draft.signature = signature::next(signature::chosen(&app.compose.draft, profile));
+      app.save_draft();
+      false
+    }
+
+    KeyCode::Char('k')
+      if key.modifiers.contains(KeyModifiers::CONTROL)
+        && app.current_page == CurrentPage::Compose =>
+    {
+      app.compose.draft.tracking = match app.compose.draft.tracking {
+        TrackingMode::Off => TrackingMode::Consented,
+        TrackingMode::Consented => TrackingMode::Override,
+        TrackingMode::Override => TrackingMode::Off,
+      };
+      app.save_draft();
+      false
+    }
+
+    KeyCode::Char('r')
+      if key.modifiers.contains(KeyModifiers::CONTROL)
+        && app.current_page == CurrentPage::Compose =>
+    {
+      app.compose.toggle_preview();
+      true
+    }
+    KeyCode::PageDown if app.compose.preview => {
+      app.compose.preview_scroll = app.compose.preview_scroll.saturating_add(5);
+      false
+    }
+    KeyCode::PageUp if app.compose.preview => {
+      app.compose.preview_scroll = app.compose.preview_scroll.saturating_sub(5);
+      false
+    }
+
+    KeyCode::Char('e')
+      if key.modifiers.contains(KeyModifiers::CONTROL)
+        && app.current_page == CurrentPage::Compose =>
+    {
+      app.save_draft();
+      match helper::export_eml(&app.config.data, &app.compose.draft) {
+        Ok(path) => app.set_notification(Notification::Success(format!(
+          "Exported to {}",
+          path.display()
+        ))),
+        Err(e) => app.set_notification(Notification::Error(format!("Export failed: {:#}", e))),
+      }
+      false
+    }
+
     KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
       if app.current_page == CurrentPage::Compose {
         app.reset_schedule_modal();
         app.current_page = CurrentPage::Schedule;
         true
       } else {
-        if let Err(e) = Storage::save_config(&app.config.data) {
+        if let Err(e) = app.config.save() {
           app.set_notification(Notification::Error(e.to_string()));
         } else {
+          app.queue.set_limits(&app.config.data);
+          app
+            .compose
+            .refresh_checks(&app.config.data, &app.contacts.consent, &app.contacts.book);
           app.set_notification(Notification::Success("Config saved".to_string()));
         }
         false
@@ -121,9 +217,21 @@ async fn handle_standard_normal_input(
   }
 }
 
-async fn handle_enter_action(app: &mut App, tx: mpsc::Sender<Action>) -> bool {
+async fn handle_enter_action(app: &mut App) -> bool {
   if app.current_page == CurrentPage::Config {
-    app.toggle_editing();
+    if app.config.field == ConfigField::Profile {
+      if let Err(e) = app.config.next_profile() {
+        app.set_notification(Notification::Error(e.to_string()));
+      }
+    } else if app.config.field == ConfigField::Signature {
+      app.config.cycle_signature();
+    } else if app.config.field == ConfigField::SmtpSecurity {
+      app.config.cycle_smtp_security();
+    } else if app.config.field == ConfigField::FilePicker {
+      app.config.cycle_file_picker();
+    } else {
+      app.toggle_editing();
+    }
     return false;
   }
   if app.current_page == CurrentPage::Dashboard {
@@ -134,6 +242,11 @@ async fn handle_enter_action(app: &mut App, tx: mpsc::Sender<Action>) -> bool {
   }
 
   match app.compose.field {
+    ComposeField::From => {
+      app.compose.draft.profile = app.config.data.next_profile(&app.compose.draft.profile);
+      app.save_draft();
+      false
+    }
     ComposeField::Body => {
       let _ = execute!(io::stdout(), LeaveAlternateScreen);
       let _ = disable_raw_mode();
@@ -146,7 +259,7 @@ async fn handle_enter_action(app: &mut App, tx: mpsc::Sender<Action>) -> bool {
       match new_body {
         Ok(content) => {
           app.compose.draft.body = content;
-          let _ = Storage::save_draft(&app.compose.draft);
+          app.save_draft();
         }
         Err(e) => {
           app.set_notification(Notification::Error(format!("Editor error: {}", e)));
@@ -155,28 +268,49 @@ async fn handle_enter_action(app: &mut App, tx: mpsc::Sender<Action>) -> bool {
       true
     }
     ComposeField::SendButton => {
-      app.set_notification(Notification::Info("Sending email...".to_string()));
-      let mut draft = app.compose.draft.clone();
-      draft.attachments = app
-        .compose
-        .attachment_input
-        .split(';')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .map(std::path::PathBuf::from)
-        .collect();
-      let config = app.config.data.clone();
-      let tx_clone = tx.clone();
-
-      tokio::spawn(async move {
-        match mailer::send_email(config, draft).await {
-          Ok(_) => tx_clone.send(Action::EmailSent).await.unwrap(),
-          Err(e) => tx_clone
-            .send(Action::EmailFailed(e.to_string()))
-            .await
-            .unwrap(),
-        }
-      });
+      app.compose.sync_inputs();
+      let draft = app.compose.draft.clone();
+      if !draft.has_recipients() {
+        app.set_notification(Notification::Error(
+          "Add at least one recipient".to_string(),
+        ));
+        return false;
+      }
+      let unfilled = compiler::unfilled_placeholders(&draft);
+      if !unfilled.is_empty() {
+        app.set_notification(Notification::Error(format!(
+          "Unfilled placeholders: {}",
+          unfilled.join(", ")
+        )));
+        return false;
+      }
+      if let Some(summary) = attachments::blocking_summary(&compiler::file_diagnostics(&draft)) {
+        app.set_notification(Notification::Error(summary));
+        return false;
+      }
+      let estimate = SizeEstimate::for_draft(
+        &draft,
+        &app.config.data,
+        &app.contacts.consent,
+        &app.contacts.book,
+      );
+      if let Some(error) = size::message_limit_error(estimate.message, &app.config.data) {
+        app.set_notification(Notification::Error(error));
+        return false;
+      }
+      let ahead = app.queue_stats.queued + app.queue_stats.in_flight;
+      if let Err(e) = app
+        .queue
+        .submit(SendOrigin::Compose, &app.config.data, draft)
+      {
+        app.set_notification(Notification::Error(format!("Outbox error: {}", e)));
+        return false;
+      }
+      app.set_notification(Notification::Info(if ahead == 0 {
+        "Sending email...".to_string()
+      } else {
+        format!("Email queued behind {} others", ahead)
+      }));
       false
     }
     _ => {
@@ -186,21 +320,66 @@ async fn handle_enter_action(app: &mut App, tx: mpsc::Sender<Action>) -> bool {
   }
 }
 
+fn profile_focused(app: &App) -> bool {
+  app.current_page == CurrentPage::Config && app.config.field == ConfigField::Profile
+}
+
+fn attachments_focused(app: &App) -> bool {
+  app.current_page == CurrentPage::Compose && app.compose.field == ComposeField::Attachments
+}
+
 fn handle_standard_editing_input(key: KeyEvent, app: &mut App) -> bool {
+  if attachments_focused(app) {
+    match key.code {
+      KeyCode::Enter => match app.compose.submit_attachment_input() {
+        Ok(_) => {
+          app.toggle_editing();
+          app.save_draft();
+        }
+        Err(e) => app.set_notification(Notification::Error(e.to_string())),
+      },
+      KeyCode::Esc => {
+        app.compose.attachment_input.clear();
+        app.toggle_editing();
+      }
+      KeyCode::Char(c) => app.push_input(c),
+      KeyCode::Backspace => app.pop_input(),
+      _ => {}
+    }
+    return false;
+  }
+
+  if !app.compose.suggestions.is_empty() && app.current_page == CurrentPage::Compose {
+    match key.code {
+      KeyCode::Down => {
+        app.compose.next_suggestion();
+        return false;
+      }
+      KeyCode::Up => {
+        app.compose.previous_suggestion();
+        return false;
+      }
+      KeyCode::Enter => {
+        app.compose.accept_suggestion();
+        return false;
+      }
+      KeyCode::Esc | KeyCode::Tab | KeyCode::BackTab => app.compose.clear_suggestions(),
+      _ => {}
+    }
+  }
+
   match key.code {
     KeyCode::Esc => {
       app.toggle_editing();
       if app.current_page == CurrentPage::Compose {
-        app.compose.sync_attachments();
-        let _ = Storage::save_draft(&app.compose.draft);
+        app.save_draft();
       }
     }
     KeyCode::Enter => {
       app.toggle_editing();
       app.cycle_field();
       if app.current_page == CurrentPage::Compose {
-        app.compose.sync_attachments();
-        let _ = Storage::save_draft(&app.compose.draft);
+        app.save_draft();
       }
     }
     KeyCode::BackTab => {
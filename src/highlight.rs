@@ -0,0 +1,42 @@
+use std::sync::LazyLock;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEME: LazyLock<Theme> = LazyLock::new(|| {
+  let mut themes = ThemeSet::load_defaults();
+  themes.themes.remove("InspiredGitHub").unwrap_or_default()
+});
+
+const PRE_STYLE: &str = "padding: 12px; border: 1px solid #e1e4e8; border-radius: 4px; overflow-x: auto; font-family: Consolas, 'Courier New', monospace; font-size: 13px; line-height: 1.45;";
+
+/// Renders a code block as a `<pre>` with every colour in inline `style` attributes,
+/// since most mail clients drop `<style>` blocks and classes.
+/// `info` is the fence's info string; its first word picks the language.
+pub fn code_block_html(code: &str, info: &str) -> String {
+  let lang = info
+    .split([',', ' ', '{'])
+    .next()
+    .unwrap_or_default()
+    .trim();
+  let syntax = SYNTAXES
+    .find_syntax_by_token(lang)
+    .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text());
+
+  match highlighted_html_for_string(code, &SYNTAXES, syntax, &THEME) {
+    Ok(html) => html.replacen("<pre style=\"", &format!("<pre style=\"{} ", PRE_STYLE), 1),
+    Err(_) => format!(
+      "<pre style=\"{}\"><code>{}</code></pre>",
+      PRE_STYLE,
+      escape_html(code)
+    ),
+  }
+}
+
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+}
@@ -1,34 +1,123 @@
-use crate::compiler;
-use crate::config::AppConfig;
-use crate::models::EmailDraft;
+use crate::config::{AppConfig, SenderProfile};
+use crate::enums::{SmtpSecurity, TrackingMode};
+use crate::models::{ConsentRegistry, EmailDraft, OutboxEntry, format_mailbox};
+use crate::storage::Storage;
+use crate::{attachments, compiler, contacts, size};
 use anyhow::{Context, Result};
 use lettre::{
   AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
   message::{
-    MultiPart, SinglePart,
+    Mailbox, MultiPart, SinglePart,
     header::{self, Header},
   },
   transport::smtp::authentication::Credentials,
 };
 use std::fs;
 
-pub async fn send_email(config: AppConfig, draft: EmailDraft) -> Result<()> {
-  let compiled = compiler::compile(&draft, &config.identity, &config.worker_url);
+/// What the server accepted: the exact bytes handed to the transport and its reply.
+pub struct SentMessage {
+  pub subject: String,
+  pub tracking_id: String,
+  pub eml: Vec<u8>,
+  pub smtp_response: String,
+  pub to: Vec<String>,
+  pub cc: Vec<String>,
+  pub bcc: Vec<String>,
+  pub groups: Vec<String>,
+}
 
-  let sender_header = if config.identity.name.is_empty() {
-      config.smtp_username.clone()
-  } else {
-      format!("\"{}\" <{}>", config.identity.name, config.smtp_username)
-  };
+/// A fully assembled MIME message, ready to be sent or written out as `.eml`.
+pub struct BuiltMessage {
+  pub message: Message,
+  pub subject: String,
+  pub tracking_id: String,
+  /// The draft with recipient groups expanded into their members.
+  pub draft: EmailDraft,
+  pub groups: Vec<String>,
+}
+
+/// Sends an outbox entry, reusing its Message-ID and tracking token so a retry is the
+/// same message as the first attempt.
+pub async fn send_email(config: AppConfig, entry: OutboxEntry) -> Result<SentMessage> {
+  let draft = entry.draft;
+  let built = build_message(&config, &draft, &entry.message_id, &entry.tracking_id)?;
+  let mailer = build_transport(config.profile(&draft.profile))?;
+
+  let eml = built.message.formatted();
+  if let Some(error) = size::message_limit_error(eml.len() as u64, &config) {
+    anyhow::bail!(error);
+  }
+  let response = mailer
+    .send_raw(built.message.envelope(), &eml)
+    .await
+    .context("SMTP transmission failed")?;
+
+  Ok(SentMessage {
+    subject: built.subject,
+    tracking_id: built.tracking_id,
+    eml,
+    smtp_response: format!(
+      "{} {}",
+      response.code(),
+      response.message().collect::<Vec<_>>().join(" ")
+    ),
+    to: built.draft.to,
+    cc: built.draft.cc,
+    bcc: built.draft.bcc,
+    groups: built.groups,
+  })
+}
 
-  let email_builder = Message::builder()
+/// Compiles the draft and assembles the exact message `send_email` would hand to the transport.
+/// `message_id` becomes the local part of the Message-ID header.
+pub fn build_message(
+  config: &AppConfig,
+  draft: &EmailDraft,
+  message_id: &str,
+  tracking_id: &str,
+) -> Result<BuiltMessage> {
+  let (draft, groups) = expand_recipients(draft)?;
+  let consent = consent_for(&draft, config, tracking_id)?;
+  let compiled = compiler::compile(&draft, config, &consent, tracking_id);
+  if !compiled.unfilled_placeholders.is_empty() {
+    anyhow::bail!(
+      "Unfilled placeholders: {}",
+      compiled.unfilled_placeholders.join(", ")
+    );
+  }
+  if let Some(summary) = attachments::blocking_summary(&compiled.diagnostics) {
+    anyhow::bail!(summary);
+  }
+
+  let profile = config.profile(&draft.profile);
+  let sender_header = format_mailbox(&profile.identity.name, &profile.smtp_username);
+
+  if !draft.has_recipients() {
+    anyhow::bail!("No recipients");
+  }
+
+  let mut email_builder = Message::builder()
     .from(
       sender_header
         .parse()
         .context("Invalid sender format (Name <email>)")?,
     )
-    .to(draft.recipient.parse().context("Invalid recipient email")?)
-    .subject(draft.subject);
+    .subject(compiled.subject.clone())
+    .message_id(Some(format!(
+      "<{}@{}>",
+      message_id,
+      sender_domain(&profile.smtp_username)
+    )));
+
+  for address in &draft.to {
+    email_builder = email_builder.to(parse_mailbox(address)?);
+  }
+  for address in &draft.cc {
+    email_builder = email_builder.cc(parse_mailbox(address)?);
+  }
+  for address in &draft.bcc {
+    email_builder = email_builder.bcc(parse_mailbox(address)?);
+  }
 
   let alternative = MultiPart::alternative()
     .singlepart(SinglePart::plain(compiled.plain_body))
@@ -37,59 +126,125 @@ pub async fn send_email(config: AppConfig, draft: EmailDraft) -> Result<()> {
   let mut related = MultiPart::related().multipart(alternative);
 
   for img in compiled.inline_images {
-    if let Ok(file_content) = fs::read(&img.path) {
-      let filename = img
-        .path
-        .file_name()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_else(|| "image.png".to_string());
+    let file_content = fs::read(&img.path)
+      .with_context(|| format!("Cannot read inline image {}", img.path.display()))?;
+    let filename = img
+      .path
+      .file_name()
+      .map(|s| s.to_string_lossy().to_string())
+      .unwrap_or_else(|| "image.png".to_string());
 
-      let content_type = mime_guess::from_path(&img.path).first_or_octet_stream();
+    let content_type = mime_guess::from_path(&img.path).first_or_octet_stream();
 
-      let disposition_header = header::ContentDisposition::inline_with_name(&filename);
+    let disposition_header = header::ContentDisposition::inline_with_name(&filename);
 
-      let part = SinglePart::builder()
-        .header(header::ContentType::parse(content_type.as_ref()).unwrap())
-        .header(header::ContentId::parse(&format!("<{}>", img.cid)).unwrap())
-        .header(disposition_header)
-        .body(file_content);
+    let part = SinglePart::builder()
+      .header(header::ContentType::parse(content_type.as_ref()).unwrap())
+      .header(header::ContentId::parse(&format!("<{}>", img.cid)).unwrap())
+      .header(disposition_header)
+      .body(file_content);
 
-      related = related.singlepart(part);
-    }
+    related = related.singlepart(part);
   }
 
   let mut multipart = MultiPart::mixed().multipart(related);
 
   for path in compiled.attachments {
-    if let Ok(file_content) = fs::read(&path) {
-      let filename = path
-        .file_name()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_default();
-      let content_type = mime_guess::from_path(&path).first_or_octet_stream();
-
-      let part = SinglePart::builder()
-        .header(header::ContentType::parse(content_type.as_ref()).unwrap())
-        .header(header::ContentDisposition::attachment(&filename))
-        .body(file_content);
-
-      multipart = multipart.singlepart(part);
-    }
+    let file_content =
+      fs::read(&path).with_context(|| format!("Cannot read attachment {}", path.display()))?;
+    let filename = path
+      .file_name()
+      .map(|s| s.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let content_type = mime_guess::from_path(&path).first_or_octet_stream();
+
+    let part = SinglePart::builder()
+      .header(header::ContentType::parse(content_type.as_ref()).unwrap())
+      .header(header::ContentDisposition::attachment(&filename))
+      .body(file_content);
+
+    multipart = multipart.singlepart(part);
   }
 
-  let email = email_builder
+  let message = email_builder
     .multipart(multipart)
     .context("Failed to build email body")?;
 
-  let creds = Credentials::new(config.smtp_username, config.smtp_app_password);
-  let mailer = AsyncSmtpTransport::<Tokio1Executor>::relay("smtp.gmail.com")?
-    .credentials(creds)
-    .build();
+  Ok(BuiltMessage {
+    message,
+    subject: compiled.subject,
+    tracking_id: compiled.tracking_id,
+    draft,
+    groups,
+  })
+}
 
-  mailer
-    .send(email)
-    .await
-    .context("SMTP transmission failed")?;
+/// Expands `@group` recipients. The address book is only read when the draft has any.
+pub fn expand_recipients(draft: &EmailDraft) -> Result<(EmailDraft, Vec<String>)> {
+  if !draft.has_group_references() {
+    return Ok((draft.clone(), Vec::new()));
+  }
+  let book = Storage::load_contacts().context("Cannot expand the recipient groups")?;
+  contacts::expand_groups(draft, &book)
+}
+
+/// The consent registry `compile` checks. It is only read when a pixel could be added and
+/// depends on consent; otherwise an empty registry is returned.
+pub fn consent_for(
+  draft: &EmailDraft,
+  config: &AppConfig,
+  tracking_id: &str,
+) -> Result<ConsentRegistry> {
+  if tracking_id.is_empty()
+    || config.tracking_secret.is_empty()
+    || draft.tracking != TrackingMode::Consented
+  {
+    return Ok(ConsentRegistry::default());
+  }
+  Storage::load_consent().context("Cannot check tracking consent")
+}
+
+/// Whether a failed send is worth retrying: connection problems and 4xx replies are,
+/// 5xx replies and anything caught before talking to the server are not.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+  match err.downcast_ref::<lettre::transport::smtp::Error>() {
+    Some(smtp) => !smtp.is_permanent() && !smtp.is_client(),
+    None => false,
+  }
+}
+
+fn sender_domain(address: &str) -> &str {
+  match address.rsplit_once('@') {
+    Some((_, domain)) if !domain.trim().is_empty() => domain.trim(),
+    _ => "localhost",
+  }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox> {
+  address
+    .parse()
+    .with_context(|| format!("Invalid recipient email: {}", address))
+}
+
+fn build_transport(profile: &SenderProfile) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+  let host = profile.smtp_host.trim();
+  let builder = match profile.smtp_security {
+    SmtpSecurity::ImplicitTls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+      .with_context(|| format!("Invalid SMTP host: {}", host))?,
+    SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+      .with_context(|| format!("Invalid SMTP host: {}", host))?,
+    SmtpSecurity::Plaintext => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+  };
+  let builder = builder.port(profile.smtp_port);
+
+  // Local relays and SMTP catchers usually do not offer AUTH at all
+  if profile.smtp_app_password.is_empty() {
+    return Ok(builder.build());
+  }
 
-  Ok(())
+  let creds = Credentials::new(
+    profile.smtp_username.clone(),
+    profile.smtp_app_password.clone(),
+  );
+  Ok(builder.credentials(creds).build())
 }
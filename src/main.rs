@@ -1,28 +1,38 @@
 mod app;
+mod attachments;
 mod client;
 mod compiler;
 mod config;
+mod contacts;
 mod enums;
 mod handler;
+mod highlight;
 mod mailer;
+mod merge;
 mod models;
+mod plaintext;
+mod queue;
+mod signature;
+mod size;
 mod storage;
+mod theme;
+mod tracking;
 mod tui;
 mod ui;
 
 use anyhow::Result;
 use app::App;
 use crossterm::event::{self, Event, KeyEventKind};
-use enums::Notification;
+use enums::{MergeRowStatus, Notification};
 use handler::Action;
+use queue::SendOrigin;
 use tokio::sync::mpsc;
 
 #[tokio::main]
 async fn main() -> Result<()> {
   let mut terminal = tui::init()?;
-  let mut app = App::new();
-
   let (tx, mut rx) = mpsc::channel(10);
+  let mut app = App::new(tx.clone());
 
   let tick_rate = std::time::Duration::from_millis(250);
   let tx_tick = tx.clone();
@@ -50,6 +60,7 @@ async fn main() -> Result<()> {
         }
         Action::LogsFetched(logs) => {
           app.dashboard.logs = logs;
+          app.index_recipient_groups();
           app.set_notification(Notification::Success("Logs updated".to_string()));
         }
         Action::LogsFailed(err) => {
@@ -68,6 +79,7 @@ async fn main() -> Result<()> {
         }
         Action::JobsFetched(jobs) => {
           app.scheduled.jobs = jobs;
+          app.index_recipient_groups();
           app.set_notification(Notification::Success("Scheduled jobs updated".to_string()));
         }
         Action::JobsFailed(err) => {
@@ -80,6 +92,55 @@ async fn main() -> Result<()> {
         Action::JobActionFailed(err) => {
           app.set_notification(Notification::Error(format!("Action failed: {}", err)));
         }
+        Action::MergeRowUpdated(row, status) => app.update_merge_row(row, status),
+        Action::QueueUpdated(stats) => app.queue_stats = stats,
+        Action::QueueJobStarted(origin) => {
+          if let SendOrigin::Merge(row) = origin {
+            app.update_merge_row(row, MergeRowStatus::InFlight);
+          }
+        }
+        Action::QueueJobSent(origin) => {
+          app.outbox.refresh();
+          let _ = app.sent.refresh();
+          let unsaved_before = app.contacts.suggestions.len();
+          app.contacts.refresh_suggestions();
+          match origin {
+            SendOrigin::Compose if app.contacts.suggestions.len() > unsaved_before => app
+              .set_notification(Notification::Success(
+                "Email sent! New addresses can be saved from Contacts [9]".to_string(),
+              )),
+            SendOrigin::Compose => app.set_notification(Notification::Success(
+              "Email sent successfully!".to_string(),
+            )),
+            SendOrigin::Merge(row) => app.update_merge_row(row, MergeRowStatus::Sent),
+            SendOrigin::Outbox => {
+              app.set_notification(Notification::Success("Outbox message sent".to_string()))
+            }
+          }
+        }
+        Action::ArchiveFailed(err) => {
+          app.set_notification(Notification::Error(format!(
+            "Sent, but not archived: {}",
+            err
+          )));
+        }
+        Action::QueueJobRetrying(origin, err) => {
+          app.outbox.refresh();
+          match origin {
+            SendOrigin::Merge(row) => app.update_merge_row(row, MergeRowStatus::Retrying(err)),
+            _ => app.set_notification(Notification::Error(format!(
+              "Sending failed, will retry: {}",
+              err
+            ))),
+          }
+        }
+        Action::QueueJobFailed(origin, err) => {
+          app.outbox.refresh();
+          match origin {
+            SendOrigin::Merge(row) => app.update_merge_row(row, MergeRowStatus::Failed(err)),
+            _ => app.set_notification(Notification::Error(format!("Sending failed: {}", err))),
+          }
+        }
       }
     }
     if event::poll(std::time::Duration::from_millis(10))? {
@@ -0,0 +1,191 @@
+use crate::compiler;
+use crate::models::{EmailDraft, split_address_list};
+use anyhow::{Context, Result};
+use std::path::Path;
+
+pub struct MergeData {
+  pub headers: Vec<String>,
+  pub rows: Vec<Vec<String>>,
+}
+
+impl MergeData {
+  pub fn value(&self, row: usize, column: usize) -> &str {
+    self
+      .rows
+      .get(row)
+      .and_then(|r| r.get(column))
+      .map(String::as_str)
+      .unwrap_or_default()
+  }
+
+  /// Best guess for the address column: the first header mentioning "mail".
+  pub fn guess_address_column(&self) -> usize {
+    self
+      .headers
+      .iter()
+      .position(|h| h.to_lowercase().contains("mail"))
+      .unwrap_or(0)
+  }
+}
+
+pub fn load_csv(path: &Path) -> Result<MergeData> {
+  let mut reader = csv::ReaderBuilder::new()
+    .flexible(true)
+    .trim(csv::Trim::All)
+    .from_path(path)
+    .with_context(|| format!("Could not open {}", path.display()))?;
+
+  let headers: Vec<String> = reader
+    .headers()
+    .context("CSV has no header row")?
+    .iter()
+    .enumerate()
+    .map(|(idx, header)| placeholder_name(idx, header))
+    .collect();
+
+  let mut rows = Vec::new();
+  for (idx, record) in reader.records().enumerate() {
+    let record = record.with_context(|| format!("Malformed CSV row {}", idx + 2))?;
+    if record.iter().all(str::is_empty) {
+      continue;
+    }
+    rows.push(record.iter().map(str::to_string).collect());
+  }
+
+  if headers.is_empty() {
+    anyhow::bail!("CSV has no columns");
+  }
+
+  Ok(MergeData { headers, rows })
+}
+
+/// Turns a column header into a usable `{{placeholder}}` name: characters placeholders cannot
+/// contain become `_` (so `First Name` is `{{First_Name}}`), and blank headers are numbered.
+fn placeholder_name(idx: usize, header: &str) -> String {
+  let name: String = header
+    .chars()
+    .map(|c| {
+      if compiler::is_placeholder_char(c) {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect();
+  if name.is_empty() {
+    format!("column{}", idx + 1)
+  } else {
+    name
+  }
+}
+
+/// Builds the draft for a single row: the row's address becomes the only To recipient
+/// and every column is available as a `{{column}}` placeholder.
+pub fn personalise(
+  draft: &EmailDraft,
+  data: &MergeData,
+  row: usize,
+  address_column: usize,
+) -> EmailDraft {
+  let mut personalised = draft.clone();
+  personalised.to = split_address_list(data.value(row, address_column));
+
+  for (column, header) in data.headers.iter().enumerate() {
+    personalised
+      .variables
+      .insert(header.clone(), data.value(row, column).to_string());
+  }
+
+  personalised
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+
+  fn csv(content: &str) -> MergeData {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(content.as_bytes()).unwrap();
+    load_csv(file.path()).unwrap()
+  }
+
+  #[test]
+  fn load_csv_reads_quoted_fields_and_skips_blank_rows() {
+    let data = csv("name,email\n\"Doe, Jane\", jane@example.com \n,\nBob\n");
+
+    assert_eq!(data.headers, ["name", "email"]);
+    assert_eq!(data.rows.len(), 2);
+    assert_eq!(data.value(0, 0), "Doe, Jane");
+    assert_eq!(data.value(0, 1), "jane@example.com");
+    assert_eq!(data.value(1, 1), "");
+    assert_eq!(data.guess_address_column(), 1);
+  }
+
+  #[test]
+  fn load_csv_turns_headers_into_placeholder_names() {
+    let data = csv("First Name,e-mail,,Org (legal)\na,b,c,d\n");
+
+    assert_eq!(
+      data.headers,
+      ["First_Name", "e-mail", "column3", "Org__legal_"]
+    );
+  }
+
+  #[test]
+  fn load_csv_rejects_missing_files_and_empty_input() {
+    assert!(load_csv(Path::new("/nonexistent/merge.csv")).is_err());
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    assert!(load_csv(file.path()).is_err());
+  }
+
+  #[test]
+  fn personalise_sets_recipient_and_variables() {
+    let data = csv("email,First Name\n\"Jane <jane@example.com>, bob@example.com\",Jane\n");
+    let draft = EmailDraft {
+      to: vec!["template@example.com".to_string()],
+      cc: vec!["boss@example.com".to_string()],
+      ..EmailDraft::default()
+    };
+
+    let personalised = personalise(&draft, &data, 0, 0);
+
+    assert_eq!(
+      personalised.to,
+      ["Jane <jane@example.com>", "bob@example.com"]
+    );
+    assert_eq!(personalised.cc, draft.cc);
+    assert_eq!(personalised.variables["First_Name"], "Jane");
+  }
+
+  #[test]
+  fn personalise_leaves_to_empty_without_an_address() {
+    let data = csv("email,name\n,Jane\n");
+    let draft = EmailDraft {
+      cc: vec!["boss@example.com".to_string()],
+      ..EmailDraft::default()
+    };
+
+    assert!(personalise(&draft, &data, 0, 0).to.is_empty());
+  }
+
+  #[test]
+  fn personalised_values_are_escaped_in_the_body() {
+    let data = csv("email,company\na@example.com,A & B <Co> *Ltd*\n");
+    let draft = EmailDraft {
+      body: "Hello {{company}}".to_string(),
+      ..EmailDraft::default()
+    };
+    let personalised = personalise(&draft, &data, 0, 0);
+
+    let body = compiler::fill_placeholders(
+      &personalised.body,
+      &personalised.variables,
+      true,
+      &mut Vec::new(),
+    );
+
+    assert_eq!(body, r"Hello A \& B \<Co\> \*Ltd\*");
+  }
+}
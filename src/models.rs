@@ -1,17 +1,402 @@
+use crate::enums::TrackingMode;
+pub use crate::enums::{JobStatus, OutboxStatus};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
-pub use crate::enums::JobStatus;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmailDraft {
-  pub recipient: String,
+  #[serde(default = "new_id")]
+  pub id: String,
+  #[serde(default)]
+  pub name: String,
+  #[serde(
+    default,
+    alias = "recipient",
+    deserialize_with = "deserialize_address_list"
+  )]
+  pub to: Vec<String>,
+  #[serde(default)]
+  pub cc: Vec<String>,
+  #[serde(default)]
+  pub bcc: Vec<String>,
   pub subject: String,
   pub body: String,
   pub attachments: Vec<PathBuf>,
   pub scheduled_at: Option<DateTime<Utc>>,
   #[serde(default)]
   pub schedule: ScheduleMetadata,
+  #[serde(default)]
+  pub variables: BTreeMap<String, String>,
+  /// Attachments and inline images the user chose to send without if they cannot be read.
+  #[serde(default)]
+  pub waived_files: Vec<PathBuf>,
+  /// Name of the HTML theme; empty means the default one.
+  #[serde(default)]
+  pub theme: String,
+  /// Name of the signature template; empty means the default one and `none` leaves it out.
+  #[serde(default)]
+  pub signature: String,
+  /// Id of the sender profile; unknown or empty ids use the first profile.
+  #[serde(default)]
+  pub profile: String,
+  #[serde(default)]
+  pub tracking: TrackingMode,
+}
+
+/// Random identifier for drafts, contacts and other stored records.
+fn new_id() -> String {
+  uuid::Uuid::new_v4().to_string()
+}
+
+impl Default for EmailDraft {
+  fn default() -> Self {
+    Self {
+      id: new_id(),
+      name: String::new(),
+      to: Vec::new(),
+      cc: Vec::new(),
+      bcc: Vec::new(),
+      subject: String::new(),
+      body: String::new(),
+      attachments: Vec::new(),
+      scheduled_at: None,
+      schedule: ScheduleMetadata::default(),
+      variables: BTreeMap::new(),
+      waived_files: Vec::new(),
+      theme: String::new(),
+      signature: String::new(),
+      profile: String::new(),
+      tracking: TrackingMode::default(),
+    }
+  }
+}
+
+impl EmailDraft {
+  pub fn display_name(&self) -> String {
+    if !self.name.trim().is_empty() {
+      self.name.clone()
+    } else if !self.subject.trim().is_empty() {
+      self.subject.clone()
+    } else {
+      "Untitled draft".to_string()
+    }
+  }
+
+  /// Copies the draft under a fresh id so it is saved to its own file.
+  pub fn duplicate(&self) -> Self {
+    Self {
+      id: new_id(),
+      name: format!("Copy of {}", self.display_name()),
+      ..self.clone()
+    }
+  }
+
+  /// Turns the draft into a reusable template; schedules are not carried over.
+  pub fn to_template(&self) -> Self {
+    Self {
+      id: new_id(),
+      name: self.display_name(),
+      scheduled_at: None,
+      schedule: ScheduleMetadata::default(),
+      ..self.clone()
+    }
+  }
+
+  /// Starts a fresh draft from a template, keeping its variable defaults.
+  pub fn from_template(template: &EmailDraft) -> Self {
+    Self {
+      id: new_id(),
+      name: String::new(),
+      ..template.clone()
+    }
+  }
+
+  pub fn has_recipients(&self) -> bool {
+    !(self.to.is_empty() && self.cc.is_empty() && self.bcc.is_empty())
+  }
+
+  /// Whether any recipient list names an `@group` that has to be expanded.
+  pub fn has_group_references(&self) -> bool {
+    self
+      .to
+      .iter()
+      .chain(&self.cc)
+      .chain(&self.bcc)
+      .any(|address| is_group_reference(address))
+  }
+}
+
+// Drafts saved before Cc/Bcc support stored a single `recipient` string
+fn deserialize_address_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+  }
+
+  Ok(match OneOrMany::deserialize(deserializer)? {
+    OneOrMany::One(s) => split_address_list(&s),
+    OneOrMany::Many(v) => v,
+  })
+}
+
+/// Splits a header-style address list on `,` or `;`, ignoring separators
+/// inside quoted display names and angle brackets.
+pub fn split_address_list(input: &str) -> Vec<String> {
+  let mut addresses = Vec::new();
+  let mut current = String::new();
+  let mut in_quotes = false;
+  let mut in_angle = false;
+  let mut escaped = false;
+
+  for c in input.chars() {
+    match c {
+      _ if escaped => escaped = false,
+      '\\' if in_quotes => escaped = true,
+      '"' => in_quotes = !in_quotes,
+      '<' if !in_quotes => in_angle = true,
+      '>' if !in_quotes => in_angle = false,
+      ',' | ';' if !in_quotes && !in_angle => {
+        addresses.push(std::mem::take(&mut current));
+        continue;
+      }
+      _ => {}
+    }
+    current.push(c);
+  }
+  addresses.push(current);
+
+  addresses
+    .into_iter()
+    .map(|a| a.trim().to_string())
+    .filter(|a| !a.is_empty())
+    .collect()
+}
+
+/// Parses `key=value; key=value` draft variables. `\;` is a literal semicolon and `\\` a
+/// literal backslash, so values can contain either.
+pub fn parse_variables(input: &str) -> BTreeMap<String, String> {
+  let mut pairs = Vec::new();
+  let mut current = String::new();
+  let mut chars = input.chars();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => match chars.next() {
+        Some(next @ (';' | '\\')) => current.push(next),
+        Some(next) => {
+          current.push(c);
+          current.push(next);
+        }
+        None => current.push(c),
+      },
+      ';' => pairs.push(std::mem::take(&mut current)),
+      _ => current.push(c),
+    }
+  }
+  pairs.push(current);
+
+  pairs
+    .iter()
+    .filter_map(|pair| pair.split_once('='))
+    .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+    .filter(|(k, _)| !k.is_empty())
+    .collect()
+}
+
+/// The inverse of `parse_variables`.
+pub fn format_variables(variables: &BTreeMap<String, String>) -> String {
+  let escape = |s: &str| s.replace('\\', "\\\\").replace(';', "\\;");
+  variables
+    .iter()
+    .map(|(k, v)| format!("{}={}", escape(k), escape(v)))
+    .collect::<Vec<_>>()
+    .join("; ")
+}
+
+/// Returns the `addr` part of `"Name" <addr>`, or the input unchanged.
+pub fn bare_address(address: &str) -> &str {
+  match (address.rfind('<'), address.rfind('>')) {
+    (Some(start), Some(end)) if start < end => address[start + 1..end].trim(),
+    _ => address.trim(),
+  }
+}
+
+/// Formats `"Name" <addr>`, escaping quotes and backslashes in the name. An empty name
+/// gives the bare address.
+pub fn format_mailbox(name: &str, address: &str) -> String {
+  if name.is_empty() {
+    address.to_string()
+  } else {
+    let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\" <{}>", name, address)
+  }
+}
+
+/// Display name of an address in `"Name" <addr>` form, if it has one.
+pub fn address_name(address: &str) -> Option<String> {
+  let start = address.rfind('<')?;
+  let raw = address[..start].trim();
+  let name = match raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')) {
+    Some(quoted) => unquote(quoted),
+    None => raw.to_string(),
+  };
+  let name = name.trim();
+  (!name.is_empty()).then(|| name.to_string())
+}
+
+fn unquote(quoted: &str) -> String {
+  let mut out = String::with_capacity(quoted.len());
+  let mut chars = quoted.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => out.extend(chars.next()),
+      _ => out.push(c),
+    }
+  }
+  out
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contact {
+  #[serde(default = "new_id")]
+  pub id: String,
+  pub name: String,
+  pub addresses: Vec<String>,
+  #[serde(default)]
+  pub timezone: String,
+  #[serde(default)]
+  pub notes: String,
+}
+
+impl Contact {
+  pub fn new(name: String, addresses: Vec<String>) -> Self {
+    Self {
+      id: new_id(),
+      name,
+      addresses,
+      timezone: String::new(),
+      notes: String::new(),
+    }
+  }
+
+  pub fn has_address(&self, address: &str) -> bool {
+    let address = bare_address(address);
+    self
+      .addresses
+      .iter()
+      .any(|a| a.eq_ignore_ascii_case(address))
+  }
+
+  /// The address formatted for a recipient field, e.g. `"Ada Lovelace" <ada@example.com>`.
+  pub fn mailbox(&self, address: &str) -> String {
+    format_mailbox(&self.name, address)
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContactBook {
+  pub contacts: Vec<Contact>,
+  #[serde(default)]
+  pub groups: Vec<RecipientGroup>,
+  /// Sent-to addresses the user chose not to save.
+  #[serde(default)]
+  pub ignored: Vec<String>,
+}
+
+impl ContactBook {
+  pub fn find_by_address(&self, address: &str) -> Option<&Contact> {
+    self.contacts.iter().find(|c| c.has_address(address))
+  }
+
+  /// Looks up a group by name, with or without the leading `@`.
+  pub fn group(&self, name: &str) -> Option<&RecipientGroup> {
+    let name = name.trim().trim_start_matches('@');
+    self
+      .groups
+      .iter()
+      .find(|g| g.name.eq_ignore_ascii_case(name))
+  }
+
+  pub fn is_known(&self, address: &str) -> bool {
+    let bare = bare_address(address);
+    self.find_by_address(bare).is_some()
+      || self.ignored.iter().any(|a| a.eq_ignore_ascii_case(bare))
+  }
+}
+
+/// Recipients who agreed to read tracking, and when.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsentRegistry {
+  pub records: Vec<ConsentRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentRecord {
+  pub address: String,
+  pub granted_at: DateTime<Utc>,
+}
+
+impl ConsentRegistry {
+  pub fn granted_at(&self, address: &str) -> Option<DateTime<Utc>> {
+    let bare = bare_address(address);
+    self
+      .records
+      .iter()
+      .find(|r| r.address.eq_ignore_ascii_case(bare))
+      .map(|r| r.granted_at)
+  }
+
+  pub fn has_consent(&self, address: &str) -> bool {
+    self.granted_at(address).is_some()
+  }
+
+  /// Records consent for `address` now, keeping the original date if it was already given.
+  pub fn grant(&mut self, address: &str) {
+    if !self.has_consent(address) {
+      self.records.push(ConsentRecord {
+        address: bare_address(address).to_string(),
+        granted_at: Utc::now(),
+      });
+    }
+  }
+
+  pub fn revoke(&mut self, address: &str) {
+    let bare = bare_address(address);
+    self
+      .records
+      .retain(|r| !r.address.eq_ignore_ascii_case(bare));
+  }
+
+  /// Recipients of `draft` that have not agreed to tracking, as bare addresses.
+  pub fn without_consent(&self, draft: &EmailDraft) -> Vec<String> {
+    draft
+      .to
+      .iter()
+      .chain(&draft.cc)
+      .chain(&draft.bcc)
+      .filter(|a| !self.has_consent(a))
+      .map(|a| bare_address(a).to_string())
+      .collect()
+  }
+}
+
+/// A named distribution list, written as `@name` in a recipient field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecipientGroup {
+  pub name: String,
+  pub members: Vec<String>,
+}
+
+/// Whether a recipient entry refers to a group rather than a mailbox.
+pub fn is_group_reference(address: &str) -> bool {
+  address.trim().starts_with('@')
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +445,9 @@ pub struct LogEntry {
   pub city: String,
   pub user_agent: String,
   pub timezone: String,
+  /// The `sig` parameter the pixel was requested with, kept so hits can be re-verified.
+  #[serde(default)]
+  pub signature: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -79,14 +467,123 @@ pub struct AttachmentStub {
   pub content: Vec<u8>,
 }
 
+/// A message waiting to be accepted by the SMTP server. Removed once it is sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+  pub id: String,
+  pub draft: EmailDraft,
+  pub created_at: DateTime<Utc>,
+  #[serde(default)]
+  pub status: OutboxStatus,
+  #[serde(default)]
+  pub attempts: u32,
+  #[serde(default)]
+  pub last_error: Option<String>,
+  #[serde(default)]
+  pub next_attempt_at: Option<DateTime<Utc>>,
+  /// Local part of the Message-ID header, fixed when the entry is created so every attempt
+  /// sends the same message.
+  #[serde(default = "new_message_id")]
+  pub message_id: String,
+  /// Token for the tracking pixel, used only if the draft is tracked.
+  #[serde(default = "crate::compiler::new_tracking_id")]
+  pub tracking_id: String,
+}
+
+fn new_message_id() -> String {
+  uuid::Uuid::new_v4().to_string()
+}
+
+impl OutboxEntry {
+  pub fn new(draft: EmailDraft) -> Self {
+    Self {
+      id: uuid::Uuid::new_v4().to_string(),
+      message_id: new_message_id(),
+      tracking_id: crate::compiler::new_tracking_id(),
+      draft,
+      created_at: Utc::now(),
+      status: OutboxStatus::Queued,
+      attempts: 0,
+      last_error: None,
+      next_attempt_at: None,
+    }
+  }
+}
+
+/// Index entry for a message in the Sent archive. The raw message is stored next to it as `<id>.eml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentRecord {
+  pub id: String,
+  pub sent_at: DateTime<Utc>,
+  pub to: Vec<String>,
+  #[serde(default)]
+  pub cc: Vec<String>,
+  #[serde(default)]
+  pub bcc: Vec<String>,
+  pub subject: String,
+  pub tracking_id: String,
+  pub smtp_response: String,
+  /// Recipient groups the message was addressed to.
+  #[serde(default)]
+  pub groups: Vec<String>,
+}
+
+/// Local record of what a tracking token stands for. The token itself is random, so the
+/// pixel URL reveals nothing about the recipient.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedMessage {
+  pub token: String,
+  /// Sent archive ID, or the Worker's job ID for scheduled messages.
+  pub message_id: String,
+  pub recipients: Vec<String>,
+  pub subject: String,
+  /// When the message was sent, or is scheduled to go out.
+  pub sent_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledJob {
   pub id: String,
   pub recipient: String,
+  #[serde(default)]
+  pub cc: String,
+  #[serde(default)]
+  pub bcc: String,
   pub subject: String,
   pub body: String,
   pub scheduled_at: DateTime<Utc>,
   pub recipient_timezone: String,
   pub status: JobStatus,
   pub attachments: Vec<AttachmentStub>,
+  #[serde(default)]
+  pub group: String,
+  #[serde(default)]
+  pub tracking_id: String,
+  /// Name of the sender profile the job was scheduled from.
+  #[serde(default)]
+  pub profile: String,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn mailbox_escapes_quotes_and_backslashes() {
+    let formatted = format_mailbox(r#"Ada "The Countess" \ Lovelace"#, "ada@example.com");
+    assert_eq!(
+      formatted,
+      r#""Ada \"The Countess\" \\ Lovelace" <ada@example.com>"#
+    );
+    assert_eq!(
+      address_name(&formatted).as_deref(),
+      Some(r#"Ada "The Countess" \ Lovelace"#)
+    );
+    let parsed: lettre::message::Mailbox = formatted.parse().unwrap();
+    assert_eq!(
+      parsed.name.as_deref(),
+      Some(r#"Ada "The Countess" \ Lovelace"#)
+    );
+    assert_eq!(format_mailbox("", "ada@example.com"), "ada@example.com");
+  }
 }
@@ -0,0 +1,451 @@
+use crate::compiler;
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+use unicode_width::UnicodeWidthStr;
+
+/// Renders Markdown as the `text/plain` alternative: links become numbered references listed
+/// at the end, lists keep their markers, headings are underlined, tables are laid out in
+/// columns and quotes are prefixed with `>`. Text is wrapped at `width` columns (0 = never).
+pub fn render(markdown: &str, width: usize) -> String {
+  let mut renderer = PlainRenderer {
+    width,
+    ..Default::default()
+  };
+  for event in Parser::new_ext(markdown, compiler::markdown_options()) {
+    renderer.handle(event);
+  }
+  renderer.finish()
+}
+
+#[derive(Default)]
+struct PlainRenderer {
+  width: usize,
+  out: String,
+  at_blank_line: bool,
+  // Inline text of the block being built
+  text: String,
+  quote_depth: usize,
+  // One entry per nested list: the next number for ordered lists, None for bullets.
+  lists: Vec<Option<u64>>,
+  // Widths of the markers of the open list items, outermost first.
+  item_indents: Vec<usize>,
+  // Marker of the current item until its first line is written.
+  item_marker: Option<String>,
+  // Open links: target and where their text starts in the active buffer.
+  open_links: Vec<(String, usize)>,
+  // Open image: target and where its alt text starts.
+  open_image: Option<(String, usize)>,
+  links: Vec<String>,
+  footnotes: Vec<String>,
+  code_block: Option<String>,
+  table: Option<Vec<Vec<String>>>,
+}
+
+impl PlainRenderer {
+  /// Where inline text currently goes: the open table cell or the current block.
+  fn buffer(&mut self) -> &mut String {
+    match self
+      .table
+      .as_mut()
+      .and_then(|rows| rows.last_mut())
+      .and_then(|row| row.last_mut())
+    {
+      Some(cell) => cell,
+      None => &mut self.text,
+    }
+  }
+
+  fn push_text(&mut self, text: &str) {
+    self.buffer().push_str(text);
+  }
+
+  fn handle(&mut self, event: Event) {
+    match event {
+      Event::Start(tag) => self.start(tag),
+      Event::End(tag) => self.end(tag),
+      Event::Text(text) => match &mut self.code_block {
+        Some(code) => code.push_str(&text),
+        None => self.push_text(&text),
+      },
+      Event::Code(code) => self.push_text(&code),
+      Event::InlineHtml(html) => self.push_text(&html),
+      Event::Html(html) => {
+        self.flush();
+        self.write_lines(html.trim_end());
+        self.blank_line();
+      }
+      Event::SoftBreak => self.push_text(" "),
+      Event::HardBreak => self.push_text("\n"),
+      Event::Rule => {
+        self.flush();
+        let rule = "-".repeat(self.available_width().min(40));
+        self.write_lines(&rule);
+        self.blank_line();
+      }
+      Event::TaskListMarker(checked) => self.push_text(if checked { "[x] " } else { "[ ] " }),
+      Event::FootnoteReference(label) => {
+        let number = self.footnote_number(&label);
+        self.push_text(&format!("[^{}]", number));
+      }
+      _ => {}
+    }
+  }
+
+  fn start(&mut self, tag: Tag) {
+    match tag {
+      Tag::Paragraph | Tag::Heading { .. } => self.flush(),
+      Tag::BlockQuote(_) => {
+        self.flush();
+        self.quote_depth += 1;
+      }
+      Tag::CodeBlock(_) => {
+        self.flush();
+        self.code_block = Some(String::new());
+      }
+      Tag::List(start) => {
+        self.flush();
+        self.lists.push(start);
+      }
+      Tag::Item => {
+        self.flush();
+        let marker = match self.lists.last_mut() {
+          Some(Some(n)) => {
+            *n += 1;
+            format!("{}. ", *n - 1)
+          }
+          _ => "- ".to_string(),
+        };
+        self.item_indents.push(marker.width());
+        self.item_marker = Some(marker);
+      }
+      Tag::FootnoteDefinition(label) => {
+        self.flush();
+        let marker = format!("[^{}]: ", self.footnote_number(&label));
+        self.item_indents.push(marker.width());
+        self.item_marker = Some(marker);
+      }
+      Tag::Link { dest_url, .. } => {
+        let start = self.buffer().len();
+        self.open_links.push((dest_url.to_string(), start));
+      }
+      Tag::Image { dest_url, .. } => {
+        let start = self.buffer().len();
+        self.open_image = Some((dest_url.to_string(), start));
+      }
+      Tag::Table(_) => {
+        self.flush();
+        self.table = Some(Vec::new());
+      }
+      Tag::TableHead | Tag::TableRow => {
+        if let Some(rows) = &mut self.table {
+          rows.push(Vec::new());
+        }
+      }
+      Tag::TableCell => {
+        if let Some(row) = self.table.as_mut().and_then(|rows| rows.last_mut()) {
+          row.push(String::new());
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn end(&mut self, tag: TagEnd) {
+    match tag {
+      TagEnd::Paragraph => {
+        self.flush();
+        if self.lists.is_empty() {
+          self.blank_line();
+        }
+      }
+      TagEnd::Heading(level) => {
+        let title = std::mem::take(&mut self.text);
+        let title = title.trim();
+        if title.is_empty() {
+          return;
+        }
+        self.write_wrapped(title);
+        let underline = if level == HeadingLevel::H1 { "=" } else { "-" };
+        let length = title
+          .lines()
+          .map(UnicodeWidthStr::width)
+          .max()
+          .unwrap_or(0)
+          .min(self.available_width());
+        self.write_lines(&underline.repeat(length));
+        self.blank_line();
+      }
+      TagEnd::BlockQuote(_) => {
+        self.flush();
+        // The quote's last block left a `>` separator; the quote ends there instead
+        if self.at_blank_line
+          && let Some(end) = self.out.trim_end_matches('\n').rfind('\n')
+        {
+          self.out.truncate(end + 1);
+          self.at_blank_line = false;
+        }
+        self.quote_depth = self.quote_depth.saturating_sub(1);
+        self.blank_line();
+      }
+      TagEnd::CodeBlock => {
+        if let Some(code) = self.code_block.take() {
+          for line in code.trim_end_matches('\n').lines() {
+            self.write_lines(&format!("    {}", line));
+          }
+        }
+        self.blank_line();
+      }
+      TagEnd::List(_) => {
+        self.flush();
+        self.lists.pop();
+        if self.lists.is_empty() {
+          self.blank_line();
+        }
+      }
+      TagEnd::Item | TagEnd::FootnoteDefinition => {
+        self.flush();
+        let unused_marker = self.item_marker.take();
+        self.item_indents.pop();
+        // An empty item still shows its marker
+        if let Some(marker) = unused_marker {
+          self.write_lines(marker.trim_end());
+        }
+      }
+      TagEnd::Link => {
+        let Some((url, start)) = self.open_links.pop() else {
+          return;
+        };
+        let shown = self.buffer()[start..].trim().to_string();
+        // Autolinks and bare addresses already show their target; in-message anchors mean
+        // nothing without the HTML part.
+        let redundant = shown == url
+          || url.strip_prefix("mailto:") == Some(shown.as_str())
+          || url.starts_with('#');
+        if !redundant {
+          let number = self.link_number(&url);
+          self.push_text(&format!(" [{}]", number));
+        }
+      }
+      TagEnd::Image => {
+        let Some((url, start)) = self.open_image.take() else {
+          return;
+        };
+        let alt = self.buffer().split_off(start);
+        let label = if alt.trim().is_empty() {
+          "image".to_string()
+        } else {
+          format!("image: {}", alt.trim())
+        };
+        let reference = if url.starts_with("http://") || url.starts_with("https://") {
+          format!(" [{}]", self.link_number(&url))
+        } else {
+          String::new()
+        };
+        self.push_text(&format!("[{}]{}", label, reference));
+      }
+      TagEnd::Table => {
+        if let Some(rows) = self.table.take() {
+          self.write_table(rows);
+        }
+        self.blank_line();
+      }
+      _ => {}
+    }
+  }
+
+  fn link_number(&mut self, url: &str) -> usize {
+    numbered(&mut self.links, url)
+  }
+
+  fn footnote_number(&mut self, label: &str) -> usize {
+    numbered(&mut self.footnotes, label)
+  }
+
+  /// Quote markers plus the indentation of every open list item.
+  fn prefix(&self) -> String {
+    let mut prefix = "> ".repeat(self.quote_depth);
+    prefix.push_str(&" ".repeat(self.item_indents.iter().sum()));
+    prefix
+  }
+
+  fn available_width(&self) -> usize {
+    match self.width {
+      0 => usize::MAX,
+      width => width.saturating_sub(self.prefix().width()).max(20),
+    }
+  }
+
+  /// Writes the pending inline text as a wrapped block.
+  fn flush(&mut self) {
+    let text = std::mem::take(&mut self.text);
+    let text = text.trim();
+    if !text.is_empty() {
+      self.write_wrapped(text);
+    }
+  }
+
+  /// Wraps `text` under the current prefix, putting a pending list marker on the first line.
+  fn write_wrapped(&mut self, text: &str) {
+    let rest = self.prefix();
+    let first = match self.item_marker.take() {
+      Some(marker) => {
+        let outer = rest.len() - marker.width();
+        format!("{}{}", &rest[..outer], marker)
+      }
+      None => rest.clone(),
+    };
+
+    let wrapped = if self.width == 0 {
+      let mut lines = text.lines();
+      let mut out = format!("{}{}", first, lines.next().unwrap_or_default());
+      for line in lines {
+        out.push('\n');
+        out.push_str(&rest);
+        out.push_str(line);
+      }
+      out
+    } else {
+      let options = textwrap::Options::new(self.width)
+        .initial_indent(&first)
+        .subsequent_indent(&rest)
+        .break_words(false);
+      textwrap::fill(text, options)
+    };
+    self.out.push_str(wrapped.trim_end());
+    self.out.push('\n');
+    self.at_blank_line = false;
+  }
+
+  /// Writes pre-formatted lines under the current prefix without wrapping them.
+  fn write_lines(&mut self, text: &str) {
+    let prefix = self.prefix();
+    for line in text.lines() {
+      self.out.push_str(format!("{}{}", prefix, line).trim_end());
+      self.out.push('\n');
+      self.at_blank_line = false;
+    }
+  }
+
+  /// Separates blocks. Inside a quote the blank line keeps its `>` so the quote stays whole.
+  fn blank_line(&mut self) {
+    if self.out.is_empty() || self.at_blank_line {
+      return;
+    }
+    let quote = "> ".repeat(self.quote_depth);
+    self.out.push_str(quote.trim_end());
+    self.out.push('\n');
+    self.at_blank_line = true;
+  }
+
+  fn write_table(&mut self, rows: Vec<Vec<String>>) {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+      .map(|c| {
+        rows
+          .iter()
+          .filter_map(|row| row.get(c))
+          .map(|cell| cell.trim().width())
+          .max()
+          .unwrap_or(0)
+      })
+      .collect();
+
+    let mut lines = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+      let cells: Vec<String> = widths
+        .iter()
+        .enumerate()
+        .map(|(c, &width)| {
+          let cell = row.get(c).map(|s| s.trim()).unwrap_or_default();
+          format!("{}{}", cell, " ".repeat(width - cell.width()))
+        })
+        .collect();
+      lines.push(cells.join(" | "));
+      if i == 0 {
+        let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        lines.push(rule.join("-+-"));
+      }
+    }
+    self.write_lines(&lines.join("\n"));
+  }
+
+  fn finish(mut self) -> String {
+    self.flush();
+    let mut out = self.out.trim_end().to_string();
+    if !self.links.is_empty() {
+      out.push_str("\n\n");
+      for (i, url) in self.links.iter().enumerate() {
+        out.push_str(&format!("[{}] {}\n", i + 1, url));
+      }
+    }
+    out.trim_end().to_string()
+  }
+}
+
+/// 1-based position of `item` in `list`, appending it when it is new.
+fn numbered(list: &mut Vec<String>, item: &str) -> usize {
+  match list.iter().position(|existing| existing == item) {
+    Some(i) => i + 1,
+    None => {
+      list.push(item.to_string());
+      list.len()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn nested_lists_inside_quotes_keep_markers_and_prefixes() {
+    let text = render(
+      "> Plan:\n>\n> 1. First\n>    - nested\n> 2. Second\n\nAfter",
+      72,
+    );
+
+    assert_eq!(
+      text,
+      "> Plan:\n>\n> 1. First\n>    - nested\n> 2. Second\n\nAfter"
+    );
+  }
+
+  #[test]
+  fn redundant_links_get_no_reference() {
+    let text = render(
+      "<https://example.com>, [mail](mailto:a@b.org), <a@b.org> and [below](#next)",
+      0,
+    );
+
+    assert_eq!(
+      text,
+      "https://example.com, mail [1], a@b.org and below\n\n[1] mailto:a@b.org"
+    );
+  }
+
+  #[test]
+  fn links_and_footnotes_are_numbered_once_per_target() {
+    let text = render(
+      "[Docs](https://a.example) and [more docs](https://a.example), [blog](https://b.example)[^n]\n\n[^n]: A note.",
+      0,
+    );
+
+    assert_eq!(
+      text,
+      "Docs [1] and more docs [1], blog [2][^1]\n\n[^1]: A note.\n\n[1] https://a.example\n[2] https://b.example"
+    );
+  }
+
+  #[test]
+  fn width_zero_never_wraps_and_width_wraps_under_the_prefix() {
+    let long = "word ".repeat(30);
+    assert_eq!(render(&long, 0), long.trim());
+
+    let wrapped = render(&format!("> {}", long), 30);
+    assert!(
+      wrapped
+        .lines()
+        .all(|line| line.starts_with("> ") && line.len() <= 30)
+    );
+    assert_eq!(wrapped.matches("word").count(), 30);
+  }
+}
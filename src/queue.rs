@@ -0,0 +1,423 @@
+use crate::config::AppConfig;
+use crate::enums::OutboxStatus;
+use crate::handler::Action;
+use crate::mailer::{self, SentMessage};
+use crate::models::{EmailDraft, OutboxEntry, SentRecord, TrackedMessage};
+use crate::storage::Storage;
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::{self, JoinSet};
+use tokio::time::Instant;
+
+const MAX_ATTEMPTS: u32 = 5;
+const BASE_BACKOFF: Duration = Duration::from_secs(30);
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// Where a queued message came from, so its result can be routed back to the right page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SendOrigin {
+  Compose,
+  Merge(usize),
+  Outbox,
+}
+
+struct QueuedEmail {
+  origin: SendOrigin,
+  config: AppConfig,
+  entry: OutboxEntry,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QueueStats {
+  pub queued: usize,
+  pub in_flight: usize,
+  pub sent: usize,
+  pub failed: usize,
+  pub paused: bool,
+}
+
+impl QueueStats {
+  pub fn is_idle(&self) -> bool {
+    self.queued == 0 && self.in_flight == 0 && self.sent == 0 && self.failed == 0 && !self.paused
+  }
+}
+
+enum Command {
+  Enqueue(Box<QueuedEmail>),
+  Discard(String),
+  Pause,
+  Resume,
+  SetLimits(Limits),
+}
+
+#[derive(Clone, Copy)]
+struct Limits {
+  per_minute: u32,
+  concurrency: usize,
+}
+
+impl Limits {
+  fn from_config(config: &AppConfig) -> Self {
+    Self {
+      per_minute: config.send_rate_per_minute,
+      concurrency: config.max_concurrent_sends.max(1) as usize,
+    }
+  }
+
+  // Spacing sends evenly keeps bursts away from the provider's rate limiter.
+  fn interval(&self) -> Duration {
+    if self.per_minute == 0 {
+      Duration::ZERO
+    } else {
+      Duration::from_secs(60) / self.per_minute
+    }
+  }
+}
+
+fn archive(entry: &OutboxEntry, sent: SentMessage) -> Result<()> {
+  let record = SentRecord {
+    id: entry.id.clone(),
+    sent_at: Utc::now(),
+    to: sent.to,
+    cc: sent.cc,
+    bcc: sent.bcc,
+    subject: sent.subject,
+    tracking_id: sent.tracking_id,
+    smtp_response: sent.smtp_response,
+    groups: sent.groups,
+  };
+  Storage::archive_sent(&record, &sent.eml, &entry.draft)?;
+
+  if record.tracking_id.is_empty() {
+    return Ok(());
+  }
+  Storage::record_tracked_message(TrackedMessage {
+    token: record.tracking_id,
+    message_id: record.id,
+    recipients: record
+      .to
+      .into_iter()
+      .chain(record.cc)
+      .chain(record.bcc)
+      .collect(),
+    subject: record.subject,
+    sent_at: record.sent_at,
+  })
+}
+
+/// Stores the failure on the outbox entry. Returns true when the entry should be retried.
+fn record_failure(entry: &mut OutboxEntry, err: &anyhow::Error) -> bool {
+  entry.attempts += 1;
+  entry.last_error = Some(format!("{:#}", err));
+
+  let retry = mailer::is_transient(err) && entry.attempts < MAX_ATTEMPTS;
+  if retry {
+    let delay = chrono::Duration::from_std(backoff(entry.attempts)).unwrap_or_default();
+    entry.status = OutboxStatus::Retrying;
+    entry.next_attempt_at = Some(Utc::now() + delay);
+  } else {
+    entry.status = OutboxStatus::Failed;
+    entry.next_attempt_at = None;
+  }
+  let _ = Storage::save_outbox_entry(entry);
+  retry
+}
+
+fn backoff(attempts: u32) -> Duration {
+  BASE_BACKOFF
+    .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+    .min(MAX_BACKOFF)
+}
+
+/// Messages waiting for a send slot, either right away or once their backoff is over.
+#[derive(Default)]
+struct Backlog {
+  pending: VecDeque<QueuedEmail>,
+  waiting: Vec<(Instant, QueuedEmail)>,
+  /// Ids discarded while they were being sent; their failures are not retried.
+  discarded: HashSet<String>,
+}
+
+impl Backlog {
+  fn len(&self) -> usize {
+    self.pending.len() + self.waiting.len()
+  }
+
+  /// Queues `email` in place of any earlier copy, delayed until its next attempt.
+  /// Returns false when the entry is in `sending`, since queuing it again while it is on
+  /// the wire would send it twice.
+  fn enqueue(&mut self, email: QueuedEmail, sending: &HashSet<String>, now: Instant) -> bool {
+    let id = &email.entry.id;
+    if sending.contains(id) {
+      return false;
+    }
+    self.pending.retain(|e| e.entry.id != *id);
+    self.waiting.retain(|(_, e)| e.entry.id != *id);
+    self.discarded.remove(id);
+
+    let delay = email
+      .entry
+      .next_attempt_at
+      .and_then(|at| (at - Utc::now()).to_std().ok())
+      .unwrap_or_default();
+    if delay.is_zero() {
+      self.pending.push_back(email);
+    } else {
+      self.waiting.push((now + delay, email));
+    }
+    true
+  }
+
+  fn discard(&mut self, id: String) {
+    self.pending.retain(|e| e.entry.id != id);
+    self.waiting.retain(|(_, e)| e.entry.id != id);
+    self.discarded.insert(id);
+  }
+
+  /// Moves every message whose backoff is over to the back of the pending queue.
+  fn promote_due(&mut self, now: Instant) {
+    let (due, later): (Vec<_>, Vec<_>) = self.waiting.drain(..).partition(|(at, _)| *at <= now);
+    self.waiting = later;
+    self.pending.extend(due.into_iter().map(|(_, email)| email));
+  }
+
+  fn next_wake(&self) -> Option<Instant> {
+    self.waiting.iter().map(|(at, _)| *at).min()
+  }
+}
+
+/// Handle to the background SMTP send queue.
+#[derive(Clone)]
+pub struct SendQueue {
+  commands: mpsc::UnboundedSender<Command>,
+  /// Outbox ids of the messages currently being handed to the SMTP server.
+  in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+impl SendQueue {
+  pub fn spawn(tx: mpsc::Sender<Action>, config: &AppConfig) -> Self {
+    let (commands, rx) = mpsc::unbounded_channel();
+    let in_flight = Arc::new(Mutex::new(HashSet::new()));
+    tokio::spawn(run(rx, tx, Limits::from_config(config), in_flight.clone()));
+    Self {
+      commands,
+      in_flight,
+    }
+  }
+
+  pub fn is_sending(&self, id: &str) -> bool {
+    self.in_flight.lock().is_ok_and(|ids| ids.contains(id))
+  }
+
+  /// Writes `draft` to the outbox, then queues it. Nothing is queued if the write fails.
+  pub fn submit(&self, origin: SendOrigin, config: &AppConfig, draft: EmailDraft) -> Result<()> {
+    let entry = OutboxEntry::new(draft);
+    Storage::save_outbox_entry(&entry)?;
+    self.enqueue(origin, config, entry);
+    Ok(())
+  }
+
+  /// Queues an entry that is already in the outbox, e.g. after a restart or a manual retry.
+  /// Entries that are being sent right now are left alone.
+  pub fn requeue(&self, config: &AppConfig, entry: OutboxEntry) {
+    self.enqueue(SendOrigin::Outbox, config, entry);
+  }
+
+  fn enqueue(&self, origin: SendOrigin, config: &AppConfig, entry: OutboxEntry) {
+    let _ = self.commands.send(Command::Enqueue(Box::new(QueuedEmail {
+      origin,
+      config: config.clone(),
+      entry,
+    })));
+  }
+
+  pub fn discard(&self, id: &str) {
+    let _ = self.commands.send(Command::Discard(id.to_string()));
+  }
+
+  pub fn pause(&self) {
+    let _ = self.commands.send(Command::Pause);
+  }
+
+  pub fn resume(&self) {
+    let _ = self.commands.send(Command::Resume);
+  }
+
+  pub fn set_limits(&self, config: &AppConfig) {
+    let _ = self
+      .commands
+      .send(Command::SetLimits(Limits::from_config(config)));
+  }
+}
+
+async fn run(
+  mut commands: mpsc::UnboundedReceiver<Command>,
+  tx: mpsc::Sender<Action>,
+  mut limits: Limits,
+  in_flight: Arc<Mutex<HashSet<String>>>,
+) {
+  let mut backlog = Backlog::default();
+  let mut running = JoinSet::new();
+  let mut running_ids: HashMap<task::Id, String> = HashMap::new();
+  let mut stats = QueueStats::default();
+  let mut next_slot = Instant::now();
+
+  loop {
+    let now = Instant::now();
+    backlog.promote_due(now);
+
+    while !stats.paused && running.len() < limits.concurrency && Instant::now() >= next_slot {
+      let Some(email) = backlog.pending.pop_front() else {
+        break;
+      };
+      next_slot = Instant::now() + limits.interval();
+      let _ = tx.send(Action::QueueJobStarted(email.origin)).await;
+      let id = email.entry.id.clone();
+      if let Ok(mut ids) = in_flight.lock() {
+        ids.insert(id.clone());
+      }
+      let handle = running.spawn(async move {
+        let result = mailer::send_email(email.config.clone(), email.entry.clone()).await;
+        (email, result)
+      });
+      running_ids.insert(handle.id(), id);
+    }
+
+    stats.queued = backlog.len();
+    stats.in_flight = running.len();
+    let _ = tx.send(Action::QueueUpdated(stats)).await;
+
+    let rate_limited =
+      !stats.paused && !backlog.pending.is_empty() && running.len() < limits.concurrency;
+    let wake_at = backlog
+      .next_wake()
+      .into_iter()
+      .chain(rate_limited.then_some(next_slot))
+      .min();
+
+    tokio::select! {
+      command = commands.recv() => match command {
+        Some(Command::Enqueue(email)) => {
+          let sending: HashSet<String> = running_ids.values().cloned().collect();
+          backlog.enqueue(*email, &sending, Instant::now());
+        }
+        Some(Command::Discard(id)) => backlog.discard(id),
+        Some(Command::Pause) => stats.paused = true,
+        Some(Command::Resume) => stats.paused = false,
+        Some(Command::SetLimits(new_limits)) => limits = new_limits,
+        None => break,
+      },
+      Some(joined) = running.join_next_with_id(), if !running.is_empty() => {
+        let task_id = match &joined {
+          Ok((task_id, _)) => *task_id,
+          Err(e) => e.id(),
+        };
+        if let Some(id) = running_ids.remove(&task_id)
+          && let Ok(mut ids) = in_flight.lock()
+        {
+          ids.remove(&id);
+        }
+        let Ok((_, (email, result))) = joined else {
+          stats.failed += 1;
+          continue;
+        };
+        let was_discarded = backlog.discarded.remove(&email.entry.id);
+        match result {
+          Ok(sent) => {
+            stats.sent += 1;
+            let archived = archive(&email.entry, sent);
+            let _ = Storage::delete_outbox_entry(&email.entry.id);
+            let _ = tx.send(Action::QueueJobSent(email.origin)).await;
+            if let Err(e) = archived {
+              let _ = tx.send(Action::ArchiveFailed(e.to_string())).await;
+            }
+          }
+          Err(e) if was_discarded => {
+            stats.failed += 1;
+            let _ = tx
+              .send(Action::QueueJobFailed(email.origin, format!("{:#}", e)))
+              .await;
+          }
+          Err(e) => {
+            let mut email = email;
+            let error = format!("{:#}", e);
+            if record_failure(&mut email.entry, &e) {
+              let delay = backoff(email.entry.attempts);
+              let _ = tx.send(Action::QueueJobRetrying(email.origin, error)).await;
+              backlog.waiting.push((Instant::now() + delay, email));
+            } else {
+              stats.failed += 1;
+              let _ = tx.send(Action::QueueJobFailed(email.origin, error)).await;
+            }
+          }
+        }
+      },
+      _ = tokio::time::sleep_until(wake_at.unwrap_or(now)), if wake_at.is_some() => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn email(entry: OutboxEntry) -> QueuedEmail {
+    QueuedEmail {
+      origin: SendOrigin::Outbox,
+      config: AppConfig::default(),
+      entry,
+    }
+  }
+
+  fn ids(backlog: &Backlog) -> Vec<String> {
+    backlog.pending.iter().map(|e| e.entry.id.clone()).collect()
+  }
+
+  #[test]
+  fn backoff_doubles_from_thirty_seconds_up_to_half_an_hour() {
+    let secs: Vec<u64> = (1..=8).map(|n| backoff(n).as_secs()).collect();
+    assert_eq!(secs, [30, 60, 120, 240, 480, 960, 1800, 1800]);
+    assert_eq!(backoff(0), BASE_BACKOFF);
+    assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+  }
+
+  #[test]
+  fn enqueue_never_queues_an_entry_that_is_being_sent() {
+    let mut backlog = Backlog::default();
+    let entry = OutboxEntry::new(EmailDraft::default());
+    let sending = HashSet::from([entry.id.clone()]);
+
+    assert!(!backlog.enqueue(email(entry.clone()), &sending, Instant::now()));
+    assert_eq!(backlog.len(), 0);
+
+    assert!(backlog.enqueue(email(entry.clone()), &HashSet::new(), Instant::now()));
+    assert!(backlog.enqueue(email(entry.clone()), &HashSet::new(), Instant::now()));
+    assert_eq!(ids(&backlog), [entry.id]);
+  }
+
+  #[test]
+  fn enqueue_waits_for_the_next_attempt_and_discard_drops_it() {
+    let now = Instant::now();
+    let mut backlog = Backlog::default();
+    let mut entry = OutboxEntry::new(EmailDraft::default());
+    entry.next_attempt_at = Some(Utc::now() + chrono::Duration::minutes(5));
+
+    backlog.enqueue(email(entry.clone()), &HashSet::new(), now);
+    assert!(backlog.pending.is_empty());
+    let wake = backlog.next_wake().unwrap();
+    assert!(wake > now + Duration::from_secs(290));
+
+    backlog.promote_due(now);
+    assert!(backlog.pending.is_empty());
+    backlog.promote_due(wake);
+    assert_eq!(ids(&backlog), [entry.id.clone()]);
+
+    backlog.discard(entry.id.clone());
+    assert_eq!(backlog.len(), 0);
+    assert!(backlog.discarded.contains(&entry.id));
+  }
+}
@@ -0,0 +1,277 @@
+use crate::config::SenderProfile;
+use crate::models::{EmailDraft, UserIdentity};
+use crate::storage::Storage;
+
+pub const DEFAULT_SIGNATURE: &str = "classic";
+/// Draft setting that leaves the signature out entirely.
+pub const NO_SIGNATURE: &str = "none";
+
+/// Built-in layouts as (name, HTML template, plain text template).
+const BUILT_IN: [(&str, &str, &str); 3] = [
+  (
+    "classic",
+    include_str!("../signatures/classic.html"),
+    include_str!("../signatures/classic.txt"),
+  ),
+  (
+    "compact",
+    include_str!("../signatures/compact.html"),
+    include_str!("../signatures/compact.txt"),
+  ),
+  (
+    "minimal",
+    include_str!("../signatures/minimal.html"),
+    include_str!("../signatures/minimal.txt"),
+  ),
+];
+
+/// A signature rendered for one identity, in both message parts.
+pub struct Signature {
+  pub html: String,
+  pub plain: String,
+}
+
+/// The signature a draft uses, with an empty name meaning the default.
+pub fn resolve(name: &str) -> &str {
+  if name.is_empty() {
+    DEFAULT_SIGNATURE
+  } else {
+    name
+  }
+}
+
+/// The signature `draft` uses when sent from `profile`: its own choice, else the profile's.
+pub fn chosen<'a>(draft: &'a EmailDraft, profile: &'a SenderProfile) -> &'a str {
+  if draft.signature.is_empty() {
+    resolve(&profile.signature)
+  } else {
+    &draft.signature
+  }
+}
+
+/// Built-in layouts, then the templates in the signatures directory, then `none`.
+pub fn available() -> Vec<String> {
+  let mut names: Vec<String> = BUILT_IN
+    .iter()
+    .map(|(name, _, _)| name.to_string())
+    .collect();
+  for name in Storage::list_signatures().unwrap_or_default() {
+    if !names.contains(&name) && name != NO_SIGNATURE {
+      names.push(name);
+    }
+  }
+  names.push(NO_SIGNATURE.to_string());
+  names
+}
+
+/// The signature after `current` in `available()`, wrapping around.
+pub fn next(current: &str) -> String {
+  let names = available();
+  match names.iter().position(|n| n == resolve(current)) {
+    Some(i) => names[(i + 1) % names.len()].clone(),
+    None => DEFAULT_SIGNATURE.to_string(),
+  }
+}
+
+/// Renders the named signature for `identity`, or `None` when the draft has it turned off.
+/// User templates (`<name>.html` and `<name>.txt`) override built-ins of the same name; a
+/// user template without a `.txt` variant uses the default plain text layout.
+pub fn render(name: &str, identity: &UserIdentity) -> Option<Signature> {
+  let name = resolve(name);
+  if name == NO_SIGNATURE {
+    return None;
+  }
+
+  let built_in = |name: &str| BUILT_IN.iter().find(|(n, _, _)| *n == name).copied();
+  let fallback = built_in(name).or_else(|| built_in(DEFAULT_SIGNATURE))?;
+  let user_html = Storage::load_signature(name, "html").ok().flatten();
+  let user_plain = Storage::load_signature(name, "txt").ok().flatten();
+
+  let (html_template, plain_template) = match user_html {
+    Some(html) => (html, user_plain.unwrap_or_else(|| fallback.2.to_string())),
+    None => (fallback.1.to_string(), fallback.2.to_string()),
+  };
+
+  Some(Signature {
+    html: render_template(&html_template, &values(identity, true), true),
+    plain: render_template(&plain_template, &values(identity, false), false)
+      .trim_end()
+      .to_string(),
+  })
+}
+
+/// Template values for `identity`. `email_links` is already-escaped markup and is the only
+/// value inserted as is.
+fn values(identity: &UserIdentity, html: bool) -> Vec<(&'static str, String)> {
+  let affiliation_separator = if html { " • " } else { " - " };
+  let affiliation = [&identity.department, &identity.institution]
+    .into_iter()
+    .filter(|v| !v.trim().is_empty())
+    .map(|v| v.trim())
+    .collect::<Vec<_>>()
+    .join(affiliation_separator);
+  let emails = identity.emails.join(if html { " • " } else { " | " });
+  let email_links = if html {
+    identity
+      .emails
+      .iter()
+      .map(|email| {
+        let email = escape_html(email);
+        format!(
+          r#"<a href="mailto:{}" style="color: {}; text-decoration: none;">{}</a>"#,
+          email,
+          escape_html(&identity.footer_color),
+          email
+        )
+      })
+      .collect::<Vec<_>>()
+      .join(" &bull; ")
+  } else {
+    emails.clone()
+  };
+
+  vec![
+    ("name", identity.name.clone()),
+    ("role", identity.role.clone()),
+    ("department", identity.department.clone()),
+    ("institution", identity.institution.clone()),
+    ("affiliation", affiliation),
+    ("phone", identity.phone.clone()),
+    ("emails", emails),
+    ("email_links", email_links),
+    ("color", identity.footer_color.clone()),
+  ]
+}
+
+/// Fills `{{key}}` with the value of `key` (HTML-escaped when `escape` is set) and keeps
+/// `{{#key}}...{{/key}}` sections only when `key` has a non-blank value. Sections nest.
+/// Unknown keys are left in place so typos are easy to spot.
+fn render_template(template: &str, values: &[(&str, String)], escape: bool) -> String {
+  let lookup = |key: &str| values.iter().find(|(k, _)| *k == key).map(|(_, v)| v);
+  let mut output = String::with_capacity(template.len());
+  let mut rest = template;
+
+  while let Some(start) = rest.find("{{") {
+    output.push_str(&rest[..start]);
+    let after_open = &rest[start + 2..];
+    let Some(end) = after_open.find("}}") else {
+      output.push_str(&rest[start..]);
+      return output;
+    };
+    let tag = after_open[..end].trim();
+    let after_tag = &after_open[end + 2..];
+
+    if let Some(key) = section_key(tag, '#') {
+      let (inner, remainder) = split_section(after_tag, key);
+      if lookup(key).is_some_and(|v| !v.trim().is_empty()) {
+        output.push_str(&render_template(inner, values, escape));
+      }
+      rest = remainder;
+      continue;
+    }
+
+    match lookup(tag) {
+      Some(value) if escape && tag != "email_links" => output.push_str(&escape_html(value)),
+      Some(value) => output.push_str(value),
+      None if tag.starts_with('/') => {}
+      None => output.push_str(&rest[start..start + 2 + end + 2]),
+    }
+    rest = after_tag;
+  }
+
+  output.push_str(rest);
+  output
+}
+
+/// Splits `text` at the `{{/key}}` closing the section just opened, skipping nested
+/// sections with the same key. An unclosed section runs to the end.
+fn split_section<'a>(text: &'a str, key: &str) -> (&'a str, &'a str) {
+  let mut depth = 0;
+  let mut offset = 0;
+
+  while let Some(i) = text[offset..].find("{{") {
+    let at = offset + i;
+    let Some(end) = text[at + 2..].find("}}").map(|j| at + 2 + j) else {
+      break;
+    };
+    let tag = text[at + 2..end].trim();
+    if section_key(tag, '/') == Some(key) {
+      if depth == 0 {
+        return (&text[..at], &text[end + 2..]);
+      }
+      depth -= 1;
+    } else if section_key(tag, '#') == Some(key) {
+      depth += 1;
+    }
+    offset = end + 2;
+  }
+  (text, "")
+}
+
+/// The key of a trimmed `#key` or `/key` tag, so `{{ #phone }}` and `{{ /phone }}` pair up.
+fn section_key(tag: &str, marker: char) -> Option<&str> {
+  tag.strip_prefix(marker).map(str::trim)
+}
+
+pub fn escape_html(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      _ => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sample() -> Vec<(&'static str, String)> {
+    vec![
+      ("name", "Ada <Lovelace> & \"Co\"".to_string()),
+      ("role", "Analyst".to_string()),
+      ("phone", "   ".to_string()),
+      ("color", "red\" onload=\"x".to_string()),
+      (
+        "email_links",
+        "<a href=\"mailto:a@b.c\">a@b.c</a>".to_string(),
+      ),
+    ]
+  }
+
+  #[test]
+  fn sections_nest_and_close_with_spaced_tags() {
+    let template =
+      "{{ #role }}[{{role}}{{#name}}/{{#role}}{{ role }}{{/role}}{{/name}}]{{ /role }}!";
+    assert_eq!(
+      render_template(template, &sample(), false),
+      "[Analyst/Analyst]!"
+    );
+  }
+
+  #[test]
+  fn blank_and_missing_values_drop_their_sections() {
+    let template = "a{{#phone}}Tel {{phone}}{{/phone}}b{{ #fax }}Fax{{ /fax }}c{{unknown}}";
+    assert_eq!(render_template(template, &sample(), true), "abc{{unknown}}");
+  }
+
+  #[test]
+  fn html_escapes_name_and_color_but_not_links() {
+    let template = "<b style=\"color: {{color}}\">{{name}}</b>{{email_links}}";
+    assert_eq!(
+      render_template(template, &sample(), true),
+      "<b style=\"color: red&quot; onload=&quot;x\">Ada &lt;Lovelace&gt; &amp; &quot;Co&quot;</b>\
+       <a href=\"mailto:a@b.c\">a@b.c</a>"
+    );
+    assert_eq!(
+      render_template("{{name}}", &sample(), false),
+      "Ada <Lovelace> & \"Co\""
+    );
+  }
+}
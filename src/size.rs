@@ -0,0 +1,170 @@
+use crate::attachments::format_size;
+use crate::compiler::{self, CompiledEmail};
+use crate::config::AppConfig;
+use crate::contacts;
+use crate::models::{ConsentRegistry, ContactBook, EmailDraft};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Top-level headers (From, Date, Message-ID, MIME-Version, ...) and the outer boundaries.
+const HEADER_OVERHEAD: u64 = 1024;
+/// Headers and boundary line of one MIME part or form field.
+const PART_OVERHEAD: u64 = 200;
+/// Plain text fields `client::schedule_email` sends next to the bodies and files.
+const FORM_FIELDS: u64 = 12;
+/// Base64 and quoted-printable bodies are wrapped at 76 characters plus CRLF.
+const LINE_LENGTH: u64 = 76;
+
+/// How big a draft gets once encoded, both as a message and as the scheduling upload.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SizeEstimate {
+  /// What the SMTP server receives, with attachments and inline images base64-encoded.
+  pub message: u64,
+  /// The multipart request sent to the worker when scheduling. Files are sent as raw bytes.
+  pub upload: u64,
+}
+
+impl SizeEstimate {
+  pub fn of(draft: &EmailDraft, compiled: &CompiledEmail) -> Self {
+    let headers = compiled.subject.len() as u64
+      + draft
+        .to
+        .iter()
+        .chain(&draft.cc)
+        .map(|a| a.len() as u64 + 2)
+        .sum::<u64>();
+    let files: Vec<u64> = compiled
+      .attachments
+      .iter()
+      .map(PathBuf::as_path)
+      .chain(compiled.inline_images.iter().map(|img| img.path.as_path()))
+      .map(file_size)
+      .collect();
+
+    let message = HEADER_OVERHEAD
+      + headers
+      + 2 * PART_OVERHEAD
+      + encoded_text_len(&compiled.plain_body)
+      + encoded_text_len(&compiled.html_body)
+      + files
+        .iter()
+        .map(|&size| base64_len(size) + PART_OVERHEAD)
+        .sum::<u64>();
+
+    let bcc = draft.bcc.iter().map(|a| a.len() as u64 + 2).sum::<u64>();
+    let upload = FORM_FIELDS * PART_OVERHEAD
+      + headers
+      + bcc
+      + compiled.plain_body.len() as u64
+      + compiled.html_body.len() as u64
+      + files.iter().map(|&size| size + PART_OVERHEAD).sum::<u64>();
+
+    Self { message, upload }
+  }
+
+  /// Compiles `draft` with the current identity and estimates its size, with `@group`
+  /// recipients expanded the way sending does. Unknown groups are left for sending to report.
+  pub fn for_draft(
+    draft: &EmailDraft,
+    config: &AppConfig,
+    consent: &ConsentRegistry,
+    book: &ContactBook,
+  ) -> Self {
+    let expanded = draft
+      .has_group_references()
+      .then(|| contacts::expand_groups(draft, book).ok())
+      .flatten()
+      .map(|(expanded, _)| expanded);
+    let draft = expanded.as_ref().unwrap_or(draft);
+    let compiled = compiler::compile(draft, config, consent, &compiler::new_tracking_id());
+    Self::of(draft, &compiled)
+  }
+}
+
+fn file_size(path: &Path) -> u64 {
+  fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn base64_len(bytes: u64) -> u64 {
+  let encoded = bytes.div_ceil(3) * 4;
+  encoded + encoded.div_ceil(LINE_LENGTH) * 2
+}
+
+/// Short-lined ASCII goes out as 7bit; anything else is quoted-printable or base64,
+/// whichever is shorter, which is at most the base64 size.
+fn encoded_text_len(text: &str) -> u64 {
+  let fits_7bit = text.is_ascii() && text.lines().all(|line| line.len() <= 998);
+  if fits_7bit {
+    (text.len() + text.lines().count()) as u64
+  } else {
+    base64_len(text.len() as u64)
+  }
+}
+
+/// Limits are configured in MB, with 0 meaning no limit.
+pub fn limit_bytes(limit_mb: u32) -> Option<u64> {
+  (limit_mb > 0).then(|| u64::from(limit_mb) * 1024 * 1024)
+}
+
+/// Error for a message the mail provider would reject, if it is over the configured limit.
+pub fn message_limit_error(size: u64, config: &AppConfig) -> Option<String> {
+  let limit = limit_bytes(config.message_size_limit_mb)?;
+  (size > limit).then(|| {
+    format!(
+      "Message is {} encoded, over the {} limit of your mail provider",
+      format_size(size),
+      format_size(limit)
+    )
+  })
+}
+
+/// Warning for a scheduling upload the worker may refuse, if it is over the configured limit.
+pub fn upload_limit_warning(size: u64, config: &AppConfig) -> Option<String> {
+  let limit = limit_bytes(config.worker_upload_limit_mb)?;
+  (size > limit).then(|| {
+    format!(
+      "Upload to the worker is {}, over its {} request limit",
+      format_size(size),
+      format_size(limit)
+    )
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::models::RecipientGroup;
+
+  #[test]
+  fn for_draft_counts_the_members_of_recipient_groups() {
+    let members: Vec<String> = (0..50)
+      .map(|i| format!("member{}@example.com", i))
+      .collect();
+    let book = ContactBook {
+      groups: vec![RecipientGroup {
+        name: "team".to_string(),
+        members: members.clone(),
+      }],
+      ..ContactBook::default()
+    };
+    let config = AppConfig::default();
+    let consent = ConsentRegistry::default();
+    let grouped = EmailDraft {
+      to: vec!["@team".to_string()],
+      ..EmailDraft::default()
+    };
+    let listed = EmailDraft {
+      to: members,
+      ..EmailDraft::default()
+    };
+
+    let estimate = SizeEstimate::for_draft(&grouped, &config, &consent, &book);
+    let expected = SizeEstimate::for_draft(&listed, &config, &consent, &book);
+    assert_eq!(estimate.message, expected.message);
+    assert_eq!(estimate.upload, expected.upload);
+
+    // An unknown group is estimated as written and left for sending to reject
+    let unknown = SizeEstimate::for_draft(&grouped, &config, &consent, &ContactBook::default());
+    assert!(unknown.message < estimate.message);
+  }
+}
@@ -1,9 +1,16 @@
-use crate::config::AppConfig;
-use crate::models::EmailDraft;
+use crate::config::{AppConfig, DEFAULT_PROFILE_ID, SenderProfile};
+use crate::models::{
+  ConsentRegistry, ContactBook, EmailDraft, OutboxEntry, SentRecord, TrackedMessage,
+};
+use crate::tracking;
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use uuid::Uuid;
 
 pub struct Storage;
 
@@ -29,11 +36,66 @@ impl Storage {
     let path = config_dir.join("config.json");
 
     if !path.exists() {
-      return Ok(AppConfig::default());
+      return Self::ensure_tracking_secret(AppConfig::default());
     }
 
     let content = fs::read_to_string(path)?;
-    Ok(serde_json::from_str(&content).unwrap_or_default())
+    let mut value: serde_json::Value =
+      serde_json::from_str(&content).context("config.json is not valid JSON")?;
+    Self::migrate_legacy_profile(&mut value);
+    let mut config: AppConfig =
+      serde_json::from_value(value).context("config.json has invalid settings")?;
+    if config.profiles.is_empty() {
+      config.profiles.push(SenderProfile::default());
+    }
+    Self::ensure_tracking_secret(config)
+  }
+
+  // The secret has to survive restarts, or every earlier pixel hit would look forged.
+  // This is the only place one is generated.
+  fn ensure_tracking_secret(mut config: AppConfig) -> Result<AppConfig> {
+    if config.tracking_secret.is_empty() {
+      config.tracking_secret = tracking::new_secret();
+      Self::save_config(&config)?;
+    }
+    Ok(config)
+  }
+
+  // Configs from before sender profiles kept one identity and SMTP login at the top level,
+  // and the SMTP server stayed there for a while after profiles were added
+  fn migrate_legacy_profile(config: &mut serde_json::Value) {
+    let Some(object) = config.as_object_mut() else {
+      return;
+    };
+    let server: Vec<_> = ["smtp_host", "smtp_port", "smtp_security"]
+      .into_iter()
+      .filter_map(|key| object.remove(key).map(|value| (key, value)))
+      .collect();
+
+    if !object.contains_key("profiles") {
+      let mut profile = serde_json::Map::new();
+      profile.insert("id".to_string(), DEFAULT_PROFILE_ID.into());
+      profile.insert("name".to_string(), "Default".into());
+      for key in ["identity", "smtp_username", "smtp_app_password"] {
+        if let Some(value) = object.remove(key) {
+          profile.insert(key.to_string(), value);
+        }
+      }
+      object.insert(
+        "profiles".to_string(),
+        serde_json::Value::Array(vec![profile.into()]),
+      );
+    }
+
+    if let Some(profiles) = object.get_mut("profiles").and_then(|p| p.as_array_mut()) {
+      for profile in profiles.iter_mut().filter_map(|p| p.as_object_mut()) {
+        for (key, value) in &server {
+          profile
+            .entry(key.to_string())
+            .or_insert_with(|| value.clone());
+        }
+      }
+    }
   }
 
   pub fn save_config(config: &AppConfig) -> Result<()> {
@@ -47,27 +109,399 @@ impl Storage {
     Ok(())
   }
 
-  pub fn load_draft() -> Result<EmailDraft> {
+  // User stylesheets live next to config.json, one `<name>.css` per theme
+  fn themes_dir() -> Result<PathBuf> {
+    let dirs = Self::get_proj_dirs()?;
+    let themes_dir = dirs.config_dir().join("themes");
+    Self::ensure_dir(&themes_dir)?;
+    Ok(themes_dir)
+  }
+
+  pub fn list_themes() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(Self::themes_dir()?)? {
+      let path = entry?.path();
+      if path.extension().is_some_and(|ext| ext == "css")
+        && let Some(stem) = path.file_stem()
+      {
+        names.push(stem.to_string_lossy().to_string());
+      }
+    }
+    names.sort();
+    Ok(names)
+  }
+
+  pub fn load_theme(name: &str) -> Result<Option<String>> {
+    let path = Self::themes_dir()?.join(format!("{}.css", name));
+    if !path.exists() {
+      return Ok(None);
+    }
+    Ok(Some(fs::read_to_string(path)?))
+  }
+
+  // Signature templates: `<name>.html` with an optional `<name>.txt` plain text variant
+  fn signatures_dir() -> Result<PathBuf> {
+    let dirs = Self::get_proj_dirs()?;
+    let signatures_dir = dirs.config_dir().join("signatures");
+    Self::ensure_dir(&signatures_dir)?;
+    Ok(signatures_dir)
+  }
+
+  pub fn list_signatures() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(Self::signatures_dir()?)? {
+      let path = entry?.path();
+      if path.extension().is_some_and(|ext| ext == "html")
+        && let Some(stem) = path.file_stem()
+      {
+        names.push(stem.to_string_lossy().to_string());
+      }
+    }
+    names.sort();
+    Ok(names)
+  }
+
+  pub fn load_signature(name: &str, extension: &str) -> Result<Option<String>> {
+    let path = Self::signatures_dir()?.join(format!("{}.{}", name, extension));
+    if !path.exists() {
+      return Ok(None);
+    }
+    Ok(Some(fs::read_to_string(path)?))
+  }
+
+  pub fn load_contacts() -> Result<ContactBook> {
+    let dirs = Self::get_proj_dirs()?;
+    let path = dirs.data_dir().join("contacts.json");
+
+    if !path.exists() {
+      return Ok(ContactBook::default());
+    }
+
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).context("The address book (contacts.json) is corrupt")
+  }
+
+  pub fn save_contacts(book: &ContactBook) -> Result<()> {
+    let dirs = Self::get_proj_dirs()?;
+    let data_dir = dirs.data_dir();
+    Self::ensure_dir(data_dir)?;
+
+    let content = serde_json::to_string_pretty(book)?;
+    fs::write(data_dir.join("contacts.json"), content)?;
+    Ok(())
+  }
+
+  pub fn load_consent() -> Result<ConsentRegistry> {
+    let dirs = Self::get_proj_dirs()?;
+    let path = dirs.data_dir().join("consent.json");
+
+    if !path.exists() {
+      return Ok(ConsentRegistry::default());
+    }
+
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+      .context("The tracking consent registry (consent.json) is corrupt")
+  }
+
+  pub fn save_consent(registry: &ConsentRegistry) -> Result<()> {
     let dirs = Self::get_proj_dirs()?;
     let data_dir = dirs.data_dir();
-    let path = data_dir.join("draft.json");
+    Self::ensure_dir(data_dir)?;
+
+    let content = serde_json::to_string_pretty(registry)?;
+    fs::write(data_dir.join("consent.json"), content)?;
+    Ok(())
+  }
+
+  /// Returns the tracking token mappings, keyed by token.
+  pub fn load_tracked_messages() -> Result<HashMap<String, TrackedMessage>> {
+    let messages = Self::read_tracked_messages()?;
+    Ok(messages.into_iter().map(|m| (m.token.clone(), m)).collect())
+  }
+
+  // Fails on a corrupt file so record_tracked_message never writes over it
+  fn read_tracked_messages() -> Result<Vec<TrackedMessage>> {
+    let dirs = Self::get_proj_dirs()?;
+    let path = dirs.data_dir().join("tracking.json");
 
     if !path.exists() {
-      return Ok(EmailDraft::default());
+      return Ok(Vec::new());
     }
 
     let content = fs::read_to_string(path)?;
-    Ok(serde_json::from_str(&content).unwrap_or_default())
+    serde_json::from_str(&content).context("The tracking token mapping is corrupt")
   }
 
-  pub fn save_draft(draft: &EmailDraft) -> Result<()> {
+  pub fn record_tracked_message(message: TrackedMessage) -> Result<()> {
     let dirs = Self::get_proj_dirs()?;
     let data_dir = dirs.data_dir();
     Self::ensure_dir(data_dir)?;
 
-    let path = data_dir.join("draft.json");
+    let mut messages = Self::read_tracked_messages()?;
+    messages.push(message);
+    let content = serde_json::to_string_pretty(&messages)?;
+    fs::write(data_dir.join("tracking.json"), content)?;
+    Ok(())
+  }
+
+  fn drafts_dir() -> Result<PathBuf> {
+    let dirs = Self::get_proj_dirs()?;
+    let drafts_dir = dirs.data_dir().join("drafts");
+    Self::ensure_dir(&drafts_dir)?;
+    Ok(drafts_dir)
+  }
+
+  // Older versions kept a single draft.json next to the drafts directory
+  fn migrate_legacy_draft() -> Result<()> {
+    let dirs = Self::get_proj_dirs()?;
+    let legacy_path = dirs.data_dir().join("draft.json");
+    if !legacy_path.exists() {
+      return Ok(());
+    }
+
+    let content = fs::read_to_string(&legacy_path)?;
+    let Ok(draft) = serde_json::from_str::<EmailDraft>(&content) else {
+      // Keep it for the user to recover by hand, out of the way of the next migration
+      fs::rename(&legacy_path, legacy_path.with_extension("json.bak"))?;
+      return Ok(());
+    };
+    Self::save_draft(&draft)?;
+    fs::remove_file(legacy_path)?;
+    Ok(())
+  }
+
+  // Reads every `*.json` file in `dir`, most recently modified first.
+  // Files that fail to parse are skipped rather than failing the whole listing.
+  fn read_json_dir<T: DeserializeOwned>(dir: &Path) -> Result<Vec<T>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+      let path = entry?.path();
+      if path.extension().is_none_or(|ext| ext != "json") {
+        continue;
+      }
+      let Ok(content) = fs::read_to_string(&path) else {
+        continue;
+      };
+      let Ok(item) = serde_json::from_str::<T>(&content) else {
+        continue;
+      };
+      let modified = fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+      entries.push((modified, item));
+    }
+
+    entries.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    Ok(entries.into_iter().map(|(_, item)| item).collect())
+  }
+
+  // Draft and template files are named after their id, which has to be a UUID so a
+  // hand-edited file cannot point the path outside the directory
+  fn id_path(dir: &Path, id: &str) -> Result<PathBuf> {
+    let id = Uuid::parse_str(id).with_context(|| format!("Invalid id '{}'", id))?;
+    Ok(dir.join(format!("{}.json", id.hyphenated())))
+  }
+
+  fn with_valid_ids(items: Vec<EmailDraft>) -> Vec<EmailDraft> {
+    items
+      .into_iter()
+      .filter(|d| Uuid::parse_str(&d.id).is_ok())
+      .collect()
+  }
+
+  /// Returns every saved draft, most recently modified first.
+  pub fn list_drafts() -> Result<Vec<EmailDraft>> {
+    Self::migrate_legacy_draft()?;
+    let drafts = Self::read_json_dir(&Self::drafts_dir()?)?;
+    Ok(Self::with_valid_ids(drafts))
+  }
+
+  pub fn save_draft(draft: &EmailDraft) -> Result<()> {
+    let path = Self::id_path(&Self::drafts_dir()?, &draft.id)?;
     let content = serde_json::to_string_pretty(draft)?;
     fs::write(path, content)?;
     Ok(())
   }
+
+  pub fn delete_draft(id: &str) -> Result<()> {
+    let path = Self::id_path(&Self::drafts_dir()?, id)?;
+    if path.exists() {
+      fs::remove_file(path)?;
+    }
+    Ok(())
+  }
+
+  fn templates_dir() -> Result<PathBuf> {
+    let dirs = Self::get_proj_dirs()?;
+    let templates_dir = dirs.data_dir().join("templates");
+    Self::ensure_dir(&templates_dir)?;
+    Ok(templates_dir)
+  }
+
+  pub fn list_templates() -> Result<Vec<EmailDraft>> {
+    let templates = Self::read_json_dir(&Self::templates_dir()?)?;
+    Ok(Self::with_valid_ids(templates))
+  }
+
+  pub fn save_template(template: &EmailDraft) -> Result<()> {
+    let path = Self::id_path(&Self::templates_dir()?, &template.id)?;
+    let content = serde_json::to_string_pretty(template)?;
+    fs::write(path, content)?;
+    Ok(())
+  }
+
+  pub fn delete_template(id: &str) -> Result<()> {
+    let path = Self::id_path(&Self::templates_dir()?, id)?;
+    if path.exists() {
+      fs::remove_file(path)?;
+    }
+    Ok(())
+  }
+
+  fn outbox_dir() -> Result<PathBuf> {
+    let dirs = Self::get_proj_dirs()?;
+    let outbox_dir = dirs.data_dir().join("outbox");
+    Self::ensure_dir(&outbox_dir)?;
+    Ok(outbox_dir)
+  }
+
+  /// Returns every message still waiting in the outbox, oldest first.
+  pub fn list_outbox() -> Result<Vec<OutboxEntry>> {
+    let mut entries: Vec<OutboxEntry> = Self::read_json_dir(&Self::outbox_dir()?)?;
+    entries.sort_by_key(|e| e.created_at);
+    Ok(entries)
+  }
+
+  pub fn save_outbox_entry(entry: &OutboxEntry) -> Result<()> {
+    let path = Self::outbox_dir()?.join(format!("{}.json", entry.id));
+    let content = serde_json::to_string_pretty(entry)?;
+    fs::write(path, content)?;
+    Ok(())
+  }
+
+  pub fn delete_outbox_entry(id: &str) -> Result<()> {
+    let path = Self::outbox_dir()?.join(format!("{}.json", id));
+    if path.exists() {
+      fs::remove_file(path)?;
+    }
+    Ok(())
+  }
+
+  fn sent_dir() -> Result<PathBuf> {
+    let dirs = Self::get_proj_dirs()?;
+    let sent_dir = dirs.data_dir().join("sent");
+    Self::ensure_dir(&sent_dir)?;
+    Ok(sent_dir)
+  }
+
+  /// Returns the Sent archive index, newest first.
+  pub fn list_sent() -> Result<Vec<SentRecord>> {
+    let path = Self::sent_dir()?.join("index.json");
+    if !path.exists() {
+      return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    // A corrupt index must not read as empty, or the next archive_sent would overwrite it
+    let mut records: Vec<SentRecord> =
+      serde_json::from_str(&content).context("The Sent archive index is corrupt")?;
+    records.sort_by_key(|r| std::cmp::Reverse(r.sent_at));
+    Ok(records)
+  }
+
+  /// Stores the raw message as `<id>.eml` and the draft it came from as `<id>.json`,
+  /// then adds the record to the index.
+  pub fn archive_sent(record: &SentRecord, eml: &[u8], draft: &EmailDraft) -> Result<()> {
+    let sent_dir = Self::sent_dir()?;
+    fs::write(sent_dir.join(format!("{}.eml", record.id)), eml)?;
+    fs::write(
+      sent_dir.join(format!("{}.json", record.id)),
+      serde_json::to_string_pretty(draft)?,
+    )?;
+
+    let mut records = Self::list_sent()?;
+    records.push(record.clone());
+    records.sort_by_key(|r| r.sent_at);
+    fs::write(
+      sent_dir.join("index.json"),
+      serde_json::to_string_pretty(&records)?,
+    )?;
+    Ok(())
+  }
+
+  pub fn sent_eml_path(id: &str) -> Result<PathBuf> {
+    Ok(Self::sent_dir()?.join(format!("{}.eml", id)))
+  }
+
+  /// The draft a sent message was built from.
+  pub fn load_sent_draft(id: &str) -> Result<EmailDraft> {
+    let path = Self::sent_dir()?.join(format!("{}.json", id));
+    let content =
+      fs::read_to_string(&path).with_context(|| format!("Missing {}", path.display()))?;
+    Ok(serde_json::from_str(&content)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::enums::SmtpSecurity;
+
+  #[test]
+  fn legacy_identity_and_login_move_into_default_profile() {
+    let mut value = serde_json::json!({
+      "identity": {
+        "name": "Ada",
+        "role": "Analyst",
+        "department": "",
+        "institution": "",
+        "phone": "",
+        "emails": ["ada@example.com"]
+      },
+      "smtp_username": "ada@example.com",
+      "smtp_app_password": "secret",
+      "smtp_host": "mail.example.com",
+      "smtp_port": 587,
+      "smtp_security": "StartTls",
+      "worker_url": "https://worker.example.com",
+      "api_secret": "key"
+    });
+    Storage::migrate_legacy_profile(&mut value);
+    let config: AppConfig = serde_json::from_value(value).unwrap();
+
+    assert_eq!(config.worker_url, "https://worker.example.com");
+    assert_eq!(config.profiles.len(), 1);
+    let profile = &config.profiles[0];
+    assert_eq!(profile.id, DEFAULT_PROFILE_ID);
+    assert_eq!(profile.identity.name, "Ada");
+    assert_eq!(profile.identity.role, "Analyst");
+    assert_eq!(profile.smtp_username, "ada@example.com");
+    assert_eq!(profile.smtp_app_password, "secret");
+    assert_eq!(profile.smtp_host, "mail.example.com");
+    assert_eq!(profile.smtp_port, 587);
+    assert_eq!(profile.smtp_security, SmtpSecurity::StartTls);
+  }
+
+  #[test]
+  fn top_level_server_fills_existing_profiles_without_overriding() {
+    let mut value = serde_json::json!({
+      "smtp_host": "mail.example.com",
+      "smtp_port": 587,
+      "worker_url": "",
+      "api_secret": "",
+      "profiles": [
+        { "id": "work", "name": "Work", "smtp_username": "a@work.org" },
+        { "id": "home", "name": "Home", "smtp_host": "smtp.home.net" }
+      ]
+    });
+    Storage::migrate_legacy_profile(&mut value);
+    let config: AppConfig = serde_json::from_value(value).unwrap();
+
+    assert_eq!(config.profiles.len(), 2);
+    assert_eq!(config.profiles[0].smtp_host, "mail.example.com");
+    assert_eq!(config.profiles[0].smtp_port, 587);
+    assert_eq!(config.profiles[1].smtp_host, "smtp.home.net");
+    assert_eq!(config.profiles[1].smtp_port, 587);
+  }
 }
@@ -0,0 +1,286 @@
+use crate::storage::Storage;
+use lol_html::html_content::Element;
+use lol_html::{ElementContentHandlers, RewriteStrSettings, Selector, rewrite_str};
+use std::borrow::Cow;
+
+pub const DEFAULT_THEME: &str = "default";
+
+const BUILT_IN: [(&str, &str); 4] = [
+  ("default", include_str!("../themes/default.css")),
+  ("modern", include_str!("../themes/modern.css")),
+  ("serif", include_str!("../themes/serif.css")),
+  ("plain", include_str!("../themes/plain.css")),
+];
+
+/// Attribute the original inline style is parked in while theme rules are applied.
+const PARKED_STYLE: &str = "data-shiryoku-style";
+
+/// The theme a draft uses, with an empty name meaning the default.
+pub fn resolve(name: &str) -> &str {
+  if name.is_empty() { DEFAULT_THEME } else { name }
+}
+
+/// Built-in themes followed by the stylesheets in the themes directory.
+pub fn available() -> Vec<String> {
+  let mut names: Vec<String> = BUILT_IN.iter().map(|(name, _)| name.to_string()).collect();
+  for name in Storage::list_themes().unwrap_or_default() {
+    if !names.contains(&name) {
+      names.push(name);
+    }
+  }
+  names
+}
+
+/// The theme after `current` in `available()`, wrapping around.
+pub fn next(current: &str) -> String {
+  let names = available();
+  let position = names.iter().position(|n| n == resolve(current));
+  match position {
+    Some(i) => names[(i + 1) % names.len()].clone(),
+    None => DEFAULT_THEME.to_string(),
+  }
+}
+
+/// The stylesheet for `name`, with `var(--accent)` replaced by `accent`.
+/// A file in the themes directory takes precedence over a built-in of the same name,
+/// and unknown themes fall back to the default.
+pub fn stylesheet(name: &str, accent: &str) -> String {
+  let name = resolve(name);
+  let built_in = |name: &str| {
+    BUILT_IN
+      .iter()
+      .find(|(n, _)| *n == name)
+      .map(|(_, css)| css.to_string())
+  };
+  let css = Storage::load_theme(name)
+    .ok()
+    .flatten()
+    .or_else(|| built_in(name))
+    .or_else(|| built_in(DEFAULT_THEME))
+    .unwrap_or_default();
+  css.replace("var(--accent)", accent)
+}
+
+struct CssRule {
+  selector: Selector,
+  declarations: String,
+  specificity: (usize, usize, usize),
+}
+
+/// Splits a stylesheet into rules that can be inlined and the CSS that has to stay in a
+/// `<style>` block: at-rules such as `@media`, and selectors with pseudo-classes like `:hover`.
+fn parse_stylesheet(css: &str) -> (Vec<CssRule>, String) {
+  let css = strip_comments(css);
+  let mut rules = Vec::new();
+  let mut kept = String::new();
+  let mut rest = css.as_str();
+
+  while let Some(open) = rest.find('{') {
+    let prelude = rest[..open].trim();
+    let Some(close) = matching_brace(&rest[open..]).map(|i| open + i) else {
+      break;
+    };
+    let body = &rest[open + 1..close];
+
+    if prelude.starts_with('@') {
+      kept.push_str(&format!("{} {{{}}}\n", prelude, body));
+    } else {
+      for selector in prelude.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        match selector.parse::<Selector>() {
+          Ok(parsed) if !selector.contains(':') => rules.push(CssRule {
+            selector: parsed,
+            declarations: body.trim().to_string(),
+            specificity: specificity(selector),
+          }),
+          _ => kept.push_str(&format!("{} {{{}}}\n", selector, body.trim())),
+        }
+      }
+    }
+    rest = &rest[close + 1..];
+  }
+
+  // Later rules win among equal specificity, so the sort has to be stable
+  rules.sort_by_key(|rule| rule.specificity);
+  (rules, kept)
+}
+
+fn strip_comments(css: &str) -> String {
+  let mut out = String::with_capacity(css.len());
+  let mut rest = css;
+  while let Some(start) = rest.find("/*") {
+    out.push_str(&rest[..start]);
+    match rest[start + 2..].find("*/") {
+      Some(end) => rest = &rest[start + 2 + end + 2..],
+      None => return out,
+    }
+  }
+  out.push_str(rest);
+  out
+}
+
+/// Offset of the `}` closing the block that starts at the beginning of `block`.
+fn matching_brace(block: &str) -> Option<usize> {
+  let mut depth = 0;
+  for (i, c) in block.char_indices() {
+    match c {
+      '{' => depth += 1,
+      '}' => {
+        depth -= 1;
+        if depth == 0 {
+          return Some(i);
+        }
+      }
+      _ => {}
+    }
+  }
+  None
+}
+
+/// (ids, classes and attributes, element names), close enough for the selectors themes use.
+fn specificity(selector: &str) -> (usize, usize, usize) {
+  let mut ids = 0;
+  let mut classes = 0;
+  let mut elements = 0;
+  for compound in selector
+    .split([' ', '>', '+', '~'])
+    .filter(|s| !s.is_empty())
+  {
+    ids += compound.matches('#').count();
+    classes += compound.matches('.').count() + compound.matches('[').count();
+    if compound.starts_with(|c: char| c.is_ascii_alphabetic()) {
+      elements += 1;
+    }
+  }
+  (ids, classes, elements)
+}
+
+fn append_declarations(style: &mut String, declarations: &str) {
+  let declarations = declarations.trim().trim_end_matches(';').trim();
+  if declarations.is_empty() {
+    return;
+  }
+  let existing = style.trim().trim_end_matches(';').trim().len();
+  style.truncate(existing);
+  if !style.is_empty() {
+    style.push_str("; ");
+  }
+  style.push_str(declarations);
+  style.push(';');
+}
+
+/// Moves the stylesheet onto each element's `style` attribute, since Gmail and Outlook
+/// drop most of `<head>`. Rules apply in order of specificity and an element's own inline
+/// style still wins. What cannot be inlined goes into a `<style>` block in `<head>`.
+pub fn inline_css(html: &str, css: &str) -> String {
+  let (rules, kept) = parse_stylesheet(css);
+
+  let html = if kept.is_empty() {
+    html.to_string()
+  } else {
+    html.replacen("</head>", &format!("<style>{}</style></head>", kept), 1)
+  };
+
+  let mut handlers: Vec<(Cow<Selector>, ElementContentHandlers)> = Vec::new();
+  let everything = || "*".parse::<Selector>().ok();
+
+  if let Some(all) = everything() {
+    handlers.push((
+      Cow::Owned(all),
+      ElementContentHandlers::default().element(|el: &mut Element| {
+        if let Some(style) = el.get_attribute("style") {
+          el.remove_attribute("style");
+          el.set_attribute(PARKED_STYLE, &style)?;
+        }
+        Ok(())
+      }),
+    ));
+  }
+  for rule in rules {
+    let declarations = rule.declarations;
+    handlers.push((
+      Cow::Owned(rule.selector),
+      ElementContentHandlers::default().element(move |el: &mut Element| {
+        let mut style = el.get_attribute("style").unwrap_or_default();
+        append_declarations(&mut style, &declarations);
+        el.set_attribute("style", &style)?;
+        Ok(())
+      }),
+    ));
+  }
+  if let Some(all) = everything() {
+    handlers.push((
+      Cow::Owned(all),
+      ElementContentHandlers::default().element(|el: &mut Element| {
+        if let Some(original) = el.get_attribute(PARKED_STYLE) {
+          el.remove_attribute(PARKED_STYLE);
+          let mut style = el.get_attribute("style").unwrap_or_default();
+          append_declarations(&mut style, &original);
+          el.set_attribute("style", &style)?;
+        }
+        Ok(())
+      }),
+    ));
+  }
+
+  let settings = RewriteStrSettings {
+    element_content_handlers: handlers,
+    ..RewriteStrSettings::new()
+  };
+  rewrite_str(&html, settings).unwrap_or(html)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn specificity_ranks_ids_over_classes_over_elements() {
+    assert_eq!(specificity("p"), (0, 0, 1));
+    assert_eq!(specificity("p.note a"), (0, 1, 2));
+    assert_eq!(specificity("a[href]"), (0, 1, 1));
+    assert!(specificity("#main") > specificity(".a.b.c"));
+    assert!(specificity(".a") > specificity("body p a"));
+  }
+
+  #[test]
+  fn rules_apply_by_specificity_and_inline_style_wins() {
+    let css = "#x { color: red } p { color: blue; margin: 0 } .note { color: green }";
+    let html = r#"<p id="x" class="note" style="color: black">hi</p><p>plain</p>"#;
+    let out = inline_css(html, css);
+    assert!(out.contains(
+      r#"<p id="x" class="note" style="color: blue; margin: 0; color: green; color: red; color: black;">"#
+    ));
+    assert!(out.contains(r#"<p style="color: blue; margin: 0;">plain</p>"#));
+    assert!(!out.contains(PARKED_STYLE));
+  }
+
+  #[test]
+  fn later_rules_win_among_equal_specificity() {
+    let out = inline_css("<p>hi</p>", "p { color: red } p { color: blue }");
+    assert!(out.contains(r#"<p style="color: red; color: blue;">"#));
+  }
+
+  #[test]
+  fn comments_are_stripped() {
+    let css = "/* p { color: red } */ p { /* inner */ color: blue }";
+    let (rules, kept) = parse_stylesheet(css);
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].declarations, "color: blue");
+    assert!(kept.is_empty());
+    assert_eq!(strip_comments("a /* unterminated"), "a ");
+  }
+
+  #[test]
+  fn nested_at_rules_and_pseudo_classes_stay_in_head() {
+    let css = "@media (max-width: 600px) { p { color: red } .a { margin: 0 } } a:hover { color: blue } p { color: green }";
+    let (rules, kept) = parse_stylesheet(css);
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].declarations, "color: green");
+    assert!(kept.contains("@media (max-width: 600px) { p { color: red } .a { margin: 0 } }"));
+    assert!(kept.contains("a:hover {color: blue}"));
+
+    let out = inline_css("<html><head></head><body><p>hi</p></body></html>", css);
+    assert!(out.contains("<style>@media"));
+    assert!(out.contains(r#"<p style="color: green;">"#));
+  }
+}
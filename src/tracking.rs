@@ -0,0 +1,34 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A fresh random key for signing tracking IDs.
+pub fn new_secret() -> String {
+  format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Hex HMAC-SHA256 of `tracking_id`, sent as the `sig` parameter of the pixel URL.
+pub fn sign(secret: &str, tracking_id: &str) -> String {
+  hex::encode(mac(secret, tracking_id).finalize().into_bytes())
+}
+
+/// Whether `signature` was made by `sign` with the same secret. An empty secret verifies
+/// nothing.
+pub fn verify(secret: &str, tracking_id: &str, signature: &str) -> bool {
+  if secret.is_empty() {
+    return false;
+  }
+  let Ok(bytes) = hex::decode(signature) else {
+    return false;
+  };
+  mac(secret, tracking_id).verify_slice(&bytes).is_ok()
+}
+
+fn mac(secret: &str, tracking_id: &str) -> HmacSha256 {
+  // HMAC accepts keys of any length
+  let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+  mac.update(tracking_id.as_bytes());
+  mac
+}
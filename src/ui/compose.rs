@@ -1,31 +1,52 @@
+use super::preview;
 use crate::app::App;
-use crate::enums::ComposeField;
+use crate::attachments::{self, AttachmentInfo, FileDiagnostic};
+use crate::compiler;
+use crate::contacts;
+use crate::enums::{ComposeField, InputMode, TrackingMode};
+use crate::models::parse_variables;
+use crate::size;
+use crate::{signature, theme};
 use ratatui::{
   Frame,
   layout::{Constraint, Direction, Layout, Rect},
   style::{Color, Modifier, Style},
-  widgets::{Block, Borders, Paragraph},
+  text::{Line, Span},
+  widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table, Wrap},
 };
 
 pub fn draw_compose(frame: &mut Frame, app: &App, area: Rect) {
+  let attachment_rows = app.compose.draft.attachments.len().clamp(1, 4) as u16;
   let layout = Layout::default()
     .direction(Direction::Vertical)
     .constraints([
-      Constraint::Length(3), // To
-      Constraint::Length(3), // Subject
-      Constraint::Length(3), // Attachments
-      Constraint::Min(5),    // Body
-      Constraint::Length(3), // Send Button
-      Constraint::Length(1), // Spacer
-      Constraint::Length(1), // Schedule Hint
+      Constraint::Length(3),                   // From / To
+      Constraint::Length(3),                   // Cc
+      Constraint::Length(3),                   // Bcc
+      Constraint::Length(3),                   // Subject
+      Constraint::Length(3),                   // Variables
+      Constraint::Length(attachment_rows + 2), // Attachments
+      Constraint::Min(5),                      // Body
+      Constraint::Length(3),                   // Send Button
+      Constraint::Length(1),                   // Spacer
+      Constraint::Length(1),                   // Schedule Hint
     ])
     .split(area);
 
+  let to_row = Layout::default()
+    .direction(Direction::Horizontal)
+    .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+    .split(layout[0]);
+
   let styles = super::get_field_styles(
     app,
     &[
+      ComposeField::From,
       ComposeField::Recipient,
+      ComposeField::Cc,
+      ComposeField::Bcc,
       ComposeField::Subject,
+      ComposeField::Variables,
       ComposeField::Attachments,
       ComposeField::Body,
       ComposeField::SendButton,
@@ -33,56 +54,133 @@ pub fn draw_compose(frame: &mut Frame, app: &App, area: Rect) {
     |f| app.compose.field == *f,
   );
 
-  // 1. Recipient
-  let recipient = Paragraph::new(app.compose.draft.recipient.as_str())
-    .block(Block::default().borders(Borders::ALL).title("To"))
+  // 1. Sender and recipients
+  let profile = app.config.data.profile(&app.compose.draft.profile);
+  let from = Paragraph::new(profile.label())
+    .block(
+      Block::default()
+        .borders(Borders::ALL)
+        .title("From (Enter to switch)"),
+    )
     .style(styles[0]);
-  frame.render_widget(recipient, layout[0]);
+  frame.render_widget(from, to_row[0]);
 
-  // 2. Subject
-  let subject = Paragraph::new(app.compose.draft.subject.as_str())
-    .block(Block::default().borders(Borders::ALL).title("Subject"))
-    .style(styles[1]);
-  frame.render_widget(subject, layout[1]);
-
-  // 3. Attachments
-  let attach_widget = Paragraph::new(app.compose.attachment_input.as_str())
+  let recipient = Paragraph::new(app.compose.to_input.as_str())
     .block(
       Block::default()
         .borders(Borders::ALL)
-        .title("Attachments (semicolon separated paths)"),
+        .title("To (comma separated, \"Name\" <addr> allowed)"),
     )
+    .style(styles[1]);
+  frame.render_widget(recipient, to_row[1]);
+
+  let cc = Paragraph::new(app.compose.cc_input.as_str())
+    .block(Block::default().borders(Borders::ALL).title("Cc"))
     .style(styles[2]);
-  frame.render_widget(attach_widget, layout[2]);
+  frame.render_widget(cc, layout[1]);
+
+  let bcc = Paragraph::new(app.compose.bcc_input.as_str())
+    .block(Block::default().borders(Borders::ALL).title("Bcc"))
+    .style(styles[3]);
+  frame.render_widget(bcc, layout[2]);
 
-  // 4. Body
-  let body_content = if app.compose.draft.body.is_empty() {
-    "Press <Enter> to open external editor...".to_string()
+  // 2. Subject
+  let subject = Paragraph::new(app.compose.draft.subject.as_str())
+    .block(Block::default().borders(Borders::ALL).title("Subject"))
+    .style(styles[4]);
+  frame.render_widget(subject, layout[3]);
+
+  // 3. Variables
+  let unfilled = compiler::unfilled_with(
+    &app.compose.draft,
+    &parse_variables(&app.compose.variables_input),
+  );
+  let variables_title = if unfilled.is_empty() {
+    "Variables (key=value; ..., \\; for a literal ;) for {{key}} placeholders".to_string()
   } else {
-    app.compose.draft.body.clone()
+    format!("Variables - missing: {}", unfilled.join(", "))
   };
-  let body = Paragraph::new(body_content)
+  let variables = Paragraph::new(app.compose.variables_input.as_str())
     .block(
       Block::default()
         .borders(Borders::ALL)
-        .title("Body (Markdown) - Press Enter to Edit"),
+        .title(variables_title),
     )
-    .style(styles[3]);
-  frame.render_widget(body, layout[3]);
+    .style(styles[5]);
+  frame.render_widget(variables, layout[4]);
+
+  // 4. Attachments
+  let diagnostics = &app.compose.diagnostics;
+  draw_attachments(frame, app, layout[5], styles[6], diagnostics);
+
+  // 5. Body
+  if app.compose.preview {
+    let preview = Paragraph::new(preview::render_preview(&app.compose.draft, profile))
+      .wrap(Wrap { trim: false })
+      .scroll((app.compose.preview_scroll, 0))
+      .block(
+        Block::default()
+          .borders(Borders::ALL)
+          .border_style(styles[7])
+          .title("Preview - [Ctrl+r] Back to Markdown | [PgUp/PgDn] Scroll"),
+      );
+    frame.render_widget(preview, layout[6]);
+  } else {
+    let body_content = if app.compose.draft.body.is_empty() {
+      "Press <Enter> to open external editor...".to_string()
+    } else {
+      app.compose.draft.body.clone()
+    };
+    let broken_images = diagnostics.iter().filter(|d| d.inline).count();
+    let body_title = if broken_images == 0 {
+      "Body (Markdown) - Press Enter to Edit | [Ctrl+r] Preview".to_string()
+    } else {
+      format!(
+        "Body (Markdown) - {} unreadable inline image(s) | [Ctrl+r] Preview",
+        broken_images
+      )
+    };
+    let body = Paragraph::new(body_content)
+      .block(Block::default().borders(Borders::ALL).title(body_title))
+      .style(styles[7]);
+    frame.render_widget(body, layout[6]);
+  }
 
-  // 5. Send Button
+  // 6. Send Button
   let button_text = if app.compose.field == ComposeField::SendButton {
     "> [SEND EMAIL] <"
   } else {
     "  [SEND EMAIL]  "
   };
+  let blocking = diagnostics.iter().filter(|d| !d.waived).count();
+  let waived = diagnostics.len() - blocking;
+  let mut send_block = Block::default().borders(Borders::ALL);
+  if blocking > 0 {
+    send_block = send_block.title(
+      Line::from(format!(
+        " {} file problem(s) block sending - [Ctrl+w] Send without them ",
+        blocking
+      ))
+      .style(Style::default().fg(Color::Red)),
+    );
+  } else if waived > 0 {
+    send_block = send_block.title(
+      Line::from(format!(
+        " Sending without {} unreadable file(s) - [Ctrl+w] Undo ",
+        waived
+      ))
+      .style(Style::default().fg(Color::Yellow)),
+    );
+  }
   let send_btn = Paragraph::new(button_text)
     .alignment(ratatui::layout::Alignment::Center)
-    .block(Block::default().borders(Borders::ALL))
-    .style(styles[4]);
-  frame.render_widget(send_btn, layout[4]);
+    .block(send_block)
+    .style(styles[8]);
+  frame.render_widget(send_btn, layout[7]);
+
+  // 7. Theme, size estimate and Schedule Hint
+  frame.render_widget(Paragraph::new(status_line(app)), layout[9]);
 
-  // 6. Schedule Hint
   let schedule_hint = Paragraph::new("Tip: Press [Ctrl + s] to Schedule Send")
     .style(
       Style::default()
@@ -90,5 +188,196 @@ pub fn draw_compose(frame: &mut Frame, app: &App, area: Rect) {
         .add_modifier(Modifier::ITALIC),
     )
     .alignment(ratatui::layout::Alignment::Right);
-  frame.render_widget(schedule_hint, layout[6]);
+  frame.render_widget(schedule_hint, layout[9]);
+
+  // 8. Address suggestions, drawn last so they overlay the fields below
+  let anchor = match app.compose.field {
+    ComposeField::Recipient => Some(to_row[1]),
+    ComposeField::Cc => Some(layout[1]),
+    ComposeField::Bcc => Some(layout[2]),
+    _ => None,
+  };
+  if let Some(anchor) = anchor
+    && app.input_mode == InputMode::Editing
+    && !app.compose.suggestions.is_empty()
+  {
+    draw_suggestions(frame, app, anchor, area);
+  }
+}
+
+fn draw_attachments(
+  frame: &mut Frame,
+  app: &App,
+  area: Rect,
+  style: Style,
+  diagnostics: &[FileDiagnostic],
+) {
+  let attachments = &app.compose.draft.attachments;
+  let mut block = Block::default()
+    .borders(Borders::ALL)
+    .title(format!("Attachments ({})", attachments.len()))
+    .border_style(style);
+  if app.input_mode == InputMode::Editing && app.compose.field == ComposeField::Attachments {
+    block = block.title_bottom(Line::from(format!(
+      " Add path: {}_ ",
+      app.compose.attachment_input
+    )));
+  }
+
+  if attachments.is_empty() {
+    let hint = Paragraph::new("No attachments - [Enter] Type a path | [Ctrl+o] Browse")
+      .style(Style::default().fg(Color::DarkGray))
+      .block(block);
+    frame.render_widget(hint, area);
+    return;
+  }
+
+  let rows: Vec<Row> = attachments
+    .iter()
+    .map(|path| {
+      let info = AttachmentInfo::inspect(path);
+      let problem = diagnostics.iter().find(|d| !d.inline && d.path == *path);
+      let (status, status_style) = match problem {
+        None => ("ok", Style::default().fg(Color::Green)),
+        Some(d) if d.waived => ("waived", Style::default().fg(Color::Yellow)),
+        Some(d) => (d.problem.label(), Style::default().fg(Color::Red)),
+      };
+      Row::new(vec![
+        Cell::from(info.name),
+        Cell::from(info.size.map(attachments::format_size).unwrap_or_default()),
+        Cell::from(info.mime),
+        Cell::from(status).style(status_style),
+      ])
+    })
+    .collect();
+
+  let table = Table::new(
+    rows,
+    [
+      Constraint::Percentage(45),
+      Constraint::Length(10),
+      Constraint::Percentage(35),
+      Constraint::Length(12),
+    ],
+  )
+  .block(block)
+  .row_highlight_style(if app.compose.field == ComposeField::Attachments {
+    Style::default()
+      .bg(Color::DarkGray)
+      .add_modifier(Modifier::BOLD)
+  } else {
+    Style::default()
+  });
+
+  let mut state = app.compose.attachment_state.clone();
+  frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn status_line(app: &App) -> Line<'static> {
+  let config = &app.config.data;
+  let estimate = app.compose.size;
+
+  let mut text = format!("Size: ~{}", attachments::format_size(estimate.message));
+  let color = match size::limit_bytes(config.message_size_limit_mb) {
+    Some(limit) => {
+      text.push_str(&format!(" of {}", attachments::format_size(limit)));
+      if estimate.message > limit {
+        text.push_str(" - too large to send");
+        Color::Red
+      } else if estimate.message * 5 > limit * 4 {
+        Color::Yellow
+      } else {
+        Color::DarkGray
+      }
+    }
+    None => Color::DarkGray,
+  };
+
+  let mut spans = vec![
+    Span::styled(
+      format!(
+        "Theme: {} [Ctrl+t] | Signature: {} [Ctrl+g] | ",
+        theme::resolve(&app.compose.draft.theme),
+        signature::chosen(
+          &app.compose.draft,
+          config.profile(&app.compose.draft.profile)
+        )
+      ),
+      Style::default().fg(Color::DarkGray),
+    ),
+    tracking_span(app),
+    Span::styled(text, Style::default().fg(color)),
+  ];
+  if let Some(warning) = size::upload_limit_warning(estimate.upload, config) {
+    spans.push(Span::styled(
+      format!(" | Scheduling: {}", warning),
+      Style::default().fg(Color::Yellow),
+    ));
+  }
+  Line::from(spans)
+}
+
+/// Tracking mode of the draft, and whether the pixel is actually embedded.
+fn tracking_span(app: &App) -> Span<'static> {
+  let draft = &app.compose.draft;
+  let (text, color) = match draft.tracking {
+    TrackingMode::Off => ("off".to_string(), Color::DarkGray),
+    TrackingMode::Override => (
+      "on for everyone (consent overridden)".to_string(),
+      Color::Yellow,
+    ),
+    TrackingMode::Consented => {
+      let expanded = contacts::expand_groups(draft, &app.contacts.book)
+        .map(|(expanded, _)| expanded)
+        .unwrap_or_else(|_| draft.clone());
+      let missing = app.contacts.consent.without_consent(&expanded);
+      if missing.is_empty() {
+        ("on".to_string(), Color::Green)
+      } else {
+        (
+          format!("no pixel, {} without consent", missing.len()),
+          Color::Yellow,
+        )
+      }
+    }
+  };
+  Span::styled(
+    format!("Tracking: {} [Ctrl+k] | ", text),
+    Style::default().fg(color),
+  )
+}
+
+fn draw_suggestions(frame: &mut Frame, app: &App, anchor: Rect, area: Rect) {
+  let height = (app.compose.suggestions.len() as u16 + 2).min(area.bottom() - anchor.bottom());
+  let dropdown = Rect::new(anchor.x, anchor.bottom(), anchor.width, height);
+
+  let items: Vec<ListItem> = app
+    .compose
+    .suggestions
+    .iter()
+    .enumerate()
+    .map(|(i, suggestion)| {
+      let is_selected = i == app.compose.suggestion_idx;
+      let style = if is_selected {
+        Style::default()
+          .bg(Color::Blue)
+          .fg(Color::Black)
+          .add_modifier(Modifier::BOLD)
+      } else {
+        Style::default().fg(Color::Gray)
+      };
+      let prefix = if is_selected { "> " } else { "  " };
+      ListItem::new(format!("{}{}", prefix, suggestion.label)).style(style)
+    })
+    .collect();
+
+  frame.render_widget(Clear, dropdown);
+  frame.render_widget(
+    List::new(items).block(
+      Block::default()
+        .borders(Borders::ALL)
+        .title(" Contacts - [Up/Down] Select | [Enter] Accept "),
+    ),
+    dropdown,
+  );
 }
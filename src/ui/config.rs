@@ -1,5 +1,6 @@
 use crate::app::App;
-use crate::enums::{ConfigField, InputMode};
+use crate::enums::{ConfigField, FilePickerMode, InputMode, SmtpSecurity};
+use crate::signature;
 use ratatui::{
   Frame,
   layout::{Constraint, Direction, Layout, Rect},
@@ -15,36 +16,65 @@ pub fn draw_config(frame: &mut Frame, app: &App, area: Rect) {
     secure: bool,
   }
 
-  let email_str = app.config.data.identity.emails.join(", ");
+  let profile = app.config.profile();
+  let profile_str = format!(
+    "< {} ({} of {}) >",
+    profile.label(),
+    app.config.profile + 1,
+    app.config.data.profiles.len()
+  );
+  let email_str = profile.identity.emails.join(", ");
+  let security_str = match profile.smtp_security {
+    SmtpSecurity::ImplicitTls => "< Implicit TLS >",
+    SmtpSecurity::StartTls => "< STARTTLS >",
+    SmtpSecurity::Plaintext => "< Plaintext (local relays only) >",
+  };
+  let picker_str = match app.config.data.file_picker {
+    FilePickerMode::Auto => "< Auto (built-in without a display server) >",
+    FilePickerMode::Terminal => "< Built-in terminal browser >",
+    FilePickerMode::System => "< System dialog >",
+  };
   let items = vec![
+    ConfigItem {
+      field: ConfigField::Profile,
+      title: "Sender Profile (Enter to switch, n to add, x to remove)",
+      value: profile_str,
+      secure: false,
+    },
+    ConfigItem {
+      field: ConfigField::ProfileName,
+      title: "Profile Name",
+      value: profile.name.clone(),
+      secure: false,
+    },
     ConfigItem {
       field: ConfigField::Name,
       title: "Full Name",
-      value: app.config.data.identity.name.clone(),
+      value: profile.identity.name.clone(),
       secure: false,
     },
     ConfigItem {
       field: ConfigField::Role,
       title: "Role",
-      value: app.config.data.identity.role.clone(),
+      value: profile.identity.role.clone(),
       secure: false,
     },
     ConfigItem {
       field: ConfigField::Department,
       title: "Department",
-      value: app.config.data.identity.department.clone(),
+      value: profile.identity.department.clone(),
       secure: false,
     },
     ConfigItem {
       field: ConfigField::Institution,
       title: "Institution",
-      value: app.config.data.identity.institution.clone(),
+      value: profile.identity.institution.clone(),
       secure: false,
     },
     ConfigItem {
       field: ConfigField::Phone,
       title: "Phone",
-      value: app.config.data.identity.phone.clone(),
+      value: profile.identity.phone.clone(),
       secure: false,
     },
     ConfigItem {
@@ -56,21 +86,69 @@ pub fn draw_config(frame: &mut Frame, app: &App, area: Rect) {
     ConfigItem {
       field: ConfigField::FooterColor,
       title: "Footer Color (Hex)",
-      value: app.config.data.identity.footer_color.clone(),
+      value: profile.identity.footer_color.clone(),
+      secure: false,
+    },
+    ConfigItem {
+      field: ConfigField::Signature,
+      title: "Default Signature (Enter to toggle)",
+      value: format!("< {} >", signature::resolve(&profile.signature)),
       secure: false,
     },
     ConfigItem {
       field: ConfigField::SmtpUser,
       title: "SMTP Email",
-      value: app.config.data.smtp_username.clone(),
+      value: profile.smtp_username.clone(),
       secure: false,
     },
     ConfigItem {
       field: ConfigField::SmtpPass,
       title: "SMTP App Password",
-      value: app.config.data.smtp_app_password.clone(),
+      value: profile.smtp_app_password.clone(),
       secure: true,
     },
+    ConfigItem {
+      field: ConfigField::SmtpHost,
+      title: "SMTP Host",
+      value: profile.smtp_host.clone(),
+      secure: false,
+    },
+    ConfigItem {
+      field: ConfigField::SmtpPort,
+      title: "SMTP Port",
+      value: app.config.smtp_port_input.clone(),
+      secure: false,
+    },
+    ConfigItem {
+      field: ConfigField::SmtpSecurity,
+      title: "SMTP Security (Enter to toggle)",
+      value: security_str.to_string(),
+      secure: false,
+    },
+    ConfigItem {
+      field: ConfigField::PlainTextWidth,
+      title: "Plain Text Wrap Width (0 = no wrapping)",
+      value: app.config.data.plain_text_width.to_string(),
+      secure: false,
+    },
+    ConfigItem {
+      field: ConfigField::SendRate,
+      title: "Send Rate (messages per minute, 0 = unlimited)",
+      value: app.config.data.send_rate_per_minute.to_string(),
+      secure: false,
+    },
+    ConfigItem {
+      field: ConfigField::SendConcurrency,
+      title: "Max Concurrent Sends",
+      value: app.config.data.max_concurrent_sends.to_string(),
+      secure: false,
+    },
+    ConfigItem {
+      field: ConfigField::MessageSizeLimit,
+      title: "Message Size Limit (MB, 0 = no limit)",
+      value: app.config.data.message_size_limit_mb.to_string(),
+      secure: false,
+    },
     ConfigItem {
       field: ConfigField::WorkerUrl,
       title: "Worker URL",
@@ -83,6 +161,24 @@ pub fn draw_config(frame: &mut Frame, app: &App, area: Rect) {
       value: app.config.data.api_secret.clone(),
       secure: true,
     },
+    ConfigItem {
+      field: ConfigField::TrackingSecret,
+      title: "Tracking Secret (changing it marks earlier opens untrusted)",
+      value: app.config.data.tracking_secret.clone(),
+      secure: true,
+    },
+    ConfigItem {
+      field: ConfigField::WorkerUploadLimit,
+      title: "Worker Upload Limit (MB, 0 = no limit)",
+      value: app.config.data.worker_upload_limit_mb.to_string(),
+      secure: false,
+    },
+    ConfigItem {
+      field: ConfigField::FilePicker,
+      title: "File Picker (Enter to toggle)",
+      value: picker_str.to_string(),
+      secure: false,
+    },
   ];
 
   let item_height = 3;
@@ -0,0 +1,288 @@
+use crate::app::App;
+use crate::enums::{ContactField, ContactsFocus, GroupField, InputMode};
+use crate::models::Contact;
+use chrono::{DateTime, Local, Utc};
+use ratatui::{
+  Frame,
+  layout::{Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Line,
+  widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+
+pub fn draw_contacts(frame: &mut Frame, app: &App, area: Rect) {
+  let chunks = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+    .split(area);
+
+  let top = Layout::default()
+    .direction(Direction::Horizontal)
+    .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+    .split(chunks[0]);
+
+  draw_contact_list(frame, app, top[0]);
+  draw_group_list(frame, app, top[1]);
+  draw_suggestions(frame, app, chunks[1]);
+
+  if app.input_mode == InputMode::Editing {
+    match app.contacts.focus {
+      ContactsFocus::Groups => draw_group_popup(frame, app, area),
+      _ => draw_form_popup(frame, app, area),
+    }
+  }
+}
+
+fn border_style(app: &App, list: ContactsFocus) -> Style {
+  if app.contacts.focus == list {
+    Style::default().fg(Color::Yellow)
+  } else {
+    Style::default()
+  }
+}
+
+fn draw_contact_list(frame: &mut Frame, app: &App, area: Rect) {
+  let rows: Vec<Row> = app
+    .contacts
+    .book
+    .contacts
+    .iter()
+    .map(|contact| {
+      Row::new(vec![
+        Cell::from(contact.name.clone()),
+        Cell::from(contact.addresses.join(", ")),
+        Cell::from(contact.timezone.clone()),
+        Cell::from(contact.notes.clone()),
+        consent_cell(app, contact),
+      ])
+    })
+    .collect();
+
+  let table = Table::new(
+    rows,
+    [
+      Constraint::Percentage(22),
+      Constraint::Percentage(30),
+      Constraint::Percentage(13),
+      Constraint::Percentage(20),
+      Constraint::Percentage(15),
+    ],
+  )
+  .header(
+    Row::new(vec!["Name", "Addresses", "Timezone", "Notes", "Tracking"]).style(
+      Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD),
+    ),
+  )
+  .block(
+    Block::default()
+      .borders(Borders::ALL)
+      .title(match &app.contacts.load_error {
+        Some(e) => Line::from(format!(" Contacts - read-only: {} ", e)).style(Style::default().fg(Color::Red)),
+        None => Line::from(" Contacts "),
+      })
+      .title_bottom(
+        Line::from(
+          "[n] New | [Enter] Edit | [x] Delete | [t] Tracking Consent | [i] Import | [e] Export .vcf",
+        )
+          .right_aligned(),
+      )
+      .border_style(border_style(app, ContactsFocus::Contacts)),
+  )
+  .row_highlight_style(
+    Style::default()
+      .bg(Color::DarkGray)
+      .add_modifier(Modifier::BOLD),
+  );
+
+  let mut state = app.contacts.list_state.clone();
+  frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_group_list(frame: &mut Frame, app: &App, area: Rect) {
+  let rows: Vec<Row> = app
+    .contacts
+    .book
+    .groups
+    .iter()
+    .map(|group| {
+      Row::new(vec![
+        Cell::from(format!("@{}", group.name)),
+        Cell::from(group.members.len().to_string()),
+      ])
+    })
+    .collect();
+
+  let table = Table::new(
+    rows,
+    [Constraint::Percentage(75), Constraint::Percentage(25)],
+  )
+  .header(
+    Row::new(vec!["Group", "Members"]).style(
+      Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD),
+    ),
+  )
+  .block(
+    Block::default()
+      .borders(Borders::ALL)
+      .title(" Groups ")
+      .title_bottom(Line::from("[n] New | [Enter] Edit | [x] Delete").right_aligned())
+      .border_style(border_style(app, ContactsFocus::Groups)),
+  )
+  .row_highlight_style(
+    Style::default()
+      .bg(Color::DarkGray)
+      .add_modifier(Modifier::BOLD),
+  );
+
+  let mut state = app.contacts.group_state.clone();
+  frame.render_stateful_widget(table, area, &mut state);
+}
+
+/// When the contact agreed to tracking, or how many of their addresses did.
+fn consent_cell(app: &App, contact: &Contact) -> Cell<'static> {
+  let dates: Vec<DateTime<Utc>> = contact
+    .addresses
+    .iter()
+    .filter_map(|a| app.contacts.consent.granted_at(a))
+    .collect();
+  match dates.iter().min() {
+    None => Cell::from("-"),
+    Some(_) if dates.len() < contact.addresses.len() => {
+      Cell::from(format!("{} of {}", dates.len(), contact.addresses.len()))
+        .style(Style::default().fg(Color::Yellow))
+    }
+    Some(first) => Cell::from(format!(
+      "since {}",
+      first.with_timezone(&Local).format("%Y-%m-%d")
+    ))
+    .style(Style::default().fg(Color::Green)),
+  }
+}
+
+fn draw_suggestions(frame: &mut Frame, app: &App, area: Rect) {
+  let rows: Vec<Row> = app
+    .contacts
+    .suggestions
+    .iter()
+    .map(|address| Row::new(vec![Cell::from(address.clone())]))
+    .collect();
+
+  let table = Table::new(rows, [Constraint::Percentage(100)])
+    .block(
+      Block::default()
+        .borders(Borders::ALL)
+        .title(" Sent To, Not Saved ")
+        .title_bottom(Line::from("[Enter] Save as Contact | [x] Ignore").right_aligned())
+        .border_style(border_style(app, ContactsFocus::Suggestions)),
+    )
+    .row_highlight_style(
+      Style::default()
+        .bg(Color::DarkGray)
+        .add_modifier(Modifier::BOLD),
+    );
+
+  let mut state = app.contacts.suggestion_state.clone();
+  frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_form_popup(frame: &mut Frame, app: &App, area: Rect) {
+  let popup_area = crate::ui::centered_popup(60, 14, area);
+  frame.render_widget(Clear, popup_area);
+
+  let form = &app.contacts.form;
+  let title = if form.id.is_some() {
+    " Edit Contact "
+  } else {
+    " New Contact "
+  };
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title(title)
+    .title_bottom("[Tab] Next Field | [Enter] Save | [Esc] Cancel");
+  let inner = block.inner(popup_area);
+  frame.render_widget(block, popup_area);
+
+  let rows = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([Constraint::Length(3); 4])
+    .split(inner);
+
+  let fields = [
+    (ContactField::Name, "Name", &form.name),
+    (
+      ContactField::Addresses,
+      "Addresses (comma separated)",
+      &form.addresses,
+    ),
+    (
+      ContactField::Timezone,
+      "Default Timezone (e.g. Europe/Berlin)",
+      &form.timezone,
+    ),
+    (ContactField::Notes, "Notes", &form.notes),
+  ];
+
+  for (i, (field, title, value)) in fields.into_iter().enumerate() {
+    let style = if form.field == field {
+      Style::default().fg(Color::Yellow)
+    } else {
+      Style::default().fg(Color::White)
+    };
+    frame.render_widget(
+      Paragraph::new(value.as_str())
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .style(style),
+      rows[i],
+    );
+  }
+}
+
+fn draw_group_popup(frame: &mut Frame, app: &App, area: Rect) {
+  let popup_area = crate::ui::centered_popup(60, 8, area);
+  frame.render_widget(Clear, popup_area);
+
+  let form = &app.contacts.group_form;
+  let title = if form.original.is_some() {
+    " Edit Group "
+  } else {
+    " New Group "
+  };
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title(title)
+    .title_bottom("[Tab] Next Field | [Enter] Save | [Esc] Cancel");
+  let inner = block.inner(popup_area);
+  frame.render_widget(block, popup_area);
+
+  let rows = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([Constraint::Length(3); 2])
+    .split(inner);
+
+  let fields = [
+    (GroupField::Name, "Name (used as @name)", &form.name),
+    (
+      GroupField::Members,
+      "Members (comma separated addresses)",
+      &form.members,
+    ),
+  ];
+
+  for (i, (field, title, value)) in fields.into_iter().enumerate() {
+    let style = if form.field == field {
+      Style::default().fg(Color::Yellow)
+    } else {
+      Style::default().fg(Color::White)
+    };
+    frame.render_widget(
+      Paragraph::new(value.as_str())
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .style(style),
+      rows[i],
+    );
+  }
+}
@@ -1,7 +1,6 @@
 use crate::app::App;
-use crate::app::dashboard::RecipientSummary;
+use crate::app::dashboard::{DashboardState, RecipientSummary};
 use crate::enums::{DashboardFocus, InputMode};
-use base64::Engine;
 use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
 use ratatui::{
@@ -27,7 +26,7 @@ pub fn draw_dashboard(frame: &mut Frame, app: &App, area: Rect) {
   if let Some(selected_id) = &app.dashboard.selected_summary_id {
     let summaries = app.dashboard.get_aggregated_logs();
     if let Some(summary) = summaries.iter().find(|s| s.tracking_id == *selected_id) {
-      draw_detail_popup(frame, summary, area);
+      draw_detail_popup(frame, &app.dashboard, summary, area);
     }
   }
 }
@@ -36,9 +35,10 @@ fn draw_filter_bar(frame: &mut Frame, app: &App, area: Rect) {
   let layout = Layout::default()
     .direction(Direction::Horizontal)
     .constraints([
-      Constraint::Percentage(40),
-      Constraint::Percentage(30),
-      Constraint::Percentage(30),
+      Constraint::Percentage(35),
+      Constraint::Percentage(25),
+      Constraint::Percentage(20),
+      Constraint::Percentage(20),
     ])
     .split(area);
 
@@ -58,17 +58,15 @@ fn draw_filter_bar(frame: &mut Frame, app: &App, area: Rect) {
     && app.dashboard.focus == DashboardFocus::FilterRecipient
     && !r_text.is_empty()
   {
-    if let Some(suggestion) = app
+    if let Some(hint) = app
       .dashboard
       .filter_options
       .recipients
       .iter()
+      .filter_map(|token| app.dashboard.tracked_messages.get(token))
+      .flat_map(|m| &m.recipients)
       .find(|r| r.contains(&r_text))
     {
-      let decoded = base64::engine::general_purpose::URL_SAFE_NO_PAD
-        .decode(suggestion)
-        .unwrap_or_default();
-      let hint = String::from_utf8_lossy(&decoded);
       r_text = format!("{} ({})", r_text, hint);
     }
   }
@@ -81,6 +79,14 @@ fn draw_filter_bar(frame: &mut Frame, app: &App, area: Rect) {
     )
     .style(get_style(DashboardFocus::FilterRecipient));
 
+  let g_input = Paragraph::new(app.dashboard.filter_group.as_str())
+    .block(
+      Block::default()
+        .borders(Borders::ALL)
+        .title(" Filter Group "),
+    )
+    .style(get_style(DashboardFocus::FilterGroup));
+
   let c_input = Paragraph::new(app.dashboard.filter_country.as_str())
     .block(
       Block::default()
@@ -94,8 +100,9 @@ fn draw_filter_bar(frame: &mut Frame, app: &App, area: Rect) {
     .style(get_style(DashboardFocus::FilterMinOpens));
 
   frame.render_widget(r_input, layout[0]);
-  frame.render_widget(c_input, layout[1]);
-  frame.render_widget(m_input, layout[2]);
+  frame.render_widget(g_input, layout[1]);
+  frame.render_widget(c_input, layout[2]);
+  frame.render_widget(m_input, layout[3]);
 }
 
 fn draw_summary_list(frame: &mut Frame, app: &App, area: Rect) {
@@ -122,8 +129,16 @@ fn draw_summary_list(frame: &mut Frame, app: &App, area: Rect) {
       let current_time = now.with_timezone(&tz).format("%H:%M %p").to_string();
 
       Row::new(vec![
-        Cell::from(s.decoded_email.clone()),
-        Cell::from(s.open_count.to_string()),
+        Cell::from(s.recipients.clone()),
+        Cell::from(s.subject.clone()),
+        Cell::from(
+          s.groups
+            .iter()
+            .map(|g| format!("@{}", g))
+            .collect::<Vec<_>>()
+            .join(" "),
+        ),
+        opens_cell(s),
         Cell::from(time_ago),
         Cell::from(current_time),
         Cell::from(s.country.clone()),
@@ -134,16 +149,20 @@ fn draw_summary_list(frame: &mut Frame, app: &App, area: Rect) {
   let table = Table::new(
     rows,
     [
-      Constraint::Percentage(40),
-      Constraint::Length(10),
+      Constraint::Percentage(25),
+      Constraint::Percentage(20),
+      Constraint::Percentage(15),
+      Constraint::Length(16),
       Constraint::Length(15),
       Constraint::Length(15),
-      Constraint::Percentage(20),
+      Constraint::Percentage(10),
     ],
   )
   .header(
     Row::new(vec![
       "Recipient",
+      "Subject",
+      "Groups",
       "Opens",
       "Last Seen",
       "Current Time",
@@ -175,13 +194,30 @@ fn draw_summary_list(frame: &mut Frame, app: &App, area: Rect) {
   frame.render_stateful_widget(table, area, &mut state);
 }
 
-fn draw_detail_popup(frame: &mut Frame, summary: &RecipientSummary, area: Rect) {
+// Opens from unsigned or forged pixel URLs are counted but called out
+fn opens_cell(summary: &RecipientSummary) -> Cell<'static> {
+  if summary.untrusted_count == 0 {
+    return Cell::from(summary.open_count.to_string());
+  }
+  Cell::from(format!(
+    "{} ({} untrusted)",
+    summary.open_count, summary.untrusted_count
+  ))
+  .style(Style::default().fg(Color::Red))
+}
+
+fn draw_detail_popup(
+  frame: &mut Frame,
+  dashboard: &DashboardState,
+  summary: &RecipientSummary,
+  area: Rect,
+) {
   let popup_area = crate::ui::centered_rect(60, 60, area);
   frame.render_widget(Clear, popup_area);
 
   let block = Block::default()
     .borders(Borders::ALL)
-    .title(format!(" Activity: {} ", summary.decoded_email))
+    .title(format!(" Activity: {} ", summary.recipients))
     .title_bottom(Line::from("[Ctrl+d] Delete History").right_aligned());
 
   let inner = block.inner(popup_area);
@@ -203,14 +239,24 @@ fn draw_detail_popup(frame: &mut Frame, summary: &RecipientSummary, area: Rect)
       let tz: Tz = log.timezone.parse().ok().unwrap_or(chrono_tz::UTC);
       let local = utc.with_timezone(&tz);
 
-      Row::new(vec![Cell::from(
-        local.format("%Y-%m-%d %H:%M:%S").to_string(),
-      )])
+      let trust = if dashboard.is_trusted(log) {
+        Cell::from("Signed").style(Style::default().fg(Color::Green))
+      } else {
+        Cell::from("Untrusted").style(Style::default().fg(Color::Red))
+      };
+
+      Row::new(vec![
+        Cell::from(local.format("%Y-%m-%d %H:%M:%S").to_string()),
+        trust,
+      ])
     })
     .collect();
 
-  let table = Table::new(rows, [Constraint::Percentage(100)])
-    .header(Row::new(vec!["Local Timestamp"]).style(Style::default().fg(Color::Cyan)));
+  let table = Table::new(
+    rows,
+    [Constraint::Percentage(70), Constraint::Percentage(30)],
+  )
+  .header(Row::new(vec!["Local Timestamp", "Signature"]).style(Style::default().fg(Color::Cyan)));
 
   frame.render_widget(table, chunks[0]);
 
@@ -225,9 +271,12 @@ fn draw_detail_popup(frame: &mut Frame, summary: &RecipientSummary, area: Rect)
     .to_string();
   let ua = latest_log.map(|l| l.user_agent.clone()).unwrap_or_default();
 
-  let info = Paragraph::new(format!("Current User Time: {}\nDevice: {}", current, ua))
-    .wrap(Wrap { trim: true })
-    .style(Style::default().fg(Color::Green));
+  let info = Paragraph::new(format!(
+    "Subject: {}\nCurrent User Time: {}\nDevice: {}",
+    summary.subject, current, ua
+  ))
+  .wrap(Wrap { trim: true })
+  .style(Style::default().fg(Color::Green));
 
   frame.render_widget(info, chunks[1]);
 }
@@ -0,0 +1,134 @@
+use crate::app::App;
+use crate::enums::{DraftsFocus, InputMode};
+use crate::models::EmailDraft;
+use ratatui::{
+  Frame,
+  layout::{Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Line,
+  widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState},
+};
+
+pub fn draw_drafts(frame: &mut Frame, app: &App, area: Rect) {
+  let chunks = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+    .split(area);
+
+  draw_list(
+    frame,
+    app,
+    &app.drafts.drafts,
+    &app.drafts.list_state,
+    DraftsFocus::Drafts,
+    chunks[0],
+  );
+  draw_list(
+    frame,
+    app,
+    &app.drafts.templates,
+    &app.drafts.template_state,
+    DraftsFocus::Templates,
+    chunks[1],
+  );
+
+  if app.input_mode == InputMode::Editing {
+    draw_rename_popup(frame, app, area);
+  }
+}
+
+fn draw_list(
+  frame: &mut Frame,
+  app: &App,
+  entries: &[EmailDraft],
+  state: &TableState,
+  list: DraftsFocus,
+  area: Rect,
+) {
+  let rows: Vec<Row> = entries
+    .iter()
+    .map(|draft| {
+      let is_active = list == DraftsFocus::Drafts && draft.id == app.compose.draft.id;
+      let marker = if is_active { "●" } else { " " };
+      let style = if is_active {
+        Style::default().fg(Color::Green)
+      } else {
+        Style::default()
+      };
+
+      Row::new(vec![
+        Cell::from(marker),
+        Cell::from(draft.display_name()),
+        Cell::from(draft.to.join(", ")),
+        Cell::from(draft.subject.clone()),
+      ])
+      .style(style)
+    })
+    .collect();
+
+  let (title, help) = match list {
+    DraftsFocus::Drafts => (
+      " Drafts ",
+      "[Enter] Open | [n] New | [c] Duplicate | [t] Save as Template | [r] Rename | [x] Delete",
+    ),
+    DraftsFocus::Templates => (
+      " Templates ",
+      "[Enter] New Draft from Template | [r] Rename | [x] Delete",
+    ),
+  };
+
+  let table = Table::new(
+    rows,
+    [
+      Constraint::Length(2),
+      Constraint::Percentage(30),
+      Constraint::Percentage(30),
+      Constraint::Percentage(40),
+    ],
+  )
+  .header(
+    Row::new(vec!["", "Name", "To", "Subject"]).style(
+      Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD),
+    ),
+  )
+  .block(
+    Block::default()
+      .borders(Borders::ALL)
+      .title(title)
+      .title_bottom(Line::from(help).right_aligned())
+      .border_style(if app.drafts.focus == list {
+        Style::default().fg(Color::Yellow)
+      } else {
+        Style::default()
+      }),
+  )
+  .row_highlight_style(
+    Style::default()
+      .bg(Color::DarkGray)
+      .add_modifier(Modifier::BOLD),
+  );
+
+  let mut state = state.clone();
+  frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_rename_popup(frame: &mut Frame, app: &App, area: Rect) {
+  let popup_area = crate::ui::centered_popup(50, 3, area);
+  frame.render_widget(Clear, popup_area);
+
+  let title = match app.drafts.focus {
+    DraftsFocus::Drafts => " Rename Draft ",
+    DraftsFocus::Templates => " Rename Template ",
+  };
+  let input = Paragraph::new(app.drafts.rename_input.as_str())
+    .block(
+      Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom("[Enter] Save | [Esc] Cancel"),
+    )
+    .style(Style::default().fg(Color::Yellow));
+  frame.render_widget(input, popup_area);
+}
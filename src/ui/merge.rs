@@ -0,0 +1,215 @@
+use crate::app::App;
+use crate::compiler;
+use crate::enums::{MergeFocus, MergeRowStatus};
+use ratatui::{
+  Frame,
+  layout::{Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::{Line, Span},
+  widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+};
+
+pub fn draw_merge(frame: &mut Frame, app: &App, area: Rect) {
+  let layout = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([
+      Constraint::Length(3), // CSV Path / Address Column
+      Constraint::Min(5),    // Rows / Preview
+      Constraint::Length(3), // Send Button
+      Constraint::Length(1), // Summary
+    ])
+    .split(area);
+
+  let top_row = Layout::default()
+    .direction(Direction::Horizontal)
+    .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+    .split(layout[0]);
+
+  let middle_row = Layout::default()
+    .direction(Direction::Horizontal)
+    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+    .split(layout[1]);
+
+  let styles = super::get_field_styles(
+    app,
+    &[
+      MergeFocus::CsvPath,
+      MergeFocus::AddressColumn,
+      MergeFocus::Rows,
+      MergeFocus::SendButton,
+    ],
+    |f| app.merge.focus == *f,
+  );
+
+  // 1. Source
+  let csv_path = Paragraph::new(app.merge.csv_path_input.as_str())
+    .block(
+      Block::default()
+        .borders(Borders::ALL)
+        .title("CSV File (header row required)"),
+    )
+    .style(styles[0]);
+  frame.render_widget(csv_path, top_row[0]);
+
+  let column = Paragraph::new(format!("< {} >", app.merge.address_header()))
+    .block(
+      Block::default()
+        .borders(Borders::ALL)
+        .title("Address Column"),
+    )
+    .style(styles[1]);
+  frame.render_widget(column, top_row[1]);
+
+  // 2. Rows
+  draw_rows(frame, app, styles[2], middle_row[0]);
+  draw_preview(frame, app, middle_row[1]);
+
+  // 3. Send
+  let send_label = if app.merge.running {
+    "[ SENDING... ]"
+  } else {
+    "[ SEND ALL ]"
+  };
+  let send_btn = Paragraph::new(send_label)
+    .alignment(ratatui::layout::Alignment::Center)
+    .block(Block::default().borders(Borders::ALL))
+    .style(if app.merge.focus == MergeFocus::SendButton {
+      Style::default()
+        .bg(Color::Green)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD)
+    } else {
+      Style::default().fg(Color::Green)
+    });
+  frame.render_widget(send_btn, layout[2]);
+
+  let (done, failed, remaining) = app.merge.tally();
+  let summary = Paragraph::new(format!(
+    "Rows: {} | Delivered: {} | Failed: {} | Remaining: {}  -  [Ctrl+s] Schedule all",
+    app.merge.row_count(),
+    done,
+    failed,
+    remaining
+  ))
+  .style(Style::default().fg(Color::DarkGray))
+  .alignment(ratatui::layout::Alignment::Center);
+  frame.render_widget(summary, layout[3]);
+}
+
+fn draw_rows(frame: &mut Frame, app: &App, style: Style, area: Rect) {
+  let rows: Vec<Row> = (0..app.merge.row_count())
+    .map(|i| {
+      let address = app
+        .merge
+        .data
+        .as_ref()
+        .map(|d| d.value(i, app.merge.address_column))
+        .unwrap_or_default();
+      let (label, color) = match app.merge.row_status.get(i) {
+        Some(MergeRowStatus::InFlight) => ("Sending".to_string(), Color::Yellow),
+        Some(MergeRowStatus::Retrying(e)) => (format!("Retrying: {}", e), Color::Yellow),
+        Some(MergeRowStatus::Sent) => ("Sent".to_string(), Color::Green),
+        Some(MergeRowStatus::Scheduled) => ("Scheduled".to_string(), Color::Cyan),
+        Some(MergeRowStatus::Failed(e)) => (format!("Failed: {}", e), Color::Red),
+        _ => ("Pending".to_string(), Color::Gray),
+      };
+
+      Row::new(vec![
+        Cell::from((i + 1).to_string()),
+        Cell::from(address.to_string()),
+        Cell::from(label).style(Style::default().fg(color)),
+      ])
+    })
+    .collect();
+
+  let table = Table::new(
+    rows,
+    [
+      Constraint::Length(5),
+      Constraint::Percentage(45),
+      Constraint::Percentage(55),
+    ],
+  )
+  .header(
+    Row::new(vec!["#", "Address", "Status"]).style(
+      Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD),
+    ),
+  )
+  .block(
+    Block::default()
+      .borders(Borders::ALL)
+      .title(" Rows ")
+      .border_style(style),
+  )
+  .row_highlight_style(
+    Style::default()
+      .bg(Color::DarkGray)
+      .add_modifier(Modifier::BOLD),
+  );
+
+  let mut state = app.merge.list_state.clone();
+  frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_preview(frame: &mut Frame, app: &App, area: Rect) {
+  let block = Block::default().borders(Borders::ALL).title(" Preview ");
+
+  let draft = app
+    .merge
+    .list_state
+    .selected()
+    .and_then(|row| app.merge.personalise(&app.compose.draft, row));
+
+  let Some(draft) = draft else {
+    let hint = Paragraph::new("Load a CSV file to preview each personalised message.")
+      .style(Style::default().fg(Color::DarkGray))
+      .block(block);
+    frame.render_widget(hint, area);
+    return;
+  };
+
+  let compiled = compiler::compile(&draft, &app.config.data, &app.contacts.consent, "");
+
+  let label = Style::default().fg(Color::Cyan);
+  let mut lines = vec![
+    Line::from(vec![
+      Span::styled("To: ", label),
+      Span::raw(draft.to.join(", ")),
+    ]),
+    Line::from(vec![
+      Span::styled("Subject: ", label),
+      Span::raw(compiled.subject.clone()),
+    ]),
+  ];
+  if let Some(data) = &app.merge.data {
+    let columns: Vec<String> = data
+      .headers
+      .iter()
+      .map(|h| format!("{{{{{}}}}}", h))
+      .collect();
+    lines.push(Line::styled(
+      format!("Columns: {}", columns.join(" ")),
+      Style::default().fg(Color::DarkGray),
+    ));
+  }
+  if !compiled.unfilled_placeholders.is_empty() {
+    lines.push(Line::styled(
+      format!("Missing: {}", compiled.unfilled_placeholders.join(", ")),
+      Style::default().fg(Color::Red),
+    ));
+  }
+  lines.push(Line::from(""));
+  lines.extend(
+    compiled
+      .plain_body
+      .lines()
+      .map(|l| Line::from(l.to_string())),
+  );
+
+  let preview = Paragraph::new(lines)
+    .wrap(Wrap { trim: false })
+    .block(block);
+  frame.render_widget(preview, area);
+}
@@ -1,8 +1,15 @@
 pub mod compose;
 pub mod config;
+pub mod contacts;
 pub mod dashboard;
+pub mod drafts;
+pub mod merge;
+pub mod outbox;
+pub mod picker;
+pub mod preview;
 pub mod schedule;
 pub mod scheduled;
+pub mod sent;
 pub mod statusbar;
 pub mod tabs;
 
@@ -32,11 +39,32 @@ pub fn draw(frame: &mut Frame, app: &App) {
     CurrentPage::Dashboard => dashboard::draw_dashboard(frame, app, chunks[1]),
     CurrentPage::Schedule => schedule::draw_schedule_page(frame, app, chunks[1]),
     CurrentPage::Scheduled => scheduled::draw_scheduled(frame, app, chunks[1]),
+    CurrentPage::Drafts => drafts::draw_drafts(frame, app, chunks[1]),
+    CurrentPage::Merge => merge::draw_merge(frame, app, chunks[1]),
+    CurrentPage::Outbox => outbox::draw_outbox(frame, app, chunks[1]),
+    CurrentPage::Sent => sent::draw_sent(frame, app, chunks[1]),
+    CurrentPage::Contacts => contacts::draw_contacts(frame, app, chunks[1]),
+  }
+
+  if let Some(file_picker) = &app.picker {
+    picker::draw_picker(frame, file_picker, chunks[1]);
   }
 
   statusbar::draw_status_bar(frame, app, chunks[2]);
 }
 
+/// Like `centered_rect`, but `height` is always a number of rows, for short form popups.
+pub(crate) fn centered_popup(percent_x: u16, height: u16, r: Rect) -> Rect {
+  let height = height.min(r.height);
+  let width = (u32::from(r.width) * u32::from(percent_x.min(100)) / 100) as u16;
+  Rect::new(
+    r.x + (r.width - width) / 2,
+    r.y + (r.height - height) / 2,
+    width,
+    height,
+  )
+}
+
 pub(crate) fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
   let v_constraints = if percent_y < 100 {
     vec![
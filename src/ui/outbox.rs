@@ -0,0 +1,160 @@
+use crate::app::App;
+use crate::enums::OutboxStatus;
+use crate::models::OutboxEntry;
+use chrono::{Local, Utc};
+use ratatui::{
+  Frame,
+  layout::{Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Line,
+  widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, Wrap},
+};
+
+pub fn draw_outbox(frame: &mut Frame, app: &App, area: Rect) {
+  let rows: Vec<Row> = app
+    .outbox
+    .entries
+    .iter()
+    .map(|entry| {
+      let (status, color) = status_label(entry);
+      Row::new(vec![
+        Cell::from(
+          entry
+            .created_at
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        ),
+        Cell::from(entry.draft.to.join(", ")),
+        Cell::from(entry.draft.subject.clone()),
+        Cell::from(status).style(Style::default().fg(color)),
+        Cell::from(entry.attempts.to_string()),
+        Cell::from(entry.last_error.clone().unwrap_or_default()),
+      ])
+    })
+    .collect();
+
+  let table = Table::new(
+    rows,
+    [
+      Constraint::Length(16),
+      Constraint::Percentage(20),
+      Constraint::Percentage(25),
+      Constraint::Length(18),
+      Constraint::Length(8),
+      Constraint::Percentage(35),
+    ],
+  )
+  .header(
+    Row::new(vec![
+      "Created",
+      "To",
+      "Subject",
+      "Status",
+      "Attempts",
+      "Last Error",
+    ])
+    .style(
+      Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD),
+    ),
+  )
+  .block(
+    Block::default()
+      .borders(Borders::ALL)
+      .title(" Outbox ")
+      .title_bottom(Line::from("[Enter] Inspect | [r] Retry Now | [x] Discard").right_aligned())
+      .border_style(Style::default().fg(Color::Yellow)),
+  )
+  .row_highlight_style(
+    Style::default()
+      .bg(Color::DarkGray)
+      .add_modifier(Modifier::BOLD),
+  );
+
+  let mut state = app.outbox.list_state.clone();
+  frame.render_stateful_widget(table, area, &mut state);
+
+  if app.outbox.inspecting
+    && let Some(entry) = app.outbox.selected()
+  {
+    draw_detail_popup(frame, entry, area);
+  }
+}
+
+fn status_label(entry: &OutboxEntry) -> (String, Color) {
+  match entry.status {
+    OutboxStatus::Queued => ("Queued".to_string(), Color::Yellow),
+    OutboxStatus::Retrying => {
+      let wait = entry
+        .next_attempt_at
+        .map(|at| (at - Utc::now()).num_seconds().max(0))
+        .unwrap_or(0);
+      (format!("Retry in {}s", wait), Color::Yellow)
+    }
+    OutboxStatus::Failed => ("Failed".to_string(), Color::Red),
+  }
+}
+
+fn draw_detail_popup(frame: &mut Frame, entry: &OutboxEntry, area: Rect) {
+  let popup_area = crate::ui::centered_rect(70, 70, area);
+  frame.render_widget(Clear, popup_area);
+
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title(format!(" Outbox: {} ", entry.draft.subject))
+    .title_bottom("[R] Retry Now | [X] Discard | [Esc] Close");
+
+  let inner = block.inner(popup_area);
+  frame.render_widget(block, popup_area);
+
+  let chunks = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([
+      Constraint::Length(1), // Recipient
+      Constraint::Length(1), // Cc / Bcc
+      Constraint::Length(1), // Status
+      Constraint::Length(2), // Last Error
+      Constraint::Min(0),    // Body
+    ])
+    .split(inner);
+
+  let (status, color) = status_label(entry);
+  frame.render_widget(
+    Paragraph::new(format!("To: {}", entry.draft.to.join(", "))),
+    chunks[0],
+  );
+  frame.render_widget(
+    Paragraph::new(format!(
+      "Cc: {} | Bcc: {}",
+      entry.draft.cc.join(", "),
+      entry.draft.bcc.join(", ")
+    )),
+    chunks[1],
+  );
+  frame.render_widget(
+    Paragraph::new(format!(
+      "Status: {} after {} attempts",
+      status, entry.attempts
+    ))
+    .style(Style::default().fg(color)),
+    chunks[2],
+  );
+  frame.render_widget(
+    Paragraph::new(format!(
+      "Last Error: {}",
+      entry.last_error.as_deref().unwrap_or("-")
+    ))
+    .wrap(Wrap { trim: true }),
+    chunks[3],
+  );
+
+  let body_block = Block::default().borders(Borders::TOP).title(" Body ");
+  frame.render_widget(
+    Paragraph::new(entry.draft.body.clone())
+      .block(body_block)
+      .wrap(Wrap { trim: false }),
+    chunks[4],
+  );
+}
@@ -0,0 +1,106 @@
+use crate::app::picker::FilePickerState;
+use crate::attachments;
+use ratatui::{
+  Frame,
+  layout::{Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Line,
+  widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+
+pub fn draw_picker(frame: &mut Frame, picker: &FilePickerState, area: Rect) {
+  let popup_area = crate::ui::centered_rect(80, 80, area);
+  frame.render_widget(Clear, popup_area);
+
+  let mut help = vec!["[Enter] Open/Choose", "[Left] Up"];
+  if picker.multi_select {
+    help.push("[Tab] Mark");
+  }
+  help.extend(["[Ctrl+t] Hidden", "[Esc] Cancel"]);
+
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title(picker.title())
+    .title_bottom(Line::from(help.join(" | ")).right_aligned());
+  let inner = block.inner(popup_area);
+  frame.render_widget(block, popup_area);
+
+  let chunks = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([
+      Constraint::Length(1), // Directory
+      Constraint::Length(3), // Filter
+      Constraint::Min(0),    // Entries
+    ])
+    .split(inner);
+
+  let mut location = picker.dir.display().to_string();
+  if picker.show_hidden {
+    location.push_str("  (showing hidden)");
+  }
+  if !picker.marked.is_empty() {
+    location.push_str(&format!("  - {} marked", picker.marked.len()));
+  }
+  frame.render_widget(
+    Paragraph::new(location).style(Style::default().fg(Color::Cyan)),
+    chunks[0],
+  );
+
+  frame.render_widget(
+    Paragraph::new(format!("{}_", picker.filter))
+      .block(Block::default().borders(Borders::ALL).title(" Filter "))
+      .style(Style::default().fg(Color::Yellow)),
+    chunks[1],
+  );
+
+  if let Some(error) = &picker.error {
+    frame.render_widget(
+      Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red)),
+      chunks[2],
+    );
+    return;
+  }
+
+  let mut rows = Vec::new();
+  if picker.has_parent() {
+    rows.push(Row::new(vec![
+      Cell::from("  "),
+      Cell::from("../").style(Style::default().fg(Color::Blue)),
+      Cell::from(""),
+    ]));
+  }
+  for entry in picker.visible() {
+    let mark = if picker.marked.contains(&entry.path) {
+      "* "
+    } else {
+      "  "
+    };
+    let (name, style) = if entry.is_dir {
+      (format!("{}/", entry.name), Style::default().fg(Color::Blue))
+    } else {
+      (entry.name.clone(), Style::default())
+    };
+    rows.push(Row::new(vec![
+      Cell::from(mark).style(Style::default().fg(Color::Green)),
+      Cell::from(name).style(style),
+      Cell::from(entry.size.map(attachments::format_size).unwrap_or_default()),
+    ]));
+  }
+
+  let table = Table::new(
+    rows,
+    [
+      Constraint::Length(2),
+      Constraint::Min(10),
+      Constraint::Length(10),
+    ],
+  )
+  .row_highlight_style(
+    Style::default()
+      .bg(Color::DarkGray)
+      .add_modifier(Modifier::BOLD),
+  );
+
+  let mut state = picker.list_state.clone();
+  frame.render_stateful_widget(table, chunks[2], &mut state);
+}
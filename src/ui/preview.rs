@@ -0,0 +1,391 @@
+use crate::config::SenderProfile;
+use crate::models::EmailDraft;
+use crate::{compiler, signature};
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Parser, Tag, TagEnd};
+use ratatui::{
+  style::{Color, Modifier, Style},
+  text::{Line, Span, Text},
+};
+use std::path::Path;
+
+/// Renders roughly what the recipient sees: the filled-in subject and body plus the signature.
+pub fn render_preview(draft: &EmailDraft, profile: &SenderProfile) -> Text<'static> {
+  let mut unfilled = Vec::new();
+  let subject = compiler::fill_placeholders(&draft.subject, &draft.variables, false, &mut unfilled);
+  let body = compiler::fill_placeholders(&draft.body, &draft.variables, true, &mut unfilled);
+
+  let mut lines = vec![
+    Line::from(vec![
+      Span::styled("Subject: ", Style::default().fg(Color::Cyan)),
+      Span::styled(subject, Style::default().add_modifier(Modifier::BOLD)),
+    ]),
+    Line::from(""),
+  ];
+  lines.extend(render_markdown(&body));
+  lines.extend(render_signature(draft, profile));
+  Text::from(lines)
+}
+
+/// The plain text variant of the draft's signature behind an accent bar, name in bold.
+fn render_signature(draft: &EmailDraft, profile: &SenderProfile) -> Vec<Line<'static>> {
+  let identity = &profile.identity;
+  let Some(signature) = signature::render(signature::chosen(draft, profile), identity) else {
+    return Vec::new();
+  };
+  let accent = parse_hex_color(&identity.footer_color).unwrap_or(Color::Cyan);
+  let bar = || Span::styled("▌ ", Style::default().fg(accent));
+
+  let mut lines = vec![Line::from("")];
+  for (i, line) in signature.plain.lines().enumerate() {
+    let style = if i == 0 {
+      Style::default().add_modifier(Modifier::BOLD)
+    } else {
+      Style::default().fg(Color::Gray)
+    };
+    lines.push(Line::from(vec![
+      bar(),
+      Span::styled(line.to_string(), style),
+    ]));
+  }
+  lines
+}
+
+fn parse_hex_color(hex: &str) -> Option<Color> {
+  let hex = hex.trim().trim_start_matches('#');
+  if hex.len() != 6 {
+    return None;
+  }
+  let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+  Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+pub fn render_markdown(markdown: &str) -> Vec<Line<'static>> {
+  let mut renderer = MarkdownRenderer::default();
+  for event in Parser::new_ext(markdown, compiler::markdown_options()) {
+    renderer.handle(event);
+  }
+  renderer.flush();
+  renderer.lines
+}
+
+#[derive(Default)]
+struct MarkdownRenderer {
+  lines: Vec<Line<'static>>,
+  current: Vec<Span<'static>>,
+  styles: Vec<Style>,
+  // One entry per nested list: the next number for ordered lists, None for bullets.
+  lists: Vec<Option<u64>>,
+  item_marker: Option<String>,
+  quote_depth: usize,
+  link_url: Option<String>,
+  image: Option<(String, String)>,
+  in_code_block: bool,
+  table: Option<Vec<Vec<String>>>,
+}
+
+impl MarkdownRenderer {
+  fn style(&self) -> Style {
+    self.styles.last().copied().unwrap_or_default()
+  }
+
+  fn push_style(&mut self, patch: Style) {
+    let style = self.style().patch(patch);
+    self.styles.push(style);
+  }
+
+  fn push_text(&mut self, text: String, style: Style) {
+    if let Some(table) = &mut self.table {
+      if let Some(cell) = table.last_mut().and_then(|row| row.last_mut()) {
+        cell.push_str(&text);
+      }
+      return;
+    }
+    if let Some((alt, _)) = &mut self.image {
+      alt.push_str(&text);
+      return;
+    }
+    self.current.push(Span::styled(text, style));
+  }
+
+  fn flush(&mut self) {
+    if self.current.is_empty() && self.item_marker.is_none() {
+      return;
+    }
+
+    let mut spans = Vec::new();
+    if self.quote_depth > 0 {
+      spans.push(Span::styled(
+        "│ ".repeat(self.quote_depth),
+        Style::default().fg(Color::DarkGray),
+      ));
+    }
+    let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+    match self.item_marker.take() {
+      Some(marker) => spans.push(Span::styled(
+        format!("{}{}", indent, marker),
+        Style::default().fg(Color::Cyan),
+      )),
+      None if !self.lists.is_empty() => spans.push(Span::raw(format!("{}  ", indent))),
+      None => {}
+    }
+    spans.append(&mut self.current);
+    self.lines.push(Line::from(spans));
+  }
+
+  fn blank_line(&mut self) {
+    if self.lines.last().is_some_and(|l| l.width() > 0) {
+      self.lines.push(Line::from(""));
+    }
+  }
+
+  fn handle(&mut self, event: Event) {
+    match event {
+      Event::Start(tag) => self.start(tag),
+      Event::End(tag) => self.end(tag),
+      Event::Text(text) if self.in_code_block => {
+        let style = Style::default().fg(Color::Yellow);
+        for line in text.lines() {
+          self
+            .current
+            .push(Span::styled(format!("  {}", line), style));
+          self.flush();
+        }
+      }
+      Event::Text(text) => self.push_text(text.to_string(), self.style()),
+      Event::Code(code) => self.push_text(
+        code.to_string(),
+        self.style().fg(Color::Yellow).bg(Color::DarkGray),
+      ),
+      Event::Html(html) | Event::InlineHtml(html) => {
+        self.push_text(html.to_string(), Style::default().fg(Color::DarkGray))
+      }
+      Event::TaskListMarker(checked) => {
+        // The checkbox stands in for a bullet but follows a number
+        let checkbox = if checked { "☑ " } else { "☐ " };
+        self.item_marker = self.item_marker.take().map(|marker| match marker.as_str() {
+          "• " => checkbox.to_string(),
+          _ => format!("{}{}", marker, checkbox),
+        });
+      }
+      Event::FootnoteReference(label) => {
+        self.push_text(format!("[{}]", label), Style::default().fg(Color::Cyan))
+      }
+      Event::SoftBreak => self.push_text(" ".to_string(), self.style()),
+      Event::HardBreak => self.flush(),
+      Event::Rule => {
+        self.flush();
+        self.lines.push(Line::styled(
+          "─".repeat(40),
+          Style::default().fg(Color::DarkGray),
+        ));
+        self.blank_line();
+      }
+      _ => {}
+    }
+  }
+
+  fn start(&mut self, tag: Tag) {
+    match tag {
+      Tag::Heading { level, .. } => {
+        self.flush();
+        let style = match level {
+          HeadingLevel::H1 => Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+          HeadingLevel::H2 => Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+          _ => Style::default().add_modifier(Modifier::BOLD),
+        };
+        self.push_style(style);
+      }
+      Tag::Emphasis => self.push_style(Style::default().add_modifier(Modifier::ITALIC)),
+      Tag::Strong => self.push_style(Style::default().add_modifier(Modifier::BOLD)),
+      Tag::Strikethrough => self.push_style(Style::default().add_modifier(Modifier::CROSSED_OUT)),
+      Tag::BlockQuote(_) => {
+        self.flush();
+        self.quote_depth += 1;
+        self.push_style(
+          Style::default()
+            .fg(Color::Gray)
+            .add_modifier(Modifier::ITALIC),
+        );
+      }
+      Tag::CodeBlock(kind) => {
+        self.flush();
+        self.in_code_block = true;
+        if let CodeBlockKind::Fenced(lang) = kind
+          && !lang.is_empty()
+        {
+          self.lines.push(Line::styled(
+            format!("  [{}]", lang),
+            Style::default().fg(Color::DarkGray),
+          ));
+        }
+      }
+      Tag::List(start) => {
+        self.flush();
+        self.lists.push(start);
+      }
+      Tag::Item => {
+        self.flush();
+        let marker = match self.lists.last_mut() {
+          Some(Some(n)) => {
+            *n += 1;
+            format!("{}. ", *n - 1)
+          }
+          _ => "• ".to_string(),
+        };
+        self.item_marker = Some(marker);
+      }
+      Tag::FootnoteDefinition(label) => {
+        self.flush();
+        self.push_text(format!("[{}]: ", label), Style::default().fg(Color::Cyan));
+      }
+      Tag::Link { dest_url, .. } => {
+        self.link_url = Some(dest_url.to_string());
+        self.push_style(
+          Style::default()
+            .fg(Color::Blue)
+            .add_modifier(Modifier::UNDERLINED),
+        );
+      }
+      Tag::Image { dest_url, .. } => {
+        self.image = Some((String::new(), dest_url.to_string()));
+      }
+      Tag::Table(_) => {
+        self.flush();
+        self.table = Some(Vec::new());
+      }
+      Tag::TableHead | Tag::TableRow => {
+        if let Some(table) = &mut self.table {
+          table.push(Vec::new());
+        }
+      }
+      Tag::TableCell => {
+        if let Some(row) = self.table.as_mut().and_then(|t| t.last_mut()) {
+          row.push(String::new());
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn end(&mut self, tag: TagEnd) {
+    match tag {
+      TagEnd::Heading(_) => {
+        self.styles.pop();
+        self.flush();
+        self.blank_line();
+      }
+      TagEnd::Paragraph => {
+        self.flush();
+        if self.lists.is_empty() {
+          self.blank_line();
+        }
+      }
+      TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+        self.styles.pop();
+      }
+      TagEnd::BlockQuote(_) => {
+        self.flush();
+        self.styles.pop();
+        self.quote_depth = self.quote_depth.saturating_sub(1);
+        self.blank_line();
+      }
+      TagEnd::CodeBlock => {
+        self.in_code_block = false;
+        self.blank_line();
+      }
+      TagEnd::List(_) => {
+        self.flush();
+        self.lists.pop();
+        if self.lists.is_empty() {
+          self.blank_line();
+        }
+      }
+      TagEnd::Item => self.flush(),
+      TagEnd::Link => {
+        self.styles.pop();
+        if let Some(url) = self.link_url.take() {
+          let shown = self
+            .current
+            .last()
+            .is_some_and(|span| span.content == url.as_str());
+          if !shown {
+            self.push_text(format!(" ({})", url), Style::default().fg(Color::DarkGray));
+          }
+        }
+      }
+      TagEnd::Image => {
+        if let Some((alt, url)) = self.image.take() {
+          let source = if url.starts_with("http://") || url.starts_with("https://") {
+            url
+          } else {
+            let name = Path::new(&url)
+              .file_name()
+              .map(|n| n.to_string_lossy().to_string())
+              .unwrap_or(url);
+            format!("inline: {}", name)
+          };
+          let label = if alt.is_empty() { "Image" } else { &alt };
+          self.push_text(
+            format!("[{} ({})]", label, source),
+            Style::default().fg(Color::Magenta),
+          );
+        }
+      }
+      TagEnd::Table => {
+        if let Some(table) = self.table.take() {
+          self.render_table(table);
+        }
+        self.blank_line();
+      }
+      _ => {}
+    }
+  }
+
+  fn render_table(&mut self, rows: Vec<Vec<String>>) {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+      .map(|c| {
+        rows
+          .iter()
+          .filter_map(|row| row.get(c))
+          .map(|cell| cell.chars().count())
+          .max()
+          .unwrap_or(0)
+      })
+      .collect();
+
+    let border = Style::default().fg(Color::DarkGray);
+    for (i, row) in rows.iter().enumerate() {
+      let mut spans = Vec::new();
+      for (c, width) in widths.iter().enumerate() {
+        if c > 0 {
+          spans.push(Span::styled(" │ ", border));
+        }
+        let cell = row.get(c).map(String::as_str).unwrap_or_default();
+        let style = if i == 0 {
+          Style::default().add_modifier(Modifier::BOLD)
+        } else {
+          Style::default()
+        };
+        spans.push(Span::styled(
+          format!("{:<width$}", cell, width = width),
+          style,
+        ));
+      }
+      self.lines.push(Line::from(spans));
+
+      if i == 0 {
+        let rule = widths
+          .iter()
+          .map(|w| "─".repeat(*w))
+          .collect::<Vec<_>>()
+          .join("─┼─");
+        self.lines.push(Line::styled(rule, border));
+      }
+    }
+  }
+}
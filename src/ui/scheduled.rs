@@ -32,7 +32,11 @@ pub fn draw_scheduled(frame: &mut Frame, app: &App, area: Rect) {
 fn draw_filters(frame: &mut Frame, app: &App, area: Rect) {
   let chunks = Layout::default()
     .direction(Direction::Horizontal)
-    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+    .constraints([
+      Constraint::Percentage(40),
+      Constraint::Percentage(30),
+      Constraint::Percentage(30),
+    ])
     .split(area);
 
   let style = |f: ScheduledFocus| {
@@ -53,6 +57,14 @@ fn draw_filters(frame: &mut Frame, app: &App, area: Rect) {
     )
     .style(style(ScheduledFocus::FilterRecipient));
 
+  let group_widget = Paragraph::new(app.scheduled.filter_group.as_str())
+    .block(
+      Block::default()
+        .borders(Borders::ALL)
+        .title(" Filter Group "),
+    )
+    .style(style(ScheduledFocus::FilterGroup));
+
   let status_str = match app.scheduled.filter_status {
     None => "ALL",
     Some(JobStatus::Pending) => "PENDING",
@@ -70,7 +82,8 @@ fn draw_filters(frame: &mut Frame, app: &App, area: Rect) {
     .style(style(ScheduledFocus::FilterStatus));
 
   frame.render_widget(recip_widget, chunks[0]);
-  frame.render_widget(status_widget, chunks[1]);
+  frame.render_widget(group_widget, chunks[1]);
+  frame.render_widget(status_widget, chunks[2]);
 }
 
 fn draw_table(frame: &mut Frame, app: &App, area: Rect) {
@@ -106,8 +119,18 @@ fn draw_table(frame: &mut Frame, app: &App, area: Rect) {
         _ => Style::default(),
       };
 
+      let group = job
+        .group
+        .split(',')
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+        .map(|g| format!("@{}", g))
+        .collect::<Vec<_>>()
+        .join(" ");
+
       Row::new(vec![
         Cell::from(to),
+        Cell::from(group),
         Cell::from(recip_time),
         Cell::from(user_time),
         Cell::from(status).style(status_style),
@@ -118,14 +141,15 @@ fn draw_table(frame: &mut Frame, app: &App, area: Rect) {
   let table = Table::new(
     rows,
     [
-      Constraint::Percentage(30),
-      Constraint::Percentage(25),
       Constraint::Percentage(25),
+      Constraint::Percentage(15),
+      Constraint::Percentage(20),
+      Constraint::Percentage(20),
       Constraint::Percentage(20),
     ],
   )
   .header(
-    Row::new(vec!["To", "Recipient Time", "Your Time", "Status"]).style(
+    Row::new(vec!["To", "Group", "Recipient Time", "Your Time", "Status"]).style(
       Style::default()
         .fg(Color::Cyan)
         .add_modifier(Modifier::BOLD),
@@ -162,16 +186,28 @@ fn draw_detail_popup(frame: &mut Frame, job: &ScheduledJob, area: Rect) {
   let chunks = Layout::default()
     .direction(Direction::Vertical)
     .constraints([
+      Constraint::Length(1), // Sender profile
       Constraint::Length(1), // Recipient
+      Constraint::Length(1), // Cc / Bcc
       Constraint::Length(1), // Time
       Constraint::Min(0),    // Body
     ])
     .split(inner);
 
-  frame.render_widget(Paragraph::new(format!("To: {}", job.recipient)), chunks[0]);
+  let profile = if job.profile.is_empty() {
+    "-"
+  } else {
+    job.profile.as_str()
+  };
+  frame.render_widget(Paragraph::new(format!("From: {}", profile)), chunks[0]);
+  frame.render_widget(Paragraph::new(format!("To: {}", job.recipient)), chunks[1]);
+  frame.render_widget(
+    Paragraph::new(format!("Cc: {} | Bcc: {}", job.cc, job.bcc)),
+    chunks[2],
+  );
   frame.render_widget(
     Paragraph::new(format!("Scheduled: {} (UTC)", job.scheduled_at)),
-    chunks[1],
+    chunks[3],
   );
 
   let body_block = Block::default().borders(Borders::TOP).title(" Body ");
@@ -179,6 +215,6 @@ fn draw_detail_popup(frame: &mut Frame, job: &ScheduledJob, area: Rect) {
     Paragraph::new(job.body.clone())
       .block(body_block)
       .wrap(Wrap { trim: false }),
-    chunks[2],
+    chunks[4],
   );
 }
@@ -0,0 +1,129 @@
+use crate::app::App;
+use crate::models::SentRecord;
+use chrono::Local;
+use ratatui::{
+  Frame,
+  layout::{Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Line,
+  widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, Wrap},
+};
+
+pub fn draw_sent(frame: &mut Frame, app: &App, area: Rect) {
+  let rows: Vec<Row> = app
+    .sent
+    .records
+    .iter()
+    .map(|record| {
+      Row::new(vec![
+        Cell::from(
+          record
+            .sent_at
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        ),
+        Cell::from(record.to.join(", ")),
+        Cell::from(record.subject.clone()),
+        Cell::from(record.tracking_id.clone()),
+        Cell::from(record.smtp_response.clone()),
+      ])
+    })
+    .collect();
+
+  let table = Table::new(
+    rows,
+    [
+      Constraint::Length(16),
+      Constraint::Percentage(25),
+      Constraint::Percentage(30),
+      Constraint::Percentage(20),
+      Constraint::Percentage(25),
+    ],
+  )
+  .header(
+    Row::new(vec![
+      "Sent",
+      "To",
+      "Subject",
+      "Tracking ID",
+      "SMTP Response",
+    ])
+    .style(
+      Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD),
+    ),
+  )
+  .block(
+    Block::default()
+      .borders(Borders::ALL)
+      .title(" Sent ")
+      .title_bottom(Line::from("[Enter] View Message | [o] Open as New Draft").right_aligned())
+      .border_style(Style::default().fg(Color::Yellow)),
+  )
+  .row_highlight_style(
+    Style::default()
+      .bg(Color::DarkGray)
+      .add_modifier(Modifier::BOLD),
+  );
+
+  let mut state = app.sent.list_state.clone();
+  frame.render_stateful_widget(table, area, &mut state);
+
+  if let Some(eml) = &app.sent.inspected_eml
+    && let Some(record) = app.sent.selected()
+  {
+    draw_message_popup(frame, record, eml, area);
+  }
+}
+
+fn draw_message_popup(frame: &mut Frame, record: &SentRecord, eml: &str, area: Rect) {
+  let popup_area = crate::ui::centered_rect(80, 80, area);
+  frame.render_widget(Clear, popup_area);
+
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title(format!(" Sent: {} ", record.subject))
+    .title_bottom("[O] Open as New Draft | [Esc] Close");
+
+  let inner = block.inner(popup_area);
+  frame.render_widget(block, popup_area);
+
+  let chunks = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([
+      Constraint::Length(1), // Recipient
+      Constraint::Length(1), // Cc / Bcc
+      Constraint::Length(1), // Response
+      Constraint::Min(0),    // Raw Message
+    ])
+    .split(inner);
+
+  frame.render_widget(
+    Paragraph::new(format!("To: {}", record.to.join(", "))),
+    chunks[0],
+  );
+  frame.render_widget(
+    Paragraph::new(format!(
+      "Cc: {} | Bcc: {}",
+      record.cc.join(", "),
+      record.bcc.join(", ")
+    )),
+    chunks[1],
+  );
+  frame.render_widget(
+    Paragraph::new(format!("SMTP: {}", record.smtp_response)),
+    chunks[2],
+  );
+
+  let raw_block = Block::default()
+    .borders(Borders::TOP)
+    .title(format!(" {}.eml ", record.id));
+  frame.render_widget(
+    Paragraph::new(eml)
+      .block(raw_block)
+      .wrap(Wrap { trim: false }),
+    chunks[3],
+  );
+}
@@ -2,7 +2,7 @@ use crate::app::App;
 use crate::enums::{ComposeField, CurrentPage, InputMode, Notification};
 use ratatui::{
   Frame,
-  layout::Rect,
+  layout::{Constraint, Direction, Layout, Rect},
   style::{Color, Modifier, Style},
   widgets::Paragraph,
 };
@@ -17,13 +17,15 @@ pub fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
   } else {
     match app.input_mode {
       InputMode::Normal => {
-        let base_help = " [Tab] Next | [Enter] Edit | [Ctrl+Enter] Send | [1/2/3] Nav | [q] Quit";
-        let extra_help = if app.current_page == CurrentPage::Compose
-          && app.compose.field == ComposeField::Attachments
-        {
-          " | [Ctrl+o] Browse Files | [Ctrl+x] Clear"
-        } else {
+        let base_help = " [Tab] Next | [Enter] Edit | [Ctrl+Enter] Send | [1-9] Nav | [q] Quit";
+        let extra_help = if app.current_page != CurrentPage::Compose {
           ""
+        } else if app.compose.field == ComposeField::Attachments {
+          " | [Enter] Add Path | [Ctrl+o] Browse | [x] Remove | [Shift+Up/Down] Move | [Ctrl+x] Clear"
+        } else if app.compose.field == ComposeField::Body {
+          " | [Ctrl+o] Insert Image | [Ctrl+e] Export .eml"
+        } else {
+          " | [Ctrl+e] Export .eml"
         };
         (format!("{}{}", base_help, extra_help), Color::DarkGray)
       }
@@ -35,5 +37,42 @@ pub fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
   };
 
   let status = Paragraph::new(text).style(Style::default().fg(color).add_modifier(Modifier::BOLD));
-  frame.render_widget(status, area);
+
+  let stats = app.queue_stats;
+  if stats.is_idle() {
+    frame.render_widget(status, area);
+    return;
+  }
+
+  let (queue_text, queue_color) = if stats.paused {
+    (
+      format!(
+        " PAUSED {} queued | {} sending | {} sent | {} failed [Ctrl+p] Resume ",
+        stats.queued, stats.in_flight, stats.sent, stats.failed
+      ),
+      Color::Yellow,
+    )
+  } else {
+    (
+      format!(
+        " Queue: {} queued | {} sending | {} sent | {} failed [Ctrl+p] Pause ",
+        stats.queued, stats.in_flight, stats.sent, stats.failed
+      ),
+      Color::Cyan,
+    )
+  };
+
+  let chunks = Layout::default()
+    .direction(Direction::Horizontal)
+    .constraints([
+      Constraint::Min(0),
+      Constraint::Length(queue_text.chars().count() as u16),
+    ])
+    .split(area);
+
+  frame.render_widget(status, chunks[0]);
+  frame.render_widget(
+    Paragraph::new(queue_text).style(Style::default().fg(queue_color)),
+    chunks[1],
+  );
 }
@@ -13,6 +13,11 @@ pub fn draw_tabs(frame: &mut Frame, app: &App, area: Rect) {
     " [2] Config ",
     " [3] Dashboard ",
     " [4] Scheduled ",
+    " [5] Drafts ",
+    " [6] Merge ",
+    " [7] Outbox ",
+    " [8] Sent ",
+    " [9] Contacts ",
   ];
   let tabs = Tabs::new(titles)
     .block(Block::default().borders(Borders::ALL).title(" Shiryoku "))
@@ -22,6 +27,11 @@ pub fn draw_tabs(frame: &mut Frame, app: &App, area: Rect) {
       CurrentPage::Dashboard => 2,
       CurrentPage::Schedule => 0,
       CurrentPage::Scheduled => 3,
+      CurrentPage::Drafts => 4,
+      CurrentPage::Merge => 5,
+      CurrentPage::Outbox => 6,
+      CurrentPage::Sent => 7,
+      CurrentPage::Contacts => 8,
     })
     .highlight_style(
       Style::default()